@@ -0,0 +1,148 @@
+// benches/pipeline.rs
+// Benchmarks de cada etapa del pipeline con tamaños parecidos a los de un frame
+// real (una ventana de 800x600 con el planeta ocupando ~100k píxeles):
+//
+//     cargo bench --bench pipeline
+//     cargo bench --bench pipeline -- planet_color    (solo un grupo)
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+use ship::debug::DebugView;
+use ship::light::Light;
+use ship::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use ship::mesh::{generate_icosphere, generate_uv_sphere};
+use ship::planets::PlanetRegistry;
+use ship::noise::{fbm, fractal_noise, noise, worley};
+use ship::shaders::{vertex_shader, VertexTransform};
+use ship::tiles::Tile;
+use ship::triangle::{rasterize, RenderMode};
+use ship::uniforms::GlobalUniforms;
+use ship::vertex::Vertex;
+
+const WIDTH: f32 = 800.0;
+const HEIGHT: f32 = 600.0;
+
+fn globals() -> GlobalUniforms {
+    GlobalUniforms {
+        view_matrix: create_view_matrix(Vector3::new(0.0, 0.0, 2.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0)),
+        projection_matrix: create_projection_matrix(PI / 3.0, WIDTH / HEIGHT, 0.1, 100.0),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, WIDTH, HEIGHT),
+        time: 1.0,
+        previous_time: 1.0,
+        camera_position: Vector3::new(0.0, 0.0, 2.0),
+        light: Light::new(Vector3::new(5.0, 3.0, 5.0)),
+        debug_view: DebugView::Shaded,
+        render_mode: RenderMode::Solid,
+        shading: Default::default(),
+        previous_view_projection: None,
+        color_space: Default::default(),
+    }
+}
+
+/// `count` points spread over the unit sphere (golden spiral), like the
+/// positions the fragment stage sees
+fn sphere_points(count: usize) -> Vec<Vector3> {
+    let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let radius = (1.0 - y * y).sqrt();
+            let angle = golden_angle * i as f32;
+            Vector3::new(radius * angle.cos(), y, radius * angle.sin()) * 0.5
+        })
+        .collect()
+}
+
+fn vertex_stage(c: &mut Criterion) {
+    let globals = globals();
+    let mut group = c.benchmark_group("vertex_shader");
+    for (name, vertices) in [("ico3", generate_icosphere(3)), ("uv96x48", generate_uv_sphere(96, 48))] {
+        group.throughput(Throughput::Elements(vertices.len() as u64));
+        let mut transformed: Vec<Vertex> = Vec::with_capacity(vertices.len());
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                let transform = VertexTransform::new(&globals, Matrix::identity(), Matrix::identity());
+                transformed.clear();
+                transformed.extend(vertices.iter().map(|vertex| vertex_shader(vertex, &transform)));
+                black_box(&transformed);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn screen_vertex(x: f32, y: f32) -> Vertex {
+    let mut vertex = Vertex::new(Vector3::new(x, y, 0.5), Vector3::new(0.0, 0.0, 1.0), Vector2::zero());
+    vertex.set_transformed(Vector3::new(x, y, 0.5), Vector3::new(0.0, 0.0, 1.0));
+    vertex
+}
+
+fn rasterization(c: &mut Criterion) {
+    let screen = Tile { x0: 0, y0: 0, x1: WIDTH as i32, y1: HEIGHT as i32 };
+
+    let mut group = c.benchmark_group("rasterize");
+    // Lado del triángulo en píxeles: los de una icosfera lejana, cercana y uno enorme
+    for size in [4.0, 32.0, 256.0] {
+        let tri = [screen_vertex(10.0, 10.0), screen_vertex(10.0 + size, 10.0), screen_vertex(10.0, 10.0 + size)];
+        let mut fragments = 0;
+        rasterize(&tri, RenderMode::Solid, false, screen, &mut |_| fragments += 1);
+        group.throughput(Throughput::Elements(fragments));
+
+        for mode in [RenderMode::Solid, RenderMode::Wireframe] {
+            group.bench_function(BenchmarkId::new(format!("{:?}", mode), size), |b| {
+                b.iter(|| {
+                    let mut depth = 0.0;
+                    rasterize(black_box(&tri), mode, false, screen, &mut |fragment| depth += fragment.depth);
+                    black_box(depth)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn noise_functions(c: &mut Criterion) {
+    let points: Vec<Vector3> = sphere_points(10_000).into_iter().map(|point| point * 8.0).collect();
+
+    let mut group = c.benchmark_group("noise");
+    group.throughput(Throughput::Elements(points.len() as u64));
+    group.bench_function("noise", |b| b.iter(|| points.iter().map(noise).sum::<f32>()));
+    for octaves in [3, 6] {
+        group.bench_function(BenchmarkId::new("fractal_noise", octaves), |b| {
+            b.iter(|| points.iter().map(|point| fractal_noise(point, octaves)).sum::<f32>())
+        });
+        group.bench_function(BenchmarkId::new("fbm", octaves), |b| {
+            b.iter(|| points.iter().map(|point| fbm(point, octaves)).sum::<f32>())
+        });
+    }
+    group.bench_function("worley", |b| b.iter(|| points.iter().map(|point| worley(point).0).sum::<f32>()));
+    group.finish();
+}
+
+fn planet_colors(c: &mut Criterion) {
+    let registry = PlanetRegistry::with_builtins();
+    let names: Vec<String> = registry.names().map(String::from).collect();
+
+    let mut group = c.benchmark_group("planet_color");
+    for count in [10_000, 100_000] {
+        let points = sphere_points(count);
+        group.throughput(Throughput::Elements(count as u64));
+        for name in &names {
+            let planet = registry.get(registry.find(name).unwrap()).unwrap();
+            group.bench_function(BenchmarkId::new(name, count), |b| {
+                b.iter(|| {
+                    let mut sum = Vector3::zero();
+                    for point in &points {
+                        sum += (planet.color)(point, 1.0, &planet.params);
+                    }
+                    black_box(sum)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, vertex_stage, rasterization, noise_functions, planet_colors);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+// benches/transform.rs
+// Compara multiply_matrix_vector4 escalar contra la versión SIMD (wide) al
+// transformar arreglos de vértices del tamaño de las mallas del lab.
+//
+//     cargo bench --bench transform
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use raylib::prelude::*;
+
+use ship::matrix::{create_model_matrix, create_projection_matrix, create_view_matrix, multiply_matrix_vector4, multiply_matrix_vectors4};
+
+fn vectors(count: usize) -> Vec<Vector4> {
+    (0..count)
+        .map(|i| {
+            let angle = i as f32 * 0.37;
+            Vector4::new(angle.cos() * 0.5, (i as f32 * 0.11).sin() * 0.5, angle.sin() * 0.5, 1.0)
+        })
+        .collect()
+}
+
+fn transform(c: &mut Criterion) {
+    let model_view_projection = create_model_matrix(Vector3::new(0.2, 0.0, 0.0), 1.0, Vector3::new(0.1, 0.4, 0.0))
+        * create_view_matrix(Vector3::new(0.0, 0.0, 5.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0))
+        * create_projection_matrix(std::f32::consts::PI / 3.0, 4.0 / 3.0, 0.1, 100.0);
+
+    let mut group = c.benchmark_group("matrix_vector4");
+    // Icosfera 3 (3840 vértices) y una UV de 96x48
+    for count in [3840, 27648] {
+        let input = vectors(count);
+        let mut output = Vec::with_capacity(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("scalar", count), &input, |b, input| {
+            b.iter(|| {
+                output.clear();
+                output.extend(input.iter().map(|vector| multiply_matrix_vector4(black_box(&model_view_projection), vector)));
+                black_box(&output);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("simd", count), &input, |b, input| {
+            b.iter(|| {
+                output.clear();
+                multiply_matrix_vectors4(black_box(&model_view_projection), input, &mut output);
+                black_box(&output);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, transform);
+criterion_main!(benches);
@@ -0,0 +1,429 @@
+// app.rs
+// La aplicación interactiva: ventana de raylib con panel de parámetros, HUD, picking,
+// grabación y recorridos alrededor del Renderer
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::path::Path;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::{Camera, CameraFocus};
+use crate::cli::{create_post_process, split_planet, Options};
+use crate::comet::Comet;
+use crate::config::ConfigWatcher;
+use crate::demo::{Demo, DemoFrame};
+use crate::depth::DepthMode;
+use crate::display::WindowPresenter;
+use crate::events::EventDetector;
+use crate::gizmos::Gizmos;
+use crate::hud;
+use crate::light::Light;
+use crate::mesh::SceneMeshes;
+use crate::picking::{describe, pick, Body, Ray};
+use crate::planets::{PlanetCrossfade, PlanetRegistry, PlanetType};
+use crate::recorder::{RecordFormat, Recorder};
+use crate::shaders::VertexEffect;
+use crate::renderer::{frame_projection, FrameSettings, Renderer, Scene, Viewport, FAR_PLANE, FOV_Y, NEAR_PLANE};
+use crate::time::TimeController;
+use crate::triangle::RenderMode;
+use crate::ui::Ui;
+
+/// Keys that select the planets, in registry order
+pub const PLANET_KEYS: [KeyboardKey; 9] = [
+    KeyboardKey::KEY_ONE,
+    KeyboardKey::KEY_TWO,
+    KeyboardKey::KEY_THREE,
+    KeyboardKey::KEY_FOUR,
+    KeyboardKey::KEY_FIVE,
+    KeyboardKey::KEY_SIX,
+    KeyboardKey::KEY_SEVEN,
+    KeyboardKey::KEY_EIGHT,
+    KeyboardKey::KEY_NINE,
+];
+
+/// Overlay with live controls for the active planet's parameters and the light direction
+fn draw_parameter_panel(d: &mut RaylibDrawHandle, ui: &mut Ui, planets: &mut [PlanetType], planet_type: &mut usize, light: &mut Light) {
+    ui.begin(d, "Parametros (TAB oculta)");
+
+    let names: Vec<&str> = planets.iter().map(|planet| planet.name.as_str()).collect();
+    ui.selector(d, "Planeta", &names, planet_type);
+
+    let params = &mut planets[*planet_type].params;
+    ui.slider(d, "Rotacion", &mut params.rotation_speed, -2.0, 2.0);
+    ui.slider(d, "Inclinacion eje", &mut params.axial_tilt, 0.0, PI);
+    ui.slider(d, "Precesion", &mut params.precession_speed, -1.0, 1.0);
+    ui.slider(d, "Escala ruido", &mut params.noise_scale, 0.1, 4.0);
+    ui.slider(d, "Frec. bandas", &mut params.band_frequency, 0.5, 20.0);
+    ui.checkbox(d, "Anillos", &mut params.rings);
+    ui.checkbox(d, "Asteroides", &mut params.asteroids);
+    ui.checkbox(d, "Atmosfera", &mut params.atmosphere);
+    ui.checkbox(d, "Auroras", &mut params.aurora);
+    let effect_names: Vec<&str> = VertexEffect::ALL.iter().map(|effect| effect.name()).collect();
+    let mut effect_index = VertexEffect::ALL.iter().position(|effect| *effect == params.effect).unwrap_or(0);
+    if ui.selector(d, "Efecto", &effect_names, &mut effect_index) {
+        params.effect = VertexEffect::ALL[effect_index];
+    }
+
+    // Dirección de la luz en coordenadas esféricas, manteniendo su distancia al origen
+    let distance = light.position.length().max(0.001);
+    let mut azimuth = light.position.z.atan2(light.position.x).to_degrees();
+    let mut elevation = (light.position.y / distance).clamp(-1.0, 1.0).asin().to_degrees();
+    let azimuth_changed = ui.slider(d, "Luz azimut", &mut azimuth, -180.0, 180.0);
+    let elevation_changed = ui.slider(d, "Luz elevacion", &mut elevation, -89.0, 89.0);
+    if azimuth_changed || elevation_changed {
+        let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+        light.position = Vector3::new(
+            distance * elevation.cos() * azimuth.cos(),
+            distance * elevation.sin(),
+            distance * elevation.cos() * azimuth.sin(),
+        );
+    }
+
+    ui.end();
+}
+
+/// Opens the window and runs the interactive loop until it is closed; `planets`
+/// are reloaded from `registry` whenever the config file changes. `planet_type` is
+/// the planet shown first and `right_planet` the one the right half of a split
+/// screen shows instead (`cli::split_planet`).
+pub fn run(options: &Options, mut camera: Camera, meshes: &SceneMeshes, registry: &PlanetRegistry, mut planets: Vec<PlanetType>, mut light: Light, (mut planet_type, mut right_planet): (usize, Option<usize>)) {
+    // Presets de planetas y luz (planets.toml), recargados al guardar el archivo
+    let mut config_watcher = ConfigWatcher::new(Path::new(&options.config_path));
+
+    let mut builder = raylib::init();
+    builder
+        .size(options.width, options.height)
+        .title("Planet Shaders with Rings and Moons")
+        .resizable()
+        .log_level(TraceLogLevel::LOG_WARNING);
+    if options.fullscreen {
+        builder.fullscreen();
+    }
+    if options.vsync {
+        builder.vsync();
+    }
+    let (mut window, raylib_thread) = builder.build();
+    window.set_target_fps(options.target_fps);
+
+    let mut renderer = Renderer::new(options.width, options.height, options.ssaa, options.display);
+    renderer.post_process = create_post_process(options);
+
+    // Reloj de las animaciones (Espacio pausa, [ ] velocidad, I invierte, , . avanzan un frame)
+    let mut clock = TimeController::default();
+
+    // Recorrido: --record-demo guarda cada frame al cerrar, --replay lo reproduce
+    let mut demo_recording = options.record_demo.as_ref().map(|_| Demo::default());
+    let replay = options.replay.as_ref().map(|path| {
+        Demo::load(Path::new(path)).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        })
+    });
+    let mut replay_frame = 0;
+
+    // Cuerpo elegido con clic izquierdo (clic en el vacío lo deselecciona)
+    let mut selected: Option<Body> = None;
+    // Animación de la cámara hacia el cuerpo seleccionado (se cancela al desplazar la cámara)
+    let mut focus: Option<CameraFocus> = None;
+
+    // Fundido entre planetas: cuando cambia planet_type (teclas, panel o reproducción)
+    // el anterior se sigue sombreando medio segundo y se desvanece
+    let mut shown_planet = planet_type;
+    let mut crossfade: Option<PlanetCrossfade> = None;
+
+    // Eclipses y tránsitos de la luna (L activa la cámara lenta mientras duran)
+    let mut events = EventDetector::default();
+    let mut slow_on_events = false;
+
+    // Vista * proyección del frame anterior, para los vectores de movimiento del motion blur
+    let mut last_view_projection: Option<Matrix> = None;
+
+    // Grabación de frames (R inicia/detiene, G cambia entre PNG y GIF)
+    let mut recorder = Recorder::new(options.record_format, "recordings");
+    recorder.target_fps = options.record_fps;
+    recorder.frame_skip = options.record_skip;
+
+    // Panel de parámetros en vivo
+    let mut ui = Ui::new(10, 10);
+    let mut show_hud = true;
+    let mut debug_view = options.debug_view;
+    let mut render_mode = options.render_mode;
+    let mut shading = options.shading;
+    let mut projection = options.projection;
+    let mut depth_mode = options.depth_mode;
+    let mut depth_overlay = options.depth_overlay;
+    let mut gizmos = options.gizmos;
+    let mut lens_flare = options.lens_flare;
+    let mut msaa = options.msaa;
+    let mut comet = Comet::new();
+    let mut belt = AsteroidBelt::new(7);
+
+    while !window.window_should_close() {
+        let real_dt = window.get_frame_time();
+
+        // Ventana redimensionada: framebuffers nuevos del tamaño de la ventana. La proyección
+        // (con la nueva relación de aspecto) y el viewport se arman en cada frame con el
+        // tamaño del framebuffer, así que no hay que tocar nada más
+        let (window_width, window_height) = (window.get_screen_width(), window.get_screen_height());
+        if window.is_window_resized() && renderer.resize(window_width, window_height) {
+            // Todos los cuadros de un GIF miden lo mismo: la grabación se cierra al cambiar el tamaño
+            if recorder.is_recording() && recorder.format == RecordFormat::Gif {
+                let dir = recorder.stop();
+                println!("Recording stopped (window resized), frames written to {}", dir.display());
+            }
+        }
+
+        if let Some(config) = config_watcher.poll(real_dt) {
+            match config.planet_types(registry) {
+                Ok(reloaded) => {
+                    planets = reloaded;
+                    planet_type = planet_type.min(planets.len() - 1);
+                    light = config.light();
+                    // Los nombres pueden haber cambiado: se vuelve a buscar el de la mitad derecha
+                    right_planet = split_planet(options, &planets).unwrap_or_else(|message| {
+                        eprintln!("Config reload: {}", message);
+                        None
+                    });
+                }
+                Err(message) => eprintln!("Config reload failed: {}", message),
+            }
+        }
+
+        // Handle keyboard input for planet switching (1-9 select a planet preset)
+        for (index, key) in PLANET_KEYS.iter().enumerate() {
+            if window.is_key_pressed(*key) && index < planets.len() {
+                planet_type = index;
+            }
+        }
+
+        if window.is_key_pressed(KeyboardKey::KEY_TAB) {
+            ui.visible = !ui.visible;
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_H) {
+            show_hud = !show_hud;
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_M) {
+            render_mode = match render_mode {
+                RenderMode::Solid => RenderMode::Wireframe,
+                RenderMode::Wireframe => RenderMode::Solid,
+            };
+            println!("Render mode: {:?}", render_mode);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_U) {
+            shading = shading.next();
+            println!("Shading: {:?}", shading);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_P) {
+            projection = projection.toggle();
+            println!("Projection: {:?}", projection);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_Z) {
+            depth_mode = depth_mode.toggle();
+            println!("Depth: {:?}", depth_mode);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_J) {
+            depth_overlay = !depth_overlay;
+            println!("depth_overlay: {}", if depth_overlay { "on" } else { "off" });
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_V) {
+            debug_view = debug_view.next();
+            println!("Debug view: {:?}", debug_view);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_T) {
+            let mut display = renderer.framebuffer.display;
+            display.tone_mapping = display.tone_mapping.next();
+            renderer.set_display(display);
+            println!("Tone mapping: {:?}", display.tone_mapping);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_C) {
+            let mut display = renderer.framebuffer.display;
+            display.color_space = display.color_space.toggle();
+            renderer.set_display(display);
+            println!("Color space: {:?}", display.color_space);
+        }
+        for (key, effect) in [(KeyboardKey::KEY_F, "fxaa"), (KeyboardKey::KEY_B, "bloom"), (KeyboardKey::KEY_O, "dof"), (KeyboardKey::KEY_N, "motion_blur")] {
+            if window.is_key_pressed(key) && let Some(enabled) = renderer.post_process.toggle(effect) {
+                println!("{}: {}", effect, if enabled { "on" } else { "off" });
+            }
+        }
+
+        if window.is_key_pressed(KeyboardKey::KEY_X) {
+            msaa = !msaa;
+            println!("msaa: {}", if msaa { "on" } else { "off" });
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_K) {
+            lens_flare = !lens_flare;
+            println!("lens_flare: {}", if lens_flare { "on" } else { "off" });
+        }
+
+        if window.is_key_pressed(KeyboardKey::KEY_G) && !recorder.is_recording() {
+            recorder.format = match recorder.format {
+                RecordFormat::Gif => RecordFormat::PngSequence,
+                RecordFormat::PngSequence => RecordFormat::Gif,
+            };
+            println!("Recording format: {:?}", recorder.format);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_R) && let Err(e) = recorder.toggle() {
+            eprintln!("Recorder error: {}", e);
+        }
+
+        if window.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            clock.toggle_pause();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_I) {
+            clock.toggle_reverse();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_RIGHT_BRACKET) {
+            clock.faster();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_LEFT_BRACKET) {
+            clock.slower();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_ZERO) {
+            clock.reset_speed();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_PERIOD) {
+            clock.step(1);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_COMMA) {
+            clock.step(-1);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_L) {
+            slow_on_events = !slow_on_events;
+            println!("Slow motion on events: {}", if slow_on_events { "on" } else { "off" });
+        }
+        let mut dt = clock.advance(real_dt);
+
+        // Durante la reproducción la cámara, el reloj y el planeta salen del archivo
+        match replay.as_ref().and_then(|demo| demo.frames.get(replay_frame)) {
+            Some(recorded) => {
+                camera.set_pose(recorded.eye, recorded.target, recorded.up);
+                clock.time = recorded.time;
+                dt = recorded.dt;
+                planet_type = recorded.planet.min(planets.len() - 1);
+                replay_frame += 1;
+                if replay.as_ref().is_some_and(|demo| replay_frame == demo.frames.len()) {
+                    println!("Replay finished");
+                }
+            }
+            None => {
+                let target = camera.target;
+                camera.process_input(&window);
+                if camera.target != target {
+                    focus = None;
+                }
+            }
+        }
+        let time = clock.time;
+        // La luz animada de este instante; el panel edita la de base, `light`
+        let scene_light = light.at(time);
+        // El planeta activo puede tener menos lunas que el de antes (otro preset o una recarga)
+        let moons = &planets[planet_type].params.moons;
+        if selected.is_some_and(|body| !body.exists(moons)) {
+            selected = None;
+            focus = None;
+        }
+
+        // Picking: rayo desde la cámara por el cursor; los clics sobre el panel son del panel
+        let mouse = window.get_mouse_position();
+        if window.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) && !ui.contains(mouse) {
+            let (mut width, height) = (renderer.framebuffer.width as f32, renderer.framebuffer.height as f32);
+            // En pantalla dividida las dos mitades muestran la misma cámara: el clic se
+            // traslada a la mitad izquierda
+            let mut mouse_x = mouse.x;
+            if options.split() {
+                width *= 0.5;
+                mouse_x %= width;
+            }
+            // El rayo se desproyecta entre NDC -1 y 1: con la profundidad estándar, sea cual sea la del frame
+            let projection_matrix = projection.matrix(&camera, FOV_Y, width / height, NEAR_PLANE, FAR_PLANE, DepthMode::Standard);
+            let ray = Ray::through_pixel(mouse_x, mouse.y, width, height, &camera.get_view_matrix(), &projection_matrix);
+            selected = pick(&ray, time, &scene_light, moons);
+            focus = selected.map(|_| CameraFocus::start(&camera));
+        }
+        if let (Some(focus), Some(body)) = (focus.as_mut(), selected) {
+            let (center, radius) = body.sphere(time, &scene_light, moons);
+            focus.update(&mut camera, center, radius, real_dt, FOV_Y);
+        }
+
+        // Órbita y eje: del cuerpo seleccionado o, sin selección, de todos a la vez
+        if window.is_key_pressed(KeyboardKey::KEY_Y) {
+            match selected {
+                Some(body) => {
+                    gizmos.toggle(body);
+                    println!("Gizmos ({}): {}", body.name(moons), if gizmos.shows(body) { "on" } else { "off" });
+                }
+                None => {
+                    gizmos = if gizmos.any() { Gizmos::NONE } else { Gizmos::ALL };
+                    println!("Gizmos: {}", if gizmos.any() { "on" } else { "off" });
+                }
+            }
+        }
+
+        for event in events.update(time, &scene_light, camera.eye, moons) {
+            println!("{}", event.description());
+        }
+        clock.slow_motion = slow_on_events && !events.active().is_empty();
+
+        if let Some(demo) = demo_recording.as_mut() {
+            demo.frames.push(DemoFrame::new(time, dt, &camera, planet_type));
+        }
+
+        // Las partículas no se pueden desintegrar hacia atrás: la cola siempre envejece hacia adelante
+        comet.update(time, dt.abs(), &scene_light);
+        belt.update(time);
+
+        // Las velocidades solo se calculan con el motion blur activo: sin él serían trabajo perdido
+        let previous_view_projection = last_view_projection.filter(|_| renderer.post_process.is_enabled("motion_blur"));
+        let frame = FrameSettings { time, dt, debug_view, render_mode, shading, projection, depth_mode, depth_overlay, selected, gizmos, fov_y: FOV_Y, previous_view_projection, lens_flare, msaa, viewport: if options.split() { Viewport::LEFT_HALF } else { Viewport::FULL } };
+        if planet_type != shown_planet {
+            crossfade = Some(PlanetCrossfade::start(shown_planet));
+            shown_planet = planet_type;
+        }
+        // Una recarga de planets.toml puede dejar el planeta anterior fuera de la lista
+        crossfade = crossfade.filter(|fade| !fade.finished() && fade.from < planets.len());
+        let fade_from = crossfade.map(|fade| (&planets[fade.from], fade.remaining()));
+        let scene = Scene { meshes, planet: &planets[planet_type], comet: &comet, belt: &belt, fade_from };
+        let stats = if options.split() {
+            // Mitad derecha: otro sombreado y/o otro planeta (sin fundido si es otro)
+            let right_frame = FrameSettings { shading: options.split_shading.unwrap_or(shading), ..frame };
+            let right_scene = Scene {
+                planet: right_planet.map_or(scene.planet, |index| &planets[index]),
+                fade_from: scene.fade_from.filter(|_| right_planet.is_none()),
+                ..scene
+            };
+            // El HUD muestra los contadores de la mitad izquierda
+            renderer.render_split(&camera, &scene_light, (&scene, &frame), (&right_scene, &right_frame))[0]
+        } else {
+            renderer.render(&camera, &scene_light, &scene, &frame)
+        };
+        if let Some(fade) = crossfade.as_mut() {
+            fade.advance(real_dt);
+        }
+        let projection_matrix = frame_projection(&camera, &frame, renderer.framebuffer.width as f32, renderer.framebuffer.height as f32);
+        last_view_projection = Some(camera.get_view_matrix() * projection_matrix);
+
+        if let Err(e) = recorder.capture(&mut renderer.framebuffer, real_dt) {
+            eprintln!("Recorder error: {}", e);
+            recorder.stop();
+        }
+
+        let selection = selected
+            .map(|body| describe(body, time, &scene_light, camera.eye, &planets[planet_type]))
+            .unwrap_or_default();
+        WindowPresenter { window: &mut window, thread: &raylib_thread }.present_with_overlay(&renderer.framebuffer, |d| {
+            if ui.visible {
+                draw_parameter_panel(d, &mut ui, &mut planets, &mut planet_type, &mut light);
+            }
+            if show_hud {
+                hud::draw_hud(d, &stats, &clock, &selection);
+            }
+            hud::draw_events(d, events.active());
+        });
+    }
+
+    if let (Some(demo), Some(path)) = (demo_recording, &options.record_demo) {
+        match demo.save(Path::new(path)) {
+            Ok(()) => println!("Demo saved to {} ({} frames)", path, demo.frames.len()),
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+}
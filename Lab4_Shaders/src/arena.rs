@@ -0,0 +1,40 @@
+// arena.rs
+// Buffers de trabajo del pipeline que se reutilizan de un frame al siguiente:
+// se vacían con clear() antes de cada uso pero conservan su capacidad, así que
+// después del primer frame el render casi no pide memoria nueva.
+use crate::capture::FrameCapture;
+use crate::light::Light;
+use crate::shadow::{ShadowMap, SHADOW_MAP_SIZE};
+use crate::tiles::TileBins;
+use crate::vertex::Vertex;
+
+/// Scratch buffers for one draw call; each draw reuses them in turn
+#[derive(Default)]
+pub struct DrawScratch {
+    pub transformed: Vec<Vertex>,
+    pub triangles: Vec<[Vertex; 3]>,
+    pub bins: TileBins,
+    pub capture: Option<FrameCapture>, // si hay uno, cada draw call del frame se vuelca ahí
+}
+
+/// Every buffer the renderer keeps between frames
+#[derive(Default)]
+pub struct FrameArena {
+    pub draw: DrawScratch,
+    shadow_map: Option<ShadowMap>,
+}
+
+impl FrameArena {
+    /// Starts a frame: returns the shadow map cleared for `light` (allocated on
+    /// first use) together with the draw scratch buffers
+    pub fn begin_frame(&mut self, light: &Light, scene_radius: f32) -> (&mut ShadowMap, &mut DrawScratch) {
+        let shadow_map = match &mut self.shadow_map {
+            Some(shadow_map) => {
+                shadow_map.reset(light, scene_radius);
+                shadow_map
+            }
+            slot => slot.insert(ShadowMap::new(SHADOW_MAP_SIZE, light, scene_radius)),
+        };
+        (shadow_map, &mut self.draw)
+    }
+}
@@ -0,0 +1,75 @@
+// asteroids.rs
+// Cinturón de asteroides: cientos de rocas que comparten una sola malla. Cada
+// asteroide solo guarda su órbita y giro; en cada frame se calcula su matriz de
+// modelo y el pipeline transforma la misma malla una vez por instancia.
+use crate::math::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+use crate::matrix::create_model_matrix;
+
+pub const ASTEROID_COLOR: Vector3 = Vector3::new(0.5, 0.45, 0.4); // Gris roca
+
+const ASTEROID_COUNT: usize = 300;
+const BELT_INNER_RADIUS: f32 = 3.6;
+const BELT_OUTER_RADIUS: f32 = 4.4;
+const BELT_THICKNESS: f32 = 0.15; // desviación máxima sobre el plano del cinturón
+const BELT_TILT: f32 = 0.15;      // inclinación del plano del cinturón (radianes)
+// Esfera centrada en el planeta que contiene todo el cinturón (radio, altura y tamaño de las rocas)
+pub const BELT_BOUNDING_RADIUS: f32 = BELT_OUTER_RADIUS + 0.25;
+const ORBIT_SPEED: f32 = 1.6;     // rad/s a radio 1; cae con r^1.5 como en las órbitas de Kepler
+
+struct Asteroid {
+    radius: f32,     // distancia al planeta
+    phase: f32,      // ángulo inicial en la órbita
+    height: f32,     // altura sobre el plano del cinturón
+    tilt: Vector3,   // orientación propia inicial (Euler)
+    spin: Vector3,   // velocidad de giro sobre sí mismo (rad/s)
+    scale: f32,
+}
+
+/// The belt's asteroids plus the per-instance model matrices of the current
+/// and the previous frame
+pub struct AsteroidBelt {
+    asteroids: Vec<Asteroid>,
+    pub instances: Vec<Matrix>,
+    pub previous_instances: Vec<Matrix>, // vacío hasta el segundo update
+}
+
+impl AsteroidBelt {
+    /// A belt with random orbits; the same `seed` always gives the same belt
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let asteroids = (0..ASTEROID_COUNT)
+            .map(|_| Asteroid {
+                radius: rng.random_range(BELT_INNER_RADIUS..BELT_OUTER_RADIUS),
+                phase: rng.random_range(0.0..2.0 * PI),
+                height: rng.random_range(-BELT_THICKNESS..BELT_THICKNESS),
+                tilt: Vector3::new(rng.random_range(0.0..PI), rng.random_range(0.0..PI), rng.random_range(0.0..PI)),
+                spin: Vector3::new(rng.random_range(-1.5..1.5), rng.random_range(-1.5..1.5), 0.0),
+                // Muchas piedras pequeñas y pocas grandes
+                scale: 0.03 + 0.09 * rng.random::<f32>().powi(3),
+            })
+            .collect();
+        AsteroidBelt { asteroids, instances: Vec::with_capacity(ASTEROID_COUNT), previous_instances: Vec::with_capacity(ASTEROID_COUNT) }
+    }
+
+    /// Recomputes every asteroid's model matrix (in the belt's plane) for time
+    /// `time`, keeping the ones it replaces as `previous_instances`
+    pub fn update(&mut self, time: f32) {
+        std::mem::swap(&mut self.instances, &mut self.previous_instances);
+        self.instances.clear();
+        self.instances.extend(self.asteroids.iter().map(|asteroid| {
+            let angle = asteroid.phase + time * ORBIT_SPEED / asteroid.radius.powf(1.5);
+            let position = Vector3::new(asteroid.radius * angle.cos(), asteroid.height, asteroid.radius * angle.sin());
+            // Primero el giro propio (en el origen) y después la traslación a la órbita
+            let spin = create_model_matrix(Vector3::zero(), asteroid.scale, asteroid.tilt + asteroid.spin * time);
+            spin * create_model_matrix(position, 1.0, Vector3::zero())
+        }));
+    }
+}
+
+/// Model matrix of the whole belt, applied after each asteroid's own matrix
+pub fn belt_model_matrix() -> Matrix {
+    create_model_matrix(Vector3::zero(), 1.0, Vector3::new(BELT_TILT, 0.0, 0.0))
+}
@@ -0,0 +1,75 @@
+// bake.rs
+// Horneado de texturas: evalúa la función de color de un planeta sobre una
+// grilla de latitud/longitud y la guarda como imagen equirectangular, para usar
+// los planetas procedurales en otros motores. El mapeo es el mismo que el de las
+// coordenadas UV de generate_uv_sphere, así la textura calza con esa malla.
+use crate::math::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::cli::Options;
+use crate::framebuffer::Framebuffer;
+use crate::mesh::SPHERE_RADIUS;
+use crate::planets::{PlanetColorFn, PlanetType};
+use crate::tonemap::{DisplayTransform, ToneMapping};
+
+// Instante en que se evalúan los shaders (los animados quedan congelados aquí)
+const BAKE_TIME: f32 = 0.0;
+
+/// Unit direction through the center of texel (`x`, `y`) of a `width` x
+/// `height` equirectangular map: u follows the longitude and v goes from the
+/// north pole (+Y) at the top to the south pole at the bottom
+pub fn texel_direction(x: i32, y: i32, width: i32, height: i32) -> Vector3 {
+    let u = (x as f32 + 0.5) / width as f32;
+    let v = (y as f32 + 0.5) / height as f32;
+    let theta = v * PI;     // ángulo desde el polo norte
+    let phi = u * 2.0 * PI; // longitud
+    Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin())
+}
+
+/// Evaluates `shader` at every texel of a `width` x `width / 2` map
+pub fn bake(shader: PlanetColorFn, planet: &PlanetType, width: i32) -> Framebuffer {
+    let height = (width / 2).max(1);
+    let mut texture = Framebuffer::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            // Mismo espacio que local_pos en el fragment shader: la superficie de la esfera
+            let position = texel_direction(x, y, width, height) * SPHERE_RADIUS;
+            texture.point(x, y, shader(&position, BAKE_TIME, &planet.params), 0.0);
+        }
+    }
+    texture
+}
+
+/// Bakes the color (and emission, if any) of `planets[index]`, or of every
+/// preset when no index is given, into PNG files in `options.output_dir`
+pub fn run(options: &Options, planets: &[PlanetType], index: Option<usize>) -> io::Result<()> {
+    fs::create_dir_all(&options.output_dir)?;
+
+    // Colores de superficie, no HDR: se recortan en vez de pasar por la curva
+    // fílmica, pero conservan la gamma y el dithering elegidos
+    let display = DisplayTransform { tone_mapping: ToneMapping::Clamp, exposure: 1.0, ..options.display };
+    let selected = match index {
+        Some(index) => &planets[index..=index],
+        None => planets,
+    };
+
+    for planet in selected {
+        // Con espacio lineal la paleta se mezcla en lineal y la gamma la vuelve a sRGB al guardar
+        let planet = &planet.in_color_space(display.color_space);
+        let layers = [("", Some(planet.color)), ("_emission", planet.emission)];
+        for (suffix, shader) in layers {
+            let Some(shader) = shader else {
+                continue;
+            };
+            let mut texture = bake(shader, planet, options.texture_size);
+            texture.resolve_with(display);
+            let path = Path::new(&options.output_dir).join(format!("{}{}.png", planet.name, suffix));
+            texture.save(&path.to_string_lossy())?;
+            println!("Baked {} ({}x{})", path.display(), texture.width, texture.height);
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,58 @@
 // camera.rs
 #![allow(dead_code)]
 
-use raylib::prelude::*;
-use crate::matrix::create_view_matrix;
+use crate::math::*;
+use crate::depth::DepthMode;
+use crate::matrix::{create_orthographic_matrix, create_projection_matrix, create_reverse_orthographic_matrix, create_reverse_projection_matrix, create_view_matrix};
+use crate::mesh::projected_radius;
+#[cfg(feature = "native")]
+use raylib::prelude::{KeyboardKey, RaylibHandle};
 use std::f32::consts::PI;
 
+/// How the scene is projected onto the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    Orthographic, // sin perspectiva: útil para diagramas de perfil de anillos y órbitas
+}
+
+impl Projection {
+    pub fn toggle(self) -> Self {
+        match self {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        }
+    }
+
+    /// Projection matrix for `camera`. The orthographic view shows the same area
+    /// as the perspective one does at the target's distance, so toggling keeps
+    /// the planet about the same size. With reverse-Z the perspective far plane
+    /// is at infinity and `far` only bounds the orthographic view.
+    pub fn matrix(self, camera: &Camera, fov_y: f32, aspect: f32, near: f32, far: f32, depth: DepthMode) -> Matrix {
+        match (self, depth) {
+            (Projection::Perspective, DepthMode::Standard) => create_projection_matrix(fov_y, aspect, near, far),
+            (Projection::Perspective, DepthMode::ReverseZ) => create_reverse_projection_matrix(fov_y, aspect, near),
+            (Projection::Orthographic, _) => {
+                let half_height = camera.half_view_height(fov_y);
+                let (half_width, half_height) = (half_height * aspect, half_height);
+                match depth {
+                    DepthMode::Standard => create_orthographic_matrix(half_width, half_height, near, far),
+                    DepthMode::ReverseZ => create_reverse_orthographic_matrix(half_width, half_height, near, far),
+                }
+            }
+        }
+    }
+
+    /// Approximate radius in pixels of a sphere on a viewport `viewport_height` pixels tall
+    pub fn screen_radius(self, camera: &Camera, center: Vector3, radius: f32, fov_y: f32, viewport_height: f32) -> f32 {
+        match self {
+            Projection::Perspective => projected_radius(center, radius, camera.eye, fov_y, viewport_height),
+            Projection::Orthographic => radius / camera.half_view_height(fov_y) * (viewport_height / 2.0),
+        }
+    }
+}
+
 pub struct Camera {
     // Camera position/orientation
     pub eye: Vector3,        // Camera position
@@ -48,6 +96,24 @@ impl Camera {
         }
     }
 
+    /// Moves the camera to an exact pose (e.g. a replayed frame), keeping its speeds
+    pub fn set_pose(&mut self, eye: Vector3, target: Vector3, up: Vector3) {
+        let posed = Camera::new(eye, target, up);
+        self.eye = posed.eye;
+        self.target = posed.target;
+        self.up = posed.up;
+        self.yaw = posed.yaw;
+        self.pitch = posed.pitch;
+        self.distance = posed.distance;
+    }
+
+    /// Sets the orbit's target and distance, keeping yaw and pitch
+    pub fn set_orbit(&mut self, target: Vector3, distance: f32) {
+        self.target = target;
+        self.distance = distance;
+        self.update_eye_position();
+    }
+
     /// Update camera eye position based on yaw, pitch, and distance
     fn update_eye_position(&mut self) {
         // Clamp pitch to avoid gimbal lock
@@ -62,12 +128,18 @@ impl Camera {
         self.eye.z = self.target.z + self.distance * self.pitch.cos() * self.yaw.sin();
     }
 
+    /// Half the height of the area a perspective view with `fov_y` sees at the target
+    pub fn half_view_height(&self, fov_y: f32) -> f32 {
+        (self.target - self.eye).length().max(0.01) * (fov_y / 2.0).tan()
+    }
+
     /// Get the view matrix for this camera
     pub fn get_view_matrix(&self) -> Matrix {
         create_view_matrix(self.eye, self.target, self.up)
     }
 
     /// Process keyboard input to control the camera
+    #[cfg(feature = "native")]
     pub fn process_input(&mut self, window: &RaylibHandle) {
         // Rotation controls (yaw)
         if window.is_key_down(KeyboardKey::KEY_A) {
@@ -146,14 +218,60 @@ impl Camera {
             self.update_eye_position();
         }
 
-        // Vertical panning
-        if window.is_key_down(KeyboardKey::KEY_R) {
+        // Vertical panning (R is reserved for the recorder)
+        if window.is_key_down(KeyboardKey::KEY_PAGE_UP) {
             self.target.y += self.pan_speed;
             self.update_eye_position();
         }
-        if window.is_key_down(KeyboardKey::KEY_F) {
+        if window.is_key_down(KeyboardKey::KEY_PAGE_DOWN) {
             self.target.y -= self.pan_speed;
             self.update_eye_position();
         }
     }
-}
\ No newline at end of file
+}
+
+// Duración de la animación al enfocar un cuerpo, en segundos reales
+pub const FOCUS_DURATION: f32 = 1.0;
+// Fracción de la altura de la vista que ocupa el cuerpo enfocado
+const FOCUS_FRAME_FRACTION: f32 = 0.35;
+
+/// Orbit distance at which a sphere of `radius` fills `FOCUS_FRAME_FRACTION` of the view height
+pub fn framing_distance(radius: f32, fov_y: f32) -> f32 {
+    (radius / (FOCUS_FRAME_FRACTION * (fov_y / 2.0).tan())).max(0.3)
+}
+
+/// Animated move of the orbit camera onto a body: target and distance are eased
+/// from where the camera was to frame the body's sphere over `FOCUS_DURATION`;
+/// afterwards the target keeps following the body while it moves
+#[derive(Clone, Copy, Debug)]
+pub struct CameraFocus {
+    start_target: Vector3,
+    start_distance: f32,
+    elapsed: f32,
+}
+
+impl CameraFocus {
+    pub fn start(camera: &Camera) -> Self {
+        CameraFocus { start_target: camera.target, start_distance: camera.distance, elapsed: 0.0 }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= FOCUS_DURATION
+    }
+
+    /// Advances the animation by `dt` seconds toward the sphere (`center`, `radius`)
+    /// where it is now, so moving bodies are reached too
+    pub fn update(&mut self, camera: &mut Camera, center: Vector3, radius: f32, dt: f32, fov_y: f32) {
+        if self.finished() {
+            // Ya enfocado: solo seguir al cuerpo, la distancia queda libre para el zoom
+            camera.set_orbit(center, camera.distance);
+            return;
+        }
+
+        self.elapsed = (self.elapsed + dt.max(0.0)).min(FOCUS_DURATION);
+        let t = self.elapsed / FOCUS_DURATION;
+        let eased = t * t * (3.0 - 2.0 * t); // arranca y frena suave
+        let distance = self.start_distance + (framing_distance(radius, fov_y) - self.start_distance) * eased;
+        camera.set_orbit(self.start_target.lerp(center, eased), distance);
+    }
+}
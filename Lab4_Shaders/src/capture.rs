@@ -0,0 +1,95 @@
+// capture.rs
+// Volcado de un frame para revisarlo fuera del programa: por cada draw call los
+// vértices ya transformados, qué hizo el ensamblado con cada triángulo (dibujado,
+// recortado o por qué se descartó) y cuántos fragmentos generó. Es lo que hace falta
+// para encontrar un triángulo degenerado sin adivinar desde la imagen final.
+use crate::math::Vector3;
+use crate::stats::RenderStats;
+use crate::triangle::TriangleStatus;
+use crate::vertex::Vertex;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// One vertex after the vertex shader
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CapturedVertex {
+    pub world: [f32; 3],
+    pub screen: [f32; 3], // x e y en píxeles, z la profundidad en NDC
+    pub clip_w: f32,
+}
+
+impl CapturedVertex {
+    pub fn new(vertex: &Vertex) -> Self {
+        let array = |v: Vector3| [v.x, v.y, v.z];
+        CapturedVertex { world: array(vertex.position), screen: array(vertex.transformed_position), clip_w: vertex.clip_w }
+    }
+}
+
+/// One assembled triangle: indices into its draw's vertices, what assembly did
+/// with it and the fragments it produced
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CapturedTriangle {
+    pub vertices: [usize; 3],
+    pub status: TriangleStatus,
+    pub fragments: usize, // generados por el rasterizador
+    pub written: usize,   // pasaron el depth test
+}
+
+/// One draw call (a body, or every instance of an instanced mesh)
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DrawCapture {
+    pub mesh_id: i32, // el mismo de DrawUniforms
+    pub culled: bool, // la esfera envolvente quedó fuera del frustum: no hay vértices
+    pub vertices: Vec<CapturedVertex>,
+    pub triangles: Vec<CapturedTriangle>,
+    #[serde(skip)]
+    drawn: Vec<usize>, // índice en `triangles` de cada triángulo que llegó al rasterizador
+}
+
+impl DrawCapture {
+    /// Capture of a draw whose transformed vertices are `vertices`, each triple a
+    /// triangle with the matching entry of `statuses`
+    pub fn new(mesh_id: i32, vertices: &[Vertex], statuses: impl Iterator<Item = TriangleStatus>) -> Self {
+        let triangles: Vec<CapturedTriangle> = statuses
+            .enumerate()
+            .map(|(index, status)| CapturedTriangle { vertices: [index * 3, index * 3 + 1, index * 3 + 2], status, fragments: 0, written: 0 })
+            .collect();
+        let drawn = triangles.iter().enumerate().filter(|(_, triangle)| !triangle.status.culled()).map(|(index, _)| index).collect();
+        DrawCapture { mesh_id, culled: false, vertices: vertices.iter().map(CapturedVertex::new).collect(), triangles, drawn }
+    }
+
+    /// Capture of a draw skipped by frustum culling
+    pub fn culled(mesh_id: i32) -> Self {
+        DrawCapture { mesh_id, culled: true, vertices: Vec::new(), triangles: Vec::new(), drawn: Vec::new() }
+    }
+
+    /// Counts a fragment of the `triangle_id`-th triangle sent to the rasterizer
+    pub fn record_fragment(&mut self, triangle_id: usize, written: bool) {
+        if let Some(triangle) = self.drawn.get(triangle_id).and_then(|&index| self.triangles.get_mut(index)) {
+            triangle.fragments += 1;
+            triangle.written += written as usize;
+        }
+    }
+}
+
+/// Intermediate data of one whole frame, see `Renderer::capture_frame`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FrameCapture {
+    pub width: i32,
+    pub height: i32,
+    pub time: f32,
+    pub stats: RenderStats,
+    pub draws: Vec<DrawCapture>,
+}
+
+impl FrameCapture {
+    /// Pretty-printed JSON; NaN coordinates (vertices behind the camera) come out as null
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("frame captures only hold numbers and strings")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json()).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,377 @@
+// cli.rs
+use std::env;
+use crate::recorder::RecordFormat;
+use crate::mesh::MeshSpec;
+use crate::tonemap::DisplayTransform;
+use crate::debug::DebugView;
+use crate::triangle::RenderMode;
+use crate::shaders::ShadingMode;
+use crate::camera::Projection;
+use crate::depth::DepthMode;
+use crate::gizmos::Gizmos;
+use crate::display::Backend;
+use crate::math::Vector3;
+use crate::planets::PlanetType;
+use crate::postprocess::{Bloom, DepthOfField, Fxaa, MotionBlur, PostProcessPipeline};
+
+pub const USAGE: &str = "\
+Usage: ship [options]
+       ship bake [--planet <name|n>] [--texture-size <px>] [--output <dir>]
+       ship cubemap [--from <x,y,z>] [--face-size <px>] [--time <s>] [--output <dir>]
+
+Options:
+  --width <px>        Initial window / render width; the window can be resized (default 1300)
+  --height <px>       Initial window / render height (default 900)
+  --fullscreen        Open the window in fullscreen mode
+  --vsync             Enable vertical sync
+  --fps <n>           Target frames per second, 0 = unlimited (default 60)
+  --planet <name|n>   Initial planet preset, by name or 1-based number (default 1)
+  --config <file>     Planet preset file, .toml or .json (default planets.toml)
+  --mesh <spec>       Sphere mesh: obj, uv:<slices>x<stacks> or ico:<subdivisions> (default obj)
+  --ssaa <n>          Supersampling: render at n times the width and height and average down, 1-4 (default 1)
+  --msaa              4x multisampling: four coverage samples per pixel, shaded once (toggle with X)
+  --fxaa              Smooth edges with FXAA on the final image (toggle with F)
+  --bloom             Glow around bright areas such as lava and glitter (toggle with B)
+  --motion-blur       Streak moving surfaces along their screen-space velocity (toggle with N)
+  --dof               Depth of field: blur what is far from the focus distance (toggle with O)
+  --focus <d>         Distance from the camera that stays sharp with --dof (default 8.0)
+  --lens-flare        Ghosts and a streak when the sun is on screen (toggle with K)
+  --tonemap <op>      HDR to display curve: clamp, reinhard or aces (default aces, cycle with T)
+  --exposure <x>      Multiplier applied to the HDR color before tone mapping (default 1.0)
+  --gamma <g>         Display gamma, 1.0 disables the correction (default 2.2)
+  --color-space <s>   Space colors are lit and blended in: linear or srgb (default linear, toggle with C)
+  --no-dither         Quantize to 8 bits without ordered dithering
+  --debug-view <v>    shaded, depth, normals, wireframe, uv or ids (default shaded, cycle with V)
+  --wireframe         Draw triangle edges only (toggle with M)
+  --shading <mode>    Where lighting is computed: flat (per triangle), gouraud (per vertex) or phong (per pixel) (default phong, cycle with U)
+  --split-shading <m> Split screen: the right half is lit with this shading mode, the left with --shading
+  --split-planet <p>  Split screen: the right half shows this planet, the left the current one
+  --orthographic      Orthographic instead of perspective projection (toggle with P)
+  --depth <mode>      Depth mapping: standard or reverse-z (infinite far plane) (default standard, toggle with Z)
+  --depth-overlay     Show how precise the depth buffer is at each distance (toggle with J)
+  --gizmos            Draw every body's orbit and spin axis (toggle per body with Y)
+  --lod               Pick an icosphere resolution per body from its size on screen (overrides --mesh)
+  --record-format <f> Recorder output: gif or png (default gif)
+  --record-fps <n>    Frames per second kept by the recorder, 0 = every frame (default 15)
+  --record-skip <n>   Recorder keeps one of every n+1 rendered frames (default 0)
+  --backend <b>       Where frames are shown: window or terminal (colored characters, q quits) (default window)
+  --headless          Render to image files without opening a window
+  --frames <n>        Number of frames to render in headless mode (default 1)
+  --output <dir>      Output directory for headless frames (default \"frames\")
+  --capture           With --headless, also dump each frame's vertices, triangles and fragment counts as JSON
+  --record-demo <f>   Save the camera and clock of every frame to a demo file on exit
+  --replay <f>        Play back a demo file; with --headless renders one image per demo frame
+  --texture-size <px> Width of the baked textures, the height is half (default 1024)
+  --from <x,y,z>      Point the cubemap is rendered from (default: the initial camera position)
+  --face-size <px>    Width and height of each cubemap face (default 512)
+  --time <s>          Simulation time the cubemap is rendered at (default 0)
+  -h, --help          Show this help
+
+The bake subcommand writes each planet's surface color (and emission, if it has
+one) as an equirectangular PNG instead of opening a window; without --planet it
+bakes every preset.
+
+The cubemap subcommand renders the scene six times with 90 degree cameras and
+writes the faces (px, nx, py, ny, pz, nz) plus an equirectangular panorama.png.";
+
+/// What the program does instead of (or before) opening the window
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Render,  // ventana interactiva, o imágenes con --headless
+    Bake,    // texturas equirectangulares de los planetas
+    Cubemap, // las seis caras de la escena vista desde un punto
+}
+
+pub struct Options {
+    pub command: Command,
+    pub width: i32,
+    pub height: i32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub target_fps: u32,
+    pub planet: Option<String>,
+    pub config_path: String,
+    pub mesh: MeshSpec,
+    pub lod: bool,
+    pub ssaa: i32,
+    pub msaa: bool,
+    pub fxaa: bool,
+    pub bloom: bool,
+    pub motion_blur: bool,
+    pub dof: bool,
+    pub focus_distance: f32,
+    pub lens_flare: bool,
+    pub display: DisplayTransform,
+    pub debug_view: DebugView,
+    pub render_mode: RenderMode,
+    pub shading: ShadingMode,
+    pub split_shading: Option<ShadingMode>, // pantalla dividida: sombreado de la mitad derecha
+    pub split_planet: Option<String>,       // pantalla dividida: planeta de la mitad derecha
+    pub projection: Projection,
+    pub depth_mode: DepthMode,
+    pub depth_overlay: bool,
+    pub gizmos: Gizmos,
+    pub record_format: RecordFormat,
+    pub record_fps: f32,
+    pub record_skip: u32,
+    pub backend: Backend,
+    pub headless: bool,
+    pub frames: u32,
+    pub output_dir: String,
+    pub capture: bool, // volcado del pipeline de cada frame headless (ver capture.rs)
+    pub record_demo: Option<String>,
+    pub replay: Option<String>,
+    pub texture_size: i32,
+    pub cubemap_origin: Option<Vector3>,
+    pub face_size: i32,
+    pub time: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            command: Command::Render,
+            width: 1300,
+            height: 900,
+            fullscreen: false,
+            vsync: false,
+            target_fps: 60,
+            planet: None,
+            config_path: String::from("planets.toml"),
+            mesh: MeshSpec::Obj,
+            lod: false,
+            ssaa: 1,
+            msaa: false,
+            fxaa: false,
+            bloom: false,
+            motion_blur: false,
+            dof: false,
+            focus_distance: 8.0,
+            lens_flare: false,
+            display: DisplayTransform::default(),
+            debug_view: DebugView::Shaded,
+            render_mode: RenderMode::Solid,
+            shading: ShadingMode::Phong,
+            split_shading: None,
+            split_planet: None,
+            projection: Projection::Perspective,
+            depth_mode: DepthMode::Standard,
+            depth_overlay: false,
+            gizmos: Gizmos::NONE,
+            record_format: RecordFormat::Gif,
+            record_fps: 15.0,
+            record_skip: 0,
+            backend: Backend::Window,
+            headless: false,
+            frames: 1,
+            output_dir: String::from("frames"),
+            capture: false,
+            record_demo: None,
+            replay: None,
+            texture_size: 1024,
+            cubemap_origin: None,
+            face_size: 512,
+            time: 0.0,
+        }
+    }
+}
+
+impl Options {
+    /// Whether the frame is split into two views side by side (`--split-shading` or `--split-planet`)
+    pub fn split(&self) -> bool {
+        self.split_shading.is_some() || self.split_planet.is_some()
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for {}", flag))?;
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+/// Parses a point written as `x,y,z`
+pub fn parse_vector(flag: &str, value: Option<String>) -> Result<Vector3, String> {
+    let value: String = parse_value(flag, value)?;
+    let invalid = || format!("invalid value for {}: {} (expected x,y,z)", flag, value);
+    let components = value.split(',').map(|part| part.trim().parse::<f32>()).collect::<Result<Vec<f32>, _>>().map_err(|_| invalid())?;
+    match components[..] {
+        [x, y, z] => Ok(Vector3::new(x, y, z)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses the process arguments. Returns `Ok(None)` when only the help was requested.
+pub fn parse_args() -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1).peekable();
+
+    // Subcomando opcional antes de las opciones
+    let command = match args.peek().map(String::as_str) {
+        Some("bake") => Some(Command::Bake),
+        Some("cubemap") => Some(Command::Cubemap),
+        _ => None,
+    };
+    if let Some(command) = command {
+        args.next();
+        options.command = command;
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => options.width = parse_value(&arg, args.next())?,
+            "--height" => options.height = parse_value(&arg, args.next())?,
+            "--fullscreen" => options.fullscreen = true,
+            "--vsync" => options.vsync = true,
+            "--fps" => options.target_fps = parse_value(&arg, args.next())?,
+            "--planet" => options.planet = Some(parse_value(&arg, args.next())?),
+            "--config" => options.config_path = parse_value(&arg, args.next())?,
+            "--mesh" => {
+                let spec: String = parse_value(&arg, args.next())?;
+                options.mesh = spec.parse()?;
+            }
+            "--record-format" => {
+                let format: String = parse_value(&arg, args.next())?;
+                options.record_format = match format.as_str() {
+                    "gif" => RecordFormat::Gif,
+                    "png" => RecordFormat::PngSequence,
+                    _ => return Err(format!("invalid value for --record-format: {}", format)),
+                };
+            }
+            "--record-fps" => options.record_fps = parse_value(&arg, args.next())?,
+            "--record-skip" => options.record_skip = parse_value(&arg, args.next())?,
+            "--lod" => options.lod = true,
+            "--msaa" => options.msaa = true,
+            "--fxaa" => options.fxaa = true,
+            "--bloom" => options.bloom = true,
+            "--motion-blur" => options.motion_blur = true,
+            "--dof" => options.dof = true,
+            "--focus" => options.focus_distance = parse_value(&arg, args.next())?,
+            "--lens-flare" => options.lens_flare = true,
+            "--tonemap" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.display.tone_mapping = name.parse()?;
+            }
+            "--wireframe" => options.render_mode = RenderMode::Wireframe,
+            "--shading" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.shading = name.parse()?;
+            }
+            "--split-shading" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.split_shading = Some(name.parse()?);
+            }
+            "--split-planet" => options.split_planet = Some(parse_value(&arg, args.next())?),
+            "--orthographic" => options.projection = Projection::Orthographic,
+            "--depth" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.depth_mode = name.parse()?;
+            }
+            "--depth-overlay" => options.depth_overlay = true,
+            "--gizmos" => options.gizmos = Gizmos::ALL,
+            "--debug-view" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.debug_view = name.parse()?;
+            }
+            "--exposure" => options.display.exposure = parse_value(&arg, args.next())?,
+            "--gamma" => options.display.gamma = parse_value(&arg, args.next())?,
+            "--color-space" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.display.color_space = name.parse()?;
+            }
+            "--no-dither" => options.display.dither = false,
+            "--ssaa" => options.ssaa = parse_value(&arg, args.next())?,
+            "--backend" => {
+                let name: String = parse_value(&arg, args.next())?;
+                options.backend = name.parse()?;
+            }
+            "--headless" => options.headless = true,
+            "--frames" => options.frames = parse_value(&arg, args.next())?,
+            "--output" => options.output_dir = parse_value(&arg, args.next())?,
+            "--capture" => options.capture = true,
+            "--record-demo" => options.record_demo = Some(parse_value(&arg, args.next())?),
+            "--replay" => options.replay = Some(parse_value(&arg, args.next())?),
+            "--texture-size" => options.texture_size = parse_value(&arg, args.next())?,
+            "--from" => options.cubemap_origin = Some(parse_vector(&arg, args.next())?),
+            "--face-size" => options.face_size = parse_value(&arg, args.next())?,
+            "--time" => options.time = parse_value(&arg, args.next())?,
+            "-h" | "--help" => return Ok(None),
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+
+    if options.width <= 0 || options.height <= 0 {
+        return Err(String::from("width and height must be positive"));
+    }
+
+    if options.texture_size < 2 {
+        return Err(String::from("--texture-size must be at least 2"));
+    }
+
+    if options.face_size <= 0 {
+        return Err(String::from("--face-size must be positive"));
+    }
+
+    if !(1..=4).contains(&options.ssaa) {
+        return Err(String::from("--ssaa must be between 1 and 4"));
+    }
+
+    if options.display.exposure <= 0.0 || options.display.gamma <= 0.0 {
+        return Err(String::from("--exposure and --gamma must be positive"));
+    }
+
+    if options.focus_distance <= 0.0 {
+        return Err(String::from("--focus must be positive"));
+    }
+
+    if options.headless && options.backend == Backend::Terminal {
+        return Err(String::from("--headless renders to files and cannot be combined with --backend terminal"));
+    }
+
+    if options.capture && !options.headless {
+        return Err(String::from("--capture only works with --headless"));
+    }
+
+    if options.split() && options.capture {
+        return Err(String::from("--capture records a single view and cannot be combined with a split screen"));
+    }
+
+    if options.split() && options.backend == Backend::Terminal {
+        return Err(String::from("the split screen only works in the window or with --headless"));
+    }
+
+    if options.record_fps < 0.0 {
+        return Err(String::from("--record-fps must not be negative"));
+    }
+
+    Ok(Some(options))
+}
+
+/// Resolves `--planet` against the preset names, falling back to a 1-based index
+pub fn resolve_planet(planet: &str, names: &[&str]) -> Result<usize, String> {
+    if let Some(index) = names.iter().position(|name| *name == planet) {
+        return Ok(index);
+    }
+    match planet.parse::<usize>() {
+        Ok(number) if number >= 1 && number <= names.len() => Ok(number - 1),
+        _ => Err(format!("unknown planet type: {} (available: {})", planet, names.join(", "))),
+    }
+}
+
+/// `--split-planet` resolved against the presets, if given
+pub fn split_planet(options: &Options, planets: &[PlanetType]) -> Result<Option<usize>, String> {
+    let names: Vec<&str> = planets.iter().map(|planet| planet.name.as_str()).collect();
+    options.split_planet.as_deref().map(|planet| resolve_planet(planet, &names)).transpose()
+}
+
+/// Post-process chain applied to every presented frame: motion blur and depth
+/// of field on the plain image, then bloom, then FXAA so the anti-aliasing also
+/// smooths the glow's edges
+pub fn create_post_process(options: &Options) -> PostProcessPipeline {
+    let mut pipeline = PostProcessPipeline::new();
+    pipeline.push(MotionBlur::default(), options.motion_blur);
+    let depth_of_field = DepthOfField { focus_distance: options.focus_distance, ..DepthOfField::default() };
+    pipeline.push(depth_of_field, options.dof);
+    pipeline.push(Bloom::default(), options.bloom);
+    pipeline.push(Fxaa, options.fxaa);
+    pipeline
+}
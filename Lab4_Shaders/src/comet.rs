@@ -0,0 +1,103 @@
+// comet.rs
+// Cometa en una órbita elíptica alrededor del planeta: un núcleo rocoso pequeño y
+// una cola de partículas que el viento solar empuja siempre lejos de la luz.
+use crate::math::*;
+use std::f32::consts::PI;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::matrix::create_model_matrix;
+use crate::particles::{Particle, ParticleSystem};
+use crate::uniforms::GlobalUniforms;
+
+// Escala de la malla del núcleo (radio 0.5 antes de escalar)
+pub const COMET_SCALE: f32 = 0.12;
+pub const COMET_COLOR: Vector3 = Vector3::new(0.55, 0.5, 0.45); // Gris polvoriento
+
+const TAIL_COLOR: Vector3 = Vector3::new(0.35, 0.6, 1.0); // Azul de la cola de iones
+
+// Órbita con el planeta en un foco; empieza en el afelio, lejos de la cámara
+const ORBIT_SEMI_MAJOR: f32 = 4.0;
+const ORBIT_ECCENTRICITY: f32 = 0.55;
+pub const ORBIT_PERIOD: f32 = 40.0; // segundos por vuelta
+const ORBIT_TILT: f32 = 0.5;    // inclinación del plano de la órbita (radianes)
+
+const TAIL_RATE: f32 = 150.0;    // partículas por segundo
+const TAIL_LIFETIME: f32 = 2.5;  // segundos
+const TAIL_SPEED: f32 = 0.3;     // velocidad inicial alejándose del sol
+const SOLAR_WIND: f32 = 0.8;     // aceleración que aleja las partículas del sol
+const MAX_TAIL_PARTICLES: usize = 512;
+
+/// Solves Kepler's equation E - e sin E = M for the eccentric anomaly E of an
+/// orbit with `eccentricity` at `mean_anomaly` (radians)
+pub fn eccentric_anomaly(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    // Unas iteraciones de Newton alcanzan para las excentricidades que se usan
+    let mut eccentric = mean_anomaly;
+    for _ in 0..5 {
+        eccentric -= (eccentric - eccentricity * eccentric.sin() - mean_anomaly) / (1.0 - eccentricity * eccentric.cos());
+    }
+    eccentric
+}
+
+/// Center of the comet at time `time`, following Kepler's equation so it speeds
+/// up near the planet
+pub fn comet_position(time: f32) -> Vector3 {
+    let eccentric = eccentric_anomaly(PI + time * 2.0 * PI / ORBIT_PERIOD, ORBIT_ECCENTRICITY);
+
+    let semi_minor = ORBIT_SEMI_MAJOR * (1.0 - ORBIT_ECCENTRICITY * ORBIT_ECCENTRICITY).sqrt();
+    let x = ORBIT_SEMI_MAJOR * (eccentric.cos() - ORBIT_ECCENTRICITY);
+    let z = semi_minor * eccentric.sin();
+    Vector3::new(x, z * ORBIT_TILT.sin(), z * ORBIT_TILT.cos())
+}
+
+pub fn comet_model_matrix(time: f32) -> Matrix {
+    // create_model_matrix rota después de trasladar: el giro va aparte para que
+    // el núcleo rote sobre sí mismo y no alrededor del origen
+    let tumble = create_model_matrix(Vector3::zero(), COMET_SCALE, Vector3::new(time * 0.7, time * 0.4, 0.0));
+    tumble * create_model_matrix(comet_position(time), 1.0, Vector3::zero())
+}
+
+/// The comet's tail; the nucleus itself is stateless and drawn from `comet_position`
+pub struct Comet {
+    pub tail: ParticleSystem,
+}
+
+impl Default for Comet {
+    fn default() -> Self {
+        Comet::new()
+    }
+}
+
+impl Comet {
+    pub fn new() -> Self {
+        Comet { tail: ParticleSystem::new(MAX_TAIL_PARTICLES, 0x00C0_FFEE) }
+    }
+
+    /// Emits new tail particles at the nucleus and pushes all of them away from the light
+    pub fn update(&mut self, time: f32, dt: f32, light: &Light) {
+        let nucleus = comet_position(time);
+        let away = (nucleus - light.position).normalized();
+
+        for _ in 0..self.tail.emit_count(TAIL_RATE, dt) {
+            let jitter = self.tail.random_vector();
+            let spread = self.tail.random_vector().x * 0.5 + 0.5;
+            self.tail.spawn(Particle {
+                position: nucleus + jitter * (COMET_SCALE * 0.3),
+                velocity: away * TAIL_SPEED + jitter * 0.05,
+                age: 0.0,
+                lifetime: TAIL_LIFETIME * (0.6 + 0.4 * spread),
+                size: 0.03,
+            });
+        }
+
+        let sun = light.position;
+        self.tail.update(dt, |particle| (particle.position - sun).normalized() * SOLAR_WIND);
+    }
+
+    /// Adds the tail's glow; brightest near the nucleus and fading as particles age
+    pub fn render_tail(&self, framebuffer: &mut Framebuffer, globals: &GlobalUniforms) {
+        self.tail.render(framebuffer, globals, |particle| {
+            let fade = 1.0 - particle.life_fraction();
+            TAIL_COLOR * (0.7 * fade)
+        });
+    }
+}
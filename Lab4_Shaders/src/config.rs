@@ -0,0 +1,298 @@
+// config.rs
+use crate::math::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::gradient::Gradient;
+use crate::light::{Light, LightAnimation};
+use crate::moons::Moon;
+use crate::planets::{PlanetRegistry, PlanetType};
+
+// Copia de planets.toml incluida en el binario, usada si el archivo no se puede leer
+const DEFAULT_CONFIG: &str = include_str!("../planets.toml");
+
+// Cada cuánto se revisa si el archivo cambió (segundos)
+const RELOAD_INTERVAL: f32 = 0.5;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LightConfig {
+    pub position: [f32; 3],
+    pub temperature: Option<f32>, // kelvin; sin la clave la luz es blanca
+    pub intensity: Option<f32>,
+    pub orbit_speed: Option<f32>,
+    pub pulse: Option<f32>,
+    pub pulse_speed: Option<f32>,
+    pub flicker: Option<f32>,
+}
+
+/// A color written either as RGB in [0, 1] or as the name of one of the preset's colors
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ColorValue {
+    Rgb([f32; 3]),
+    Named(String),
+}
+
+/// One stop of a `[planet.gradients]` entry
+#[derive(Deserialize, Debug, Clone)]
+pub struct GradientStop {
+    pub at: f32,
+    pub color: ColorValue,
+}
+
+/// One `[[planet.moons]]` entry; orbital elements left out keep the default moon's
+#[derive(Deserialize, Debug, Clone)]
+pub struct MoonPreset {
+    pub name: String,
+    pub shader: Option<String>,
+    pub scale: Option<f32>,
+    pub distance: Option<f32>,
+    pub period: Option<f32>,
+    pub eccentricity: Option<f32>,
+    pub inclination: Option<f32>,
+    pub node: Option<f32>,
+    pub phase: Option<f32>,
+}
+
+impl MoonPreset {
+    fn to_moon(&self) -> Result<Moon, String> {
+        let defaults = Moon::default();
+        let shader = match &self.shader {
+            Some(shader) => shader.parse().map_err(|e| format!("moon '{}': {}", self.name, e))?,
+            None => defaults.shader,
+        };
+        let moon = Moon {
+            name: self.name.clone(),
+            scale: self.scale.unwrap_or(defaults.scale),
+            distance: self.distance.unwrap_or(defaults.distance),
+            period: self.period.unwrap_or(defaults.period),
+            eccentricity: self.eccentricity.unwrap_or(defaults.eccentricity),
+            inclination: self.inclination.unwrap_or(defaults.inclination),
+            node: self.node.unwrap_or(defaults.node),
+            phase: self.phase.unwrap_or(defaults.phase),
+            shader,
+        };
+        if !(0.0..1.0).contains(&moon.eccentricity) || moon.period == 0.0 || moon.distance <= 0.0 {
+            return Err(format!("moon '{}': needs distance > 0, period != 0 and eccentricity in [0, 1)", self.name));
+        }
+        Ok(moon)
+    }
+}
+
+/// One `[[planet]]` entry: a registered shader plus overrides for its parameters
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlanetPreset {
+    pub name: String,
+    pub shader: String,
+    pub rotation_speed: Option<f32>,
+    pub axial_tilt: Option<f32>,
+    pub precession_speed: Option<f32>,
+    pub noise_scale: Option<f32>,
+    pub band_frequency: Option<f32>,
+    pub rings: Option<bool>,
+    pub asteroids: Option<bool>,
+    pub atmosphere: Option<bool>,
+    pub aurora: Option<bool>,
+    pub displacement: Option<f32>,
+    pub triplanar: Option<bool>,
+    pub effect: Option<String>,
+    pub moons: Option<Vec<MoonPreset>>, // sin la clave queda la luna de siempre; `moons = []` la quita
+    #[serde(default)]
+    pub colors: HashMap<String, [f32; 3]>,
+    #[serde(default)]
+    pub gradients: HashMap<String, Vec<GradientStop>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Config {
+    pub light: LightConfig,
+    #[serde(default, rename = "planet")]
+    pub planets: Vec<PlanetPreset>,
+}
+
+impl Config {
+    /// Parses a config; `.json` files are read as JSON, anything else as TOML
+    pub fn parse(text: &str, path: &Path) -> Result<Config, String> {
+        let is_json = path.extension().is_some_and(|extension| extension == "json");
+        if is_json {
+            serde_json::from_str(text).map_err(|e| format!("{}: {}", path.display(), e))
+        } else {
+            toml::from_str(text).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Config::parse(&text, path)
+    }
+
+    /// The presets that ship with the lab (compiled in from planets.toml)
+    pub fn builtin() -> Config {
+        Config::parse(DEFAULT_CONFIG, Path::new("planets.toml")).expect("built-in planets.toml is invalid")
+    }
+
+    pub fn load_or_builtin(path: &Path) -> Config {
+        Config::load(path).unwrap_or_else(|message| {
+            eprintln!("Using built-in planet presets ({})", message);
+            Config::builtin()
+        })
+    }
+
+    pub fn light_position(&self) -> Vector3 {
+        let [x, y, z] = self.light.position;
+        Vector3::new(x, y, z)
+    }
+
+    /// The scene's light with its color and animation; frontends draw `light().at(time)`
+    pub fn light(&self) -> Light {
+        let preset = &self.light;
+        let defaults = LightAnimation::default();
+        let animation = LightAnimation {
+            orbit_speed: preset.orbit_speed.unwrap_or(defaults.orbit_speed),
+            pulse: preset.pulse.unwrap_or(defaults.pulse),
+            pulse_speed: preset.pulse_speed.unwrap_or(defaults.pulse_speed),
+            flicker: preset.flicker.unwrap_or(defaults.flicker),
+        };
+        let light = Light { intensity: preset.intensity.unwrap_or(1.0), ..Light::new(self.light_position()) };
+        let light = preset.temperature.map_or(light, |kelvin| light.with_temperature(kelvin));
+        light.with_animation(animation)
+    }
+
+    /// Builds the list of selectable planets: every preset, followed by any
+    /// registered shader that no preset uses (e.g. a plugin without a preset)
+    pub fn planet_types(&self, registry: &PlanetRegistry) -> Result<Vec<PlanetType>, String> {
+        let mut planets = Vec::new();
+
+        for preset in &self.planets {
+            let index = registry.find(&preset.shader).ok_or_else(|| {
+                let available: Vec<&str> = registry.names().collect();
+                format!("planet '{}' uses unknown shader '{}' (available: {})", preset.name, preset.shader, available.join(", "))
+            })?;
+            let mut planet = registry.get(index).expect("index returned by find").clone();
+
+            planet.name = preset.name.clone();
+            let params = &mut planet.params;
+            if let Some(rotation_speed) = preset.rotation_speed {
+                params.rotation_speed = rotation_speed;
+            }
+            if let Some(axial_tilt) = preset.axial_tilt {
+                params.axial_tilt = axial_tilt;
+            }
+            if let Some(precession_speed) = preset.precession_speed {
+                params.precession_speed = precession_speed;
+            }
+            if let Some(noise_scale) = preset.noise_scale {
+                params.noise_scale = noise_scale;
+            }
+            if let Some(band_frequency) = preset.band_frequency {
+                params.band_frequency = band_frequency;
+            }
+            if let Some(rings) = preset.rings {
+                params.rings = rings;
+            }
+            if let Some(asteroids) = preset.asteroids {
+                params.asteroids = asteroids;
+            }
+            if let Some(atmosphere) = preset.atmosphere {
+                params.atmosphere = atmosphere;
+            }
+            if let Some(aurora) = preset.aurora {
+                params.aurora = aurora;
+            }
+            if let Some(displacement) = preset.displacement {
+                params.displacement = displacement;
+            }
+            if let Some(triplanar) = preset.triplanar {
+                params.triplanar = triplanar;
+            }
+            if let Some(effect) = &preset.effect {
+                params.effect = effect.parse().map_err(|e| format!("planet '{}': {}", preset.name, e))?;
+            }
+            if let Some(moons) = &preset.moons {
+                params.moons = moons.iter()
+                    .map(|moon| moon.to_moon().map_err(|e| format!("planet '{}': {}", preset.name, e)))
+                    .collect::<Result<_, _>>()?;
+            }
+            for (name, [r, g, b]) in &preset.colors {
+                params.colors.insert(name.clone(), Vector3::new(*r, *g, *b));
+            }
+            for (name, stops) in &preset.gradients {
+                let mut resolved = Vec::with_capacity(stops.len());
+                for stop in stops {
+                    let color = match &stop.color {
+                        ColorValue::Rgb([r, g, b]) => Vector3::new(*r, *g, *b),
+                        ColorValue::Named(color) => *params.colors.get(color).ok_or_else(|| {
+                            format!("planet '{}': gradient '{}' uses unknown color '{}'", preset.name, name, color)
+                        })?,
+                    };
+                    resolved.push((stop.at, color));
+                }
+                params.gradients.insert(name.clone(), Gradient::new(resolved));
+            }
+
+            planets.push(planet);
+        }
+
+        for name in registry.names() {
+            if !self.planets.iter().any(|preset| preset.shader == name) {
+                let index = registry.find(name).expect("name returned by names");
+                planets.push(registry.get(index).expect("index returned by find").clone());
+            }
+        }
+
+        if planets.is_empty() {
+            return Err(String::from("no planet types defined"));
+        }
+        Ok(planets)
+    }
+}
+
+/// Polls the config file's modification time so edits are picked up while running
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    elapsed: f32,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &Path) -> Self {
+        ConfigWatcher {
+            path: path.to_path_buf(),
+            modified: Self::modified_time(path),
+            elapsed: 0.0,
+        }
+    }
+
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    /// Returns the reloaded config when the file changed since the last poll.
+    /// Parse errors are reported and the current config is kept.
+    pub fn poll(&mut self, dt: f32) -> Option<Config> {
+        self.elapsed += dt;
+        if self.elapsed < RELOAD_INTERVAL {
+            return None;
+        }
+        self.elapsed = 0.0;
+
+        let modified = Self::modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        match Config::load(&self.path) {
+            Ok(config) => {
+                println!("Reloaded {}", self.path.display());
+                Some(config)
+            }
+            Err(message) => {
+                eprintln!("Config reload failed: {}", message);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// cubemap.rs
+// Exportación de la escena como cubemap: se renderiza seis veces desde un mismo
+// punto con cámaras de 90° (una por eje) y, además de las caras, se arma un
+// panorama equirectangular muestreando el cubo. Sirve para hacer skyboxes con el
+// propio sistema solar.
+use crate::math::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::{Camera, Projection};
+use crate::cli::Options;
+use crate::comet::Comet;
+use crate::framebuffer::Framebuffer;
+use crate::gizmos::Gizmos;
+use crate::light::Light;
+use crate::mesh::SceneMeshes;
+use crate::planets::PlanetType;
+use crate::renderer::{FrameSettings, Renderer, Scene, Viewport};
+
+// Con 90° de campo de visión y caras cuadradas, las seis vistas cubren todo sin solaparse
+const FACE_FOV: f32 = PI / 2.0;
+
+// La cola del cometa se simula este tiempo antes del instante pedido para que
+// tenga partículas (viven menos de 2.5 s)
+const TAIL_WARMUP: f32 = 3.0;
+const SIMULATION_DT: f32 = 1.0 / 30.0;
+
+/// One face of the cube: the camera looks along `forward` with `up` at the top
+/// of the image. Side faces keep +Y up; the top and bottom faces have -Z and +Z
+/// at the top, the usual layout of skybox images.
+#[derive(Clone, Copy, Debug)]
+pub struct CubeFace {
+    pub name: &'static str,
+    pub forward: Vector3,
+    pub up: Vector3,
+}
+
+pub const FACES: [CubeFace; 6] = [
+    CubeFace { name: "px", forward: Vector3::new(1.0, 0.0, 0.0), up: Vector3::new(0.0, 1.0, 0.0) },
+    CubeFace { name: "nx", forward: Vector3::new(-1.0, 0.0, 0.0), up: Vector3::new(0.0, 1.0, 0.0) },
+    CubeFace { name: "py", forward: Vector3::new(0.0, 1.0, 0.0), up: Vector3::new(0.0, 0.0, -1.0) },
+    CubeFace { name: "ny", forward: Vector3::new(0.0, -1.0, 0.0), up: Vector3::new(0.0, 0.0, 1.0) },
+    CubeFace { name: "pz", forward: Vector3::new(0.0, 0.0, 1.0), up: Vector3::new(0.0, 1.0, 0.0) },
+    CubeFace { name: "nz", forward: Vector3::new(0.0, 0.0, -1.0), up: Vector3::new(0.0, 1.0, 0.0) },
+];
+
+impl CubeFace {
+    /// Right-hand side of the face's image (same as the view matrix's right axis)
+    pub fn right(&self) -> Vector3 {
+        self.forward.cross(self.up)
+    }
+
+    pub fn camera(&self, origin: Vector3) -> Camera {
+        Camera::new(origin, origin + self.forward, self.up)
+    }
+}
+
+/// Face index and pixel of a `size` x `size` face that `direction` falls on
+pub fn face_texel(direction: Vector3, size: i32) -> (usize, i32, i32) {
+    // La cara es la del eje en que la dirección avanza más
+    let (index, face) = FACES
+        .iter()
+        .enumerate()
+        .max_by(|a, b| direction.dot(a.1.forward).total_cmp(&direction.dot(b.1.forward)))
+        .unwrap();
+
+    // Proyección perspectiva a 90°: coordenadas de -1 a 1 sobre el plano de la cara
+    let depth = direction.dot(face.forward);
+    let ndc_x = direction.dot(face.right()) / depth;
+    let ndc_y = direction.dot(face.up) / depth;
+    let to_pixel = |ndc: f32| ((ndc + 1.0) * 0.5 * size as f32).floor().clamp(0.0, size as f32 - 1.0) as i32;
+    (index, to_pixel(ndc_x), to_pixel(-ndc_y))
+}
+
+/// Unit direction through the center of pixel (`x`, `y`) of a `width` x `height`
+/// panorama: the center column looks down -Z, +X is a quarter to the right and
+/// the top row is straight up
+pub fn panorama_direction(x: i32, y: i32, width: i32, height: i32) -> Vector3 {
+    let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+    let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * PI;
+    Vector3::new(
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+        -latitude.cos() * longitude.cos(),
+    )
+}
+
+/// Renders the six faces seen from `origin` at `options.time` and writes them,
+/// plus a 4:2 equirectangular panorama stitched from them, into `options.output_dir`
+pub fn run(options: &Options, origin: Vector3, light: &Light, meshes: &SceneMeshes, planet: &PlanetType) -> io::Result<()> {
+    fs::create_dir_all(&options.output_dir)?;
+
+    let time = options.time;
+    let mut comet = Comet::new();
+    let mut simulated = (time - TAIL_WARMUP).max(0.0);
+    while simulated < time {
+        let dt = SIMULATION_DT.min(time - simulated);
+        simulated += dt;
+        comet.update(simulated, dt, &light.at(simulated));
+    }
+    let light = &light.at(time);
+    let mut belt = AsteroidBelt::new(7);
+    belt.update(time);
+    let scene = Scene { meshes, planet, comet: &comet, belt: &belt, fade_from: None };
+
+    // Sin post-proceso ni destello: el bloom, el FXAA y los efectos de lente de cada
+    // cara dejarían costuras en los bordes
+    let frame = FrameSettings {
+        time,
+        dt: SIMULATION_DT,
+        debug_view: options.debug_view,
+        render_mode: options.render_mode,
+        shading: options.shading,
+        projection: Projection::Perspective,
+        depth_mode: options.depth_mode,
+        depth_overlay: false,
+        selected: None,
+        gizmos: Gizmos::NONE,
+        fov_y: FACE_FOV,
+        previous_view_projection: None,
+        lens_flare: false,
+        msaa: options.msaa,
+        viewport: Viewport::FULL,
+    };
+    let size = options.face_size;
+    let mut renderer = Renderer::new(size, size, options.ssaa, options.display);
+
+    let mut faces = Vec::with_capacity(FACES.len());
+    for face in &FACES {
+        let camera = face.camera(origin);
+        renderer.render(&camera, light, &scene, &frame);
+
+        let path = Path::new(&options.output_dir).join(format!("{}.png", face.name));
+        renderer.framebuffer.save(&path.to_string_lossy())?;
+        println!("Rendered {}", path.display());
+        faces.push(renderer.framebuffer.pixels());
+    }
+
+    // Panorama: cada píxel toma el color del texel del cubo en su dirección
+    let (width, height) = (size * 4, size * 2);
+    let mut panorama = Framebuffer::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let (face, face_x, face_y) = face_texel(panorama_direction(x, y, width, height), size);
+            panorama.set_current_color(faces[face][(face_y * size + face_x) as usize]);
+            panorama.set_pixel(x, y);
+        }
+    }
+    let path = Path::new(&options.output_dir).join("panorama.png");
+    panorama.save(&path.to_string_lossy())?;
+    println!("Rendered {}", path.display());
+
+    Ok(())
+}
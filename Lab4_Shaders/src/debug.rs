@@ -0,0 +1,96 @@
+// debug.rs
+// Vistas de depuración que reemplazan el color sombreado (V cambia entre ellas)
+use crate::math::*;
+use crate::fragment::Fragment;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DebugView {
+    #[default]
+    Shaded,      // salida normal de los shaders
+    Depth,       // depth buffer en escala de grises (cerca = claro)
+    Normals,     // normal interpolada como RGB
+    Wireframe,   // bordes de cada triángulo
+    UvChecker,   // tablero de ajedrez con las coordenadas UV
+    TriangleIds, // un color plano distinto por triángulo
+}
+
+// Grosor de los bordes en la vista wireframe, como peso baricéntrico mínimo
+const WIREFRAME_EDGE: f32 = 0.04;
+const CHECKER_TILES: Vector2 = Vector2::new(16.0, 8.0);
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Shaded => DebugView::Depth,
+            DebugView::Depth => DebugView::Normals,
+            DebugView::Normals => DebugView::Wireframe,
+            DebugView::Wireframe => DebugView::UvChecker,
+            DebugView::UvChecker => DebugView::TriangleIds,
+            DebugView::TriangleIds => DebugView::Shaded,
+        }
+    }
+
+    /// Debug color for a fragment, or None when the regular shader should run.
+    /// `mesh_id` tells the bodies apart so their triangle IDs get different colors.
+    /// The depth view is drawn from the depth buffer afterwards, so here it only
+    /// needs something to be written.
+    pub fn shade(self, fragment: &Fragment, mesh_id: i32) -> Option<Vector3> {
+        match self {
+            DebugView::Shaded => None,
+            DebugView::Depth => Some(Vector3::one()),
+            DebugView::Normals => Some(Vector3::new(
+                fragment.normal.x * 0.5 + 0.5,
+                fragment.normal.y * 0.5 + 0.5,
+                fragment.normal.z * 0.5 + 0.5,
+            )),
+            DebugView::Wireframe => {
+                let b = fragment.barycentric;
+                if b.x.min(b.y).min(b.z) < WIREFRAME_EDGE {
+                    Some(Vector3::new(0.9, 0.9, 0.9))
+                } else {
+                    Some(Vector3::new(0.05, 0.05, 0.08))
+                }
+            }
+            DebugView::UvChecker => {
+                let u = (fragment.tex_coords.x * CHECKER_TILES.x).floor() as i32;
+                let v = (fragment.tex_coords.y * CHECKER_TILES.y).floor() as i32;
+                if (u + v) % 2 == 0 {
+                    Some(Vector3::new(0.9, 0.9, 0.9))
+                } else {
+                    Some(Vector3::new(0.8, 0.2, 0.6))
+                }
+            }
+            DebugView::TriangleIds => Some(id_color(fragment.triangle_id, mesh_id)),
+        }
+    }
+}
+
+impl FromStr for DebugView {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "shaded" => Ok(DebugView::Shaded),
+            "depth" => Ok(DebugView::Depth),
+            "normals" => Ok(DebugView::Normals),
+            "wireframe" => Ok(DebugView::Wireframe),
+            "uv" => Ok(DebugView::UvChecker),
+            "ids" => Ok(DebugView::TriangleIds),
+            _ => Err(format!("invalid debug view: {} (expected shaded, depth, normals, wireframe, uv or ids)", name)),
+        }
+    }
+}
+
+/// Stable pseudo-random color for a triangle index
+fn id_color(triangle_id: usize, mesh_id: i32) -> Vector3 {
+    let mut hash = (triangle_id as u32).wrapping_mul(2654435761) ^ (mesh_id as u32).wrapping_mul(40503);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(2246822519);
+    hash ^= hash >> 13;
+    Vector3::new(
+        (hash & 0xff) as f32 / 255.0,
+        ((hash >> 8) & 0xff) as f32 / 255.0,
+        ((hash >> 16) & 0xff) as f32 / 255.0,
+    )
+}
@@ -0,0 +1,90 @@
+// demo.rs
+// Grabación y reproducción de recorridos: por cada frame se guarda el estado
+// que producen las entradas (pose de la cámara, tiempo del reloj y planeta), no
+// las teclas, así la reproducción es exacta aunque cambie la velocidad de la
+// máquina. Sirve para volver a renderizar el mismo recorrido tras cambiar un
+// shader (en ventana con --replay o sin ella con --headless --replay).
+use crate::math::*;
+use std::fs;
+use std::path::Path;
+use crate::camera::Camera;
+
+const HEADER: &str = "# demo v1: time dt eye.x eye.y eye.z target.x target.y target.z up.x up.y up.z planet";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DemoFrame {
+    pub time: f32, // tiempo de simulación del frame
+    pub dt: f32,   // paso de simulación (negativo si el tiempo iba hacia atrás)
+    pub eye: Vector3,
+    pub target: Vector3,
+    pub up: Vector3,
+    pub planet: usize, // índice del preset activo
+}
+
+impl DemoFrame {
+    pub fn new(time: f32, dt: f32, camera: &Camera, planet: usize) -> Self {
+        DemoFrame { time, dt, eye: camera.eye, target: camera.target, up: camera.up, planet }
+    }
+
+    /// A camera with this frame's pose
+    pub fn camera(&self) -> Camera {
+        Camera::new(self.eye, self.target, self.up)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Demo {
+    pub frames: Vec<DemoFrame>,
+}
+
+impl Demo {
+    /// One line per frame; `{}` prints the shortest text that reads back as the
+    /// same f32, so a saved demo replays bit for bit
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(HEADER);
+        text.push('\n');
+        for frame in &self.frames {
+            let (e, t, u) = (frame.eye, frame.target, frame.up);
+            text.push_str(&format!(
+                "{} {} {} {} {} {} {} {} {} {} {} {}\n",
+                frame.time, frame.dt, e.x, e.y, e.z, t.x, t.y, t.z, u.x, u.y, u.z, frame.planet
+            ));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Demo, String> {
+        let mut frames = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || format!("line {}: expected 11 numbers and a planet index", number + 1);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 12 {
+                return Err(invalid());
+            }
+            let values = fields[..11].iter().map(|field| field.parse::<f32>()).collect::<Result<Vec<f32>, _>>().map_err(|_| invalid())?;
+            let planet = fields[11].parse().map_err(|_| invalid())?;
+            frames.push(DemoFrame {
+                time: values[0],
+                dt: values[1],
+                eye: Vector3::new(values[2], values[3], values[4]),
+                target: Vector3::new(values[5], values[6], values[7]),
+                up: Vector3::new(values[8], values[9], values[10]),
+                planet,
+            });
+        }
+        Ok(Demo { frames })
+    }
+
+    pub fn load(path: &Path) -> Result<Demo, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        Demo::parse(&text).map_err(|message| format!("{}: {}", path.display(), message))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+}
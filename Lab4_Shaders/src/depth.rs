@@ -0,0 +1,165 @@
+// depth.rs
+// Cómo se guarda la profundidad. En la convención estándar (la de OpenGL) el plano
+// cercano va a -1 y el lejano a 1, y casi toda la resolución del float se gasta junto a
+// la cámara: los cuerpos lejanos se pelean por los mismos valores (z-fighting). Con
+// reverse-Z el plano cercano va a 1 y el infinito a 0, y como los floats son más densos
+// cerca de 0 la resolución queda pareja en toda la escena. J muestra esa distribución.
+use crate::math::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{multiply_matrix_vector4, view_depth};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Mapping from view distance to stored depth, chosen per frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DepthMode {
+    #[default]
+    Standard, // cerca = -1, lejos = 1: gana la profundidad menor
+    ReverseZ, // cerca = 1, infinito = 0: gana la profundidad mayor
+}
+
+impl DepthMode {
+    pub fn toggle(self) -> Self {
+        match self {
+            DepthMode::Standard => DepthMode::ReverseZ,
+            DepthMode::ReverseZ => DepthMode::Standard,
+        }
+    }
+
+    /// Whether a surface at depth `a` is in front of one at depth `b`
+    pub fn nearer(self, a: f32, b: f32) -> bool {
+        match self {
+            DepthMode::Standard => a < b,
+            DepthMode::ReverseZ => a > b,
+        }
+    }
+
+    /// The nearer of two depths
+    pub fn nearest(self, a: f32, b: f32) -> f32 {
+        if self.nearer(b, a) { b } else { a }
+    }
+
+    /// Depth of a pixel nothing was drawn to: behind every surface and not finite,
+    /// so `is_finite` tells covered pixels apart in either mode
+    pub fn cleared(self) -> f32 {
+        match self {
+            DepthMode::Standard => f32::INFINITY,
+            DepthMode::ReverseZ => f32::NEG_INFINITY,
+        }
+    }
+
+    /// Depth in front of every surface, for glows that nothing may cover
+    pub fn frontmost(self) -> f32 {
+        -self.cleared()
+    }
+
+    /// NDC depths between the near and the far plane; a triangle with a vertex
+    /// outside is dropped
+    pub fn ndc_range(self) -> RangeInclusive<f32> {
+        match self {
+            DepthMode::Standard => -1.0..=1.0,
+            DepthMode::ReverseZ => 0.0..=1.0,
+        }
+    }
+}
+
+impl FromStr for DepthMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "standard" => Ok(DepthMode::Standard),
+            "reverse-z" => Ok(DepthMode::ReverseZ),
+            _ => Err(format!("invalid depth mode: {} (expected standard or reverse-z)", name)),
+        }
+    }
+}
+
+/// Smallest change in distance the depth buffer can record for a surface `distance`
+/// in front of a camera with `projection`: one float step of the stored depth,
+/// converted back to a distance
+pub fn depth_resolution(distance: f32, projection: &Matrix) -> f32 {
+    let clip = multiply_matrix_vector4(projection, &Vector4::new(0.0, 0.0, -distance, 1.0));
+    let ndc = clip.z / clip.w;
+    let step = f32::from_bits(ndc.abs().to_bits() + 1) - ndc.abs();
+    // Se deshace la proyección en f64 para que el error no sea el del propio cálculo
+    let p = projection;
+    let distance_at = |ndc: f32| {
+        let ndc = ndc as f64;
+        -(p.m14 as f64 - ndc * p.m15 as f64) / (ndc * p.m11 as f64 - p.m10 as f64)
+    };
+    ((distance_at(ndc + step) - distance_at(ndc - step)).abs() / 2.0) as f32
+}
+
+// Panel de precisión, abajo a la derecha: distancia en escala logarítmica de izquierda
+// a derecha y error relativo (resolución / distancia) en potencias de 10 hacia arriba
+const PANEL_WIDTH: i32 = 200;
+const PANEL_HEIGHT: i32 = 90;
+const PANEL_MARGIN: i32 = 10;
+const ERROR_EXPONENTS: (f32, f32) = (-9.0, -2.0);
+
+const PANEL_COLOR: Color = Color::new(20, 10, 35, 255);
+const HISTOGRAM_COLOR: Color = Color::new(90, 80, 120, 255);
+const ACTIVE_COLOR: Color = Color::new(120, 255, 150, 255);
+const OTHER_COLOR: Color = Color::new(200, 90, 90, 255);
+
+/// Draws the depth-precision panel over the finished frame: gray bars are how many
+/// pixels show a surface at each distance between `near` and `far`, the bright
+/// curve is the relative depth resolution with `projection` (the one the frame was
+/// rendered with) and the dim one the same with `other`, the other depth mode.
+/// Only the part of the frame inside the scissor is counted and the panel goes in
+/// its corner, so each half of a split screen can have its own.
+pub fn draw_depth_precision(framebuffer: &mut Framebuffer, projection: &Matrix, other: &Matrix, near: f32, far: f32) {
+    let view = framebuffer.scissor();
+    let left = view.x1 - PANEL_WIDTH - PANEL_MARGIN;
+    let top = view.y1 - PANEL_HEIGHT - PANEL_MARGIN;
+    let span = (far / near).ln();
+    let column_distance = |column: i32| near * (span * (column as f32 + 0.5) / PANEL_WIDTH as f32).exp();
+
+    // Cuántos píxeles hay a cada distancia (misma escala logarítmica que el eje)
+    let mut histogram = vec![0u32; PANEL_WIDTH as usize];
+    let (depth, width) = (framebuffer.depth(), framebuffer.width);
+    let depths = (view.y0..view.y1).flat_map(|y| (view.x0..view.x1).map(move |x| depth[(y * width + x) as usize]));
+    for depth in depths.filter(|depth| depth.is_finite()) {
+        let t = (view_depth(depth, projection) / near).ln() / span;
+        if (0.0..1.0).contains(&t) {
+            histogram[(t * PANEL_WIDTH as f32) as usize] += 1;
+        }
+    }
+    let tallest = histogram.iter().copied().max().unwrap_or(0).max(1);
+
+    // Fondo oscurecido y barras del histograma
+    for column in 0..PANEL_WIDTH {
+        let bar = (histogram[column as usize] as f32 / tallest as f32 * PANEL_HEIGHT as f32).round() as i32;
+        for row in 0..PANEL_HEIGHT {
+            let (x, y) = (left + column, top + row);
+            let color = if row >= PANEL_HEIGHT - bar {
+                HISTOGRAM_COLOR
+            } else {
+                let Some(behind) = framebuffer.get_pixel_color(x, y) else { continue };
+                let mix = |a: u8, b: u8| ((a as u16 + b as u16 * 4) / 5) as u8;
+                Color::new(mix(behind.r, PANEL_COLOR.r), mix(behind.g, PANEL_COLOR.g), mix(behind.b, PANEL_COLOR.b), 255)
+            };
+            framebuffer.set_current_color(color);
+            framebuffer.set_pixel(x, y);
+        }
+    }
+
+    // Curvas de resolución; el modo activo va encima
+    let (lowest, highest) = ERROR_EXPONENTS;
+    for (matrix, color) in [(other, OTHER_COLOR), (projection, ACTIVE_COLOR)] {
+        framebuffer.set_current_color(color);
+        let mut previous = None;
+        for column in 0..PANEL_WIDTH {
+            let distance = column_distance(column);
+            let exponent = (depth_resolution(distance, matrix) / distance).log10().clamp(lowest, highest);
+            let row = ((highest - exponent) / (highest - lowest) * (PANEL_HEIGHT - 1) as f32).round() as i32;
+            // Se une con la columna anterior para que los saltos no dejen huecos
+            let (from, to) = previous.map_or((row, row), |previous: i32| (previous.min(row), previous.max(row)));
+            for y in from..=to {
+                framebuffer.set_pixel(left + column, top + y);
+            }
+            previous = Some(row);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// display.rs
+// Paso de presentación: el renderizador solo llena un Framebuffer y cada backend
+// decide cómo llega a la pantalla (la ventana de raylib, la terminal o un canvas en la web)
+#[cfg(feature = "native")]
+use raylib::prelude::*;
+use std::io;
+use std::str::FromStr;
+use crate::framebuffer::Framebuffer;
+
+/// Where frames are shown, chosen with `--backend`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Backend {
+    #[default]
+    Window,   // ventana de raylib, con panel de parámetros y HUD
+    Terminal, // caracteres de colores en la terminal (crossterm)
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "window" | "raylib" => Ok(Backend::Window),
+            "terminal" => Ok(Backend::Terminal),
+            _ => Err(format!("invalid backend: {} (expected window or terminal)", name)),
+        }
+    }
+}
+
+/// Shows finished frames somewhere
+pub trait Presenter {
+    /// Size in pixels a frame should have to fill the output right now
+    fn size(&self) -> (i32, i32);
+
+    /// Shows the color buffer of `framebuffer` with `status` lines on top (mutable
+    /// because raylib only reads image pixels through `&mut`)
+    fn present(&mut self, framebuffer: &mut Framebuffer, status: &[String]) -> io::Result<()>;
+}
+
+/// The raylib window as a presentation backend
+#[cfg(feature = "native")]
+pub struct WindowPresenter<'a> {
+    pub window: &'a mut RaylibHandle,
+    pub thread: &'a RaylibThread,
+}
+
+#[cfg(feature = "native")]
+impl WindowPresenter<'_> {
+    /// Uploads the framebuffer to a texture and draws it to the window, then lets
+    /// `draw_overlay` draw 2D elements (UI, HUD) on top of it
+    pub fn present_with_overlay<F>(&mut self, framebuffer: &Framebuffer, draw_overlay: F)
+    where
+        F: FnOnce(&mut RaylibDrawHandle),
+    {
+        if let Ok(texture) = self.window.load_texture_from_image(self.thread, &framebuffer.color_buffer) {
+            let mut d = self.window.begin_drawing(self.thread);
+            d.clear_background(framebuffer.background_color());
+            d.draw_texture(&texture, 0, 0, Color::WHITE);
+            draw_overlay(&mut d);
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Presenter for WindowPresenter<'_> {
+    fn size(&self) -> (i32, i32) {
+        (self.window.get_screen_width(), self.window.get_screen_height())
+    }
+
+    fn present(&mut self, framebuffer: &mut Framebuffer, status: &[String]) -> io::Result<()> {
+        self.present_with_overlay(framebuffer, |d| {
+            for (i, line) in status.iter().enumerate() {
+                d.draw_text(line, 10, 10 + i as i32 * 18, 14, Color::WHITE);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// events.rs
+// Eventos astronómicos: detecta cuándo alguna luna tapa al planeta visto desde el
+// sol (eclipse: su sombra cae sobre el planeta) o visto desde la cámara
+// (tránsito), para anotarlos en pantalla y, si se pide, ir en cámara lenta.
+use crate::math::*;
+use crate::light::Light;
+use crate::moons::Moon;
+use crate::picking::Body;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Eclipse, // la sombra de una luna cae sobre el planeta
+    Transit, // una luna pasa por delante del planeta vista desde la cámara
+}
+
+impl Event {
+    pub fn description(self) -> &'static str {
+        match self {
+            Event::Eclipse => "Eclipse: la sombra de una luna cae sobre el planeta",
+            Event::Transit => "Transito: una luna pasa frente al planeta",
+        }
+    }
+}
+
+/// Whether the sphere (`near`, `near_radius`) covers part of (`far`, `far_radius`)
+/// as seen from `viewer`: it must be closer and their discs must overlap
+pub fn occults(viewer: Vector3, near: Vector3, near_radius: f32, far: Vector3, far_radius: f32) -> bool {
+    let (to_near, to_far) = (near - viewer, far - viewer);
+    let (near_distance, far_distance) = (to_near.length(), to_far.length());
+    if near_distance <= near_radius || far_distance <= far_radius || near_distance >= far_distance {
+        return false;
+    }
+
+    // Separación angular entre los centros contra la suma de los radios angulares
+    let separation = (to_near.dot(to_far) / (near_distance * far_distance)).clamp(-1.0, 1.0).acos();
+    separation < (near_radius / near_distance).asin() + (far_radius / far_distance).asin()
+}
+
+/// Tracks which events are happening, to report the moment each one starts
+#[derive(Default)]
+pub struct EventDetector {
+    active: Vec<Event>,
+}
+
+impl EventDetector {
+    /// Checks the bodies at `time` and returns the events that began this frame;
+    /// an event lasts while any of `moons` causes it
+    pub fn update(&mut self, time: f32, light: &Light, camera_position: Vector3, moons: &[Moon]) -> Vec<Event> {
+        let (planet, planet_radius) = Body::Planet.sphere(time, light, moons);
+        let occulted_from = |viewer: Vector3| {
+            (0..moons.len()).any(|index| {
+                let (moon, moon_radius) = Body::Moon(index).sphere(time, light, moons);
+                occults(viewer, moon, moon_radius, planet, planet_radius)
+            })
+        };
+
+        let happening = [
+            (Event::Eclipse, occulted_from(light.position)),
+            (Event::Transit, occulted_from(camera_position)),
+        ];
+        let started = happening
+            .iter()
+            .filter(|(event, now)| *now && !self.active.contains(event))
+            .map(|(event, _)| *event)
+            .collect();
+        self.active = happening.iter().filter(|(_, now)| *now).map(|(event, _)| *event).collect();
+        started
+    }
+
+    pub fn active(&self) -> &[Event] {
+        &self.active
+    }
+}
@@ -0,0 +1,132 @@
+// flare.rs
+// Destello de lente: cuando el sol está en pantalla y nada lo tapa, se suman
+// "fantasmas" (reflejos entre las lentes de la cámara) sobre la recta que va del
+// sol al centro de la pantalla, más un trazo de luz a lo largo de esa recta. Todo
+// se escala con la fracción visible del disco del sol, medida en el depth buffer.
+use crate::math::*;
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::GlobalUniforms;
+
+// Fantasmas: posición sobre la recta (0 = sol, 1 = centro de la pantalla, >1 del
+// otro lado), radio como fracción del alto de la pantalla y color
+const GHOSTS: [(f32, f32, Vector3); 6] = [
+    (0.35, 0.03, Vector3::new(0.9, 0.6, 0.3)),
+    (0.6, 0.06, Vector3::new(0.4, 0.7, 1.0)),
+    (0.85, 0.02, Vector3::new(1.0, 0.9, 0.6)),
+    (1.3, 0.09, Vector3::new(0.5, 1.0, 0.6)),
+    (1.7, 0.05, Vector3::new(0.9, 0.4, 0.9)),
+    (2.0, 0.12, Vector3::new(0.3, 0.5, 1.0)),
+];
+const GHOST_INTENSITY: f32 = 0.25;
+
+const STREAK_COLOR: Vector3 = Vector3::new(1.0, 0.85, 0.6);
+const STREAK_INTENSITY: f32 = 0.6;
+const STREAK_LENGTH: f32 = 0.6;    // largo hacia cada lado, como fracción del ancho de la pantalla
+const STREAK_THICKNESS: i32 = 1;   // semiancho del trazo en píxeles
+
+// Rejilla de muestras sobre el disco del sol para medir cuánto se ve
+const VISIBILITY_GRID: i32 = 5;
+
+/// Screen position of the sun, or None when it is behind the camera
+fn sun_on_screen(globals: &GlobalUniforms, center: Vector3) -> Option<Vector2> {
+    globals.project(center).map(|screen| Vector2::new(screen.x, screen.y))
+}
+
+/// Fraction (0..1) of the sun's disc that can be seen: samples spread over the
+/// disc count when they are on screen and nothing nearer than the sun's front
+/// was drawn there
+pub fn sun_visibility(framebuffer: &Framebuffer, globals: &GlobalUniforms, center: Vector3, radius: f32) -> f32 {
+    let Some(screen_center) = globals.project(center) else {
+        return 0.0;
+    };
+    // Profundidad del punto del sol más cercano a la cámara: cualquier cosa por
+    // delante de él lo tapa; su propia superficie queda detrás
+    let Some(front) = globals.project(center + (globals.camera_position - center).normalized() * radius) else {
+        return 0.0;
+    };
+    let view = &globals.view_matrix;
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let Some(screen_edge) = globals.project(center + right * radius) else {
+        return 0.0;
+    };
+    let screen_radius = (screen_edge.x - screen_center.x).hypot(screen_edge.y - screen_center.y).max(0.5);
+
+    let depth = framebuffer.depth();
+    let (mut samples, mut visible) = (0, 0);
+    for j in 0..VISIBILITY_GRID {
+        for i in 0..VISIBILITY_GRID {
+            let u = (i as f32 + 0.5) / VISIBILITY_GRID as f32 * 2.0 - 1.0;
+            let v = (j as f32 + 0.5) / VISIBILITY_GRID as f32 * 2.0 - 1.0;
+            if u * u + v * v > 1.0 {
+                continue;
+            }
+            samples += 1;
+            let x = (screen_center.x + u * screen_radius).floor() as i32;
+            let y = (screen_center.y + v * screen_radius).floor() as i32;
+            if x < 0 || x >= framebuffer.width || y < 0 || y >= framebuffer.height {
+                continue;
+            }
+            let stored = depth[(y * framebuffer.width + x) as usize];
+            if !(framebuffer.depth_mode.nearer(stored, front.z) && (stored - front.z).abs() > 1e-5) {
+                visible += 1;
+            }
+        }
+    }
+    visible as f32 / samples as f32
+}
+
+/// Centers of the ghosts for a sun at `sun` on a `width` x `height` screen: all
+/// on the line from the sun through the screen center
+pub fn ghost_positions(sun: Vector2, width: i32, height: i32) -> Vec<Vector2> {
+    let center = Vector2::new(width as f32 / 2.0, height as f32 / 2.0);
+    GHOSTS.iter().map(|(t, _, _)| sun + (center - sun) * *t).collect()
+}
+
+/// Adds the lens flare of a sun at `center` with radius `radius` on top of the
+/// whole frame; call it once everything else is drawn so the depth buffer is complete
+pub fn draw_lens_flare(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, center: Vector3, radius: f32) {
+    let Some(sun) = sun_on_screen(globals, center) else {
+        return;
+    };
+    let visibility = sun_visibility(framebuffer, globals, center, radius);
+    if visibility <= 0.0 {
+        return;
+    }
+    let (width, height) = (framebuffer.width, framebuffer.height);
+
+    // Fantasmas: discos suaves que se apagan hacia el borde
+    for (position, (_, size, color)) in ghost_positions(sun, width, height).into_iter().zip(GHOSTS) {
+        let ghost_radius = size * height as f32;
+        let min_x = (position.x - ghost_radius).floor().max(0.0) as i32;
+        let max_x = (position.x + ghost_radius).ceil().min(width as f32 - 1.0) as i32;
+        let min_y = (position.y - ghost_radius).floor().max(0.0) as i32;
+        let max_y = (position.y + ghost_radius).ceil().min(height as f32 - 1.0) as i32;
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let distance = (x as f32 + 0.5 - position.x).hypot(y as f32 + 0.5 - position.y) / ghost_radius;
+                if distance < 1.0 {
+                    let falloff = (1.0 - distance) * (1.0 - distance);
+                    // Profundidad -inf: el destello está en el lente, delante de todo
+                    framebuffer.add_glow(x, y, color * (GHOST_INTENSITY * falloff * visibility), framebuffer.depth_mode.frontmost());
+                }
+            }
+        }
+    }
+
+    // Trazo por el sol en la dirección del centro de la pantalla (horizontal si el sol está en el centro)
+    let screen_center = Vector2::new(width as f32 / 2.0, height as f32 / 2.0);
+    let to_center = screen_center - sun;
+    let direction = if to_center.length() > 1.0 { to_center / to_center.length() } else { Vector2::new(1.0, 0.0) };
+    let normal = Vector2::new(-direction.y, direction.x);
+    let length = STREAK_LENGTH * width as f32;
+    let mut step = -length;
+    while step <= length {
+        let strength = (-3.0 * step.abs() / length).exp() * STREAK_INTENSITY * visibility;
+        for offset in -STREAK_THICKNESS..=STREAK_THICKNESS {
+            let across = 1.0 - offset.abs() as f32 / (STREAK_THICKNESS + 1) as f32;
+            let point = sun + direction * step + normal * offset as f32;
+            framebuffer.add_glow(point.x.floor() as i32, point.y.floor() as i32, STREAK_COLOR * (strength * across), framebuffer.depth_mode.frontmost());
+        }
+        step += 1.0;
+    }
+}
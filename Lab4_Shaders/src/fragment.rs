@@ -1,11 +1,29 @@
 // fragment.rs
-use raylib::prelude::Vector3;
+use crate::math::{Vector2, Vector3};
 
+// MSAA 4x: posiciones de las muestras respecto al centro del píxel, en fracciones de
+// píxel (patrón de cuadrícula rotada, el estándar de 4x)
+pub const MSAA_SAMPLES: usize = 4;
+pub const MSAA_OFFSETS: [(f32, f32); MSAA_SAMPLES] = [(-0.125, -0.375), (0.375, -0.125), (-0.375, 0.125), (0.125, 0.375)];
+/// Coverage mask with every sample set: what a single-sampled fragment covers
+pub const FULL_COVERAGE: u8 = (1 << MSAA_SAMPLES) - 1;
+
+/// One sample of a triangle (or line) with the vertex attributes interpolated
+/// at its position; everything but depth, barycentric and velocity is corrected
+/// for perspective
 pub struct Fragment {
     pub position: Vector3, // screen position
-    pub color: Vector3,
+    pub color: Vector3,    // color de vértice interpolado
+    pub light: Vector3,    // luz de los vértices interpolada (Flat y Gouraud, ver Vertex::light)
     pub depth: f32,
-    pub world_position: Vector3,
+    pub world_position: Vector3, // posición en el espacio del modelo (antes de la matriz de modelo)
+    pub normal: Vector3,      // normal en el mundo interpolada (normalizada)
+    pub tex_coords: Vector2,  // UV interpoladas
+    pub barycentric: Vector3, // pesos de los tres vértices del triángulo
+    pub triangle_id: usize,   // índice del triángulo dentro de su malla
+    pub velocity: Vector2,    // desplazamiento en pantalla desde el frame anterior (pixeles)
+    pub coverage: u8,         // bit i: la muestra MSAA_OFFSETS[i] cae dentro del triángulo
+    pub depth_slope: Vector2, // cambio de la profundidad por píxel en x e y (profundidad de cada muestra)
 }
 
 impl Fragment {
@@ -13,8 +31,16 @@ impl Fragment {
         Fragment {
             position: Vector3::new(x, y, depth), // La z se actualiza con depth
             color,
+            light: Vector3::new(0.0, 0.0, 0.0),
             depth,
             world_position,
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            tex_coords: Vector2::new(0.0, 0.0),
+            barycentric: Vector3::new(0.0, 0.0, 0.0),
+            triangle_id: 0,
+            velocity: Vector2::new(0.0, 0.0),
+            coverage: FULL_COVERAGE,
+            depth_slope: Vector2::new(0.0, 0.0),
         }
     }
 }
\ No newline at end of file
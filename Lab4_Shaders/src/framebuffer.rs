@@ -1,58 +1,292 @@
 // framebuffer.rs
-use raylib::prelude::*;
+use crate::math::*;
+#[cfg(feature = "native")]
+use std::io;
+use crate::depth::DepthMode;
+use crate::fragment::{FULL_COVERAGE, MSAA_OFFSETS, MSAA_SAMPLES};
+use crate::tiles::Tile;
+use crate::tonemap::DisplayTransform;
+
+/// Outcome of writing a fragment with `Framebuffer::point`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthTest {
+    Passed,
+    Rejected,
+    OutOfBounds,
+}
 
 pub struct Framebuffer {
     pub width: i32,
     pub height: i32,
-    pub color_buffer: Image,
+    pub color_buffer: Image,        // colores de pantalla (8 bits), se llena en resolve()
+    pub display: DisplayTransform,  // tone mapping y gamma usados en resolve()
+    pub depth_mode: DepthMode,      // convención de profundidad: qué valor está más cerca y con cuál se limpia
+    hdr_buffer: Vec<Vector3>,       // color lineal sin recortar que escriben los shaders
+    glow_buffer: Vec<Vector3>,      // luz aditiva (p. ej. la corona del sol) que se suma al resolver
     background_color: Color,
     current_color: Color,
     depth_buffer: Vec<f32>,
+    velocity_buffer: Vec<Vector2>, // movimiento en pantalla de la superficie visible (para el motion blur)
+    // Con MSAA cada píxel guarda la profundidad y el color de sus cuatro muestras y
+    // resolve() los promedia; vacíos cuando está desactivado
+    sample_depth: Vec<[f32; MSAA_SAMPLES]>,
+    sample_color: Vec<[Vector3; MSAA_SAMPLES]>,
+    // Rectángulo donde se escribe (toda la imagen salvo en pantalla dividida): clear(),
+    // las escrituras y resolve() no tocan lo que queda afuera
+    scissor: Tile,
+}
+
+/// Depth of MSAA sample `sample` of a fragment with center depth `depth`
+fn sample_depth(depth: f32, depth_slope: Vector2, sample: usize) -> f32 {
+    let (offset_x, offset_y) = MSAA_OFFSETS[sample];
+    depth + depth_slope.x * offset_x + depth_slope.y * offset_y
 }
 
 impl Framebuffer {
     pub fn new(width: i32, height: i32) -> Self {
         let background_color = Color::BLACK; // Un color por defecto
         let color_buffer = Image::gen_image_color(width, height, background_color);
-        let depth_buffer = vec![f32::INFINITY; (width * height) as usize];
+        let depth_mode = DepthMode::default();
+        let depth_buffer = vec![depth_mode.cleared(); (width * height) as usize];
         Framebuffer {
             width,
             height,
             color_buffer,
+            display: DisplayTransform::default(),
+            depth_mode,
+            hdr_buffer: vec![Vector3::zero(); (width * height) as usize],
+            glow_buffer: vec![Vector3::zero(); (width * height) as usize],
             background_color,
             current_color: Color::WHITE,
             depth_buffer,
+            velocity_buffer: vec![Vector2::zero(); (width * height) as usize],
+            sample_depth: Vec::new(),
+            sample_color: Vec::new(),
+            scissor: Tile { x0: 0, y0: 0, x1: width, y1: height },
         }
     }
 
+    /// Limits clearing, writing and resolving to `rect` (clamped to the image);
+    /// None goes back to the whole image
+    pub fn set_scissor(&mut self, rect: Option<Tile>) {
+        let full = Tile { x0: 0, y0: 0, x1: self.width, y1: self.height };
+        self.scissor = match rect {
+            Some(rect) => Tile { x0: rect.x0.max(0), y0: rect.y0.max(0), x1: rect.x1.min(self.width), y1: rect.y1.min(self.height) },
+            None => full,
+        };
+    }
+
+    pub fn scissor(&self) -> Tile {
+        self.scissor
+    }
+
+    fn scissored(&self) -> bool {
+        self.scissor != Tile { x0: 0, y0: 0, x1: self.width, y1: self.height }
+    }
+
+    pub fn msaa(&self) -> bool {
+        !self.sample_depth.is_empty()
+    }
+
+    /// Turns 4x multisampling on or off; the sample buffers are only allocated while it is on
+    pub fn set_msaa(&mut self, enabled: bool) {
+        if enabled == self.msaa() {
+            return;
+        }
+        let (depth, color) = if enabled {
+            let pixels = (self.width * self.height) as usize;
+            (vec![[self.depth_mode.cleared(); MSAA_SAMPLES]; pixels], vec![[Vector3::zero(); MSAA_SAMPLES]; pixels])
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        self.sample_depth = depth;
+        self.sample_color = color;
+    }
+
     pub fn clear(&mut self) {
+        if self.scissored() {
+            self.clear_scissor();
+            return;
+        }
         self.color_buffer.clear_background(self.background_color);
-        self.depth_buffer.fill(f32::INFINITY);
+        self.hdr_buffer.fill(Vector3::zero());
+        self.glow_buffer.fill(Vector3::zero());
+        self.depth_buffer.fill(self.depth_mode.cleared());
+        self.velocity_buffer.fill(Vector2::zero());
+        self.sample_depth.fill([self.depth_mode.cleared(); MSAA_SAMPLES]);
+        self.sample_color.fill([Vector3::zero(); MSAA_SAMPLES]);
     }
 
+    /// `clear` for the pixels inside the scissor only
+    fn clear_scissor(&mut self) {
+        let cleared = self.depth_mode.cleared();
+        for y in self.scissor.y0..self.scissor.y1 {
+            for x in self.scissor.x0..self.scissor.x1 {
+                let index = (y * self.width + x) as usize;
+                self.color_buffer.draw_pixel(x, y, self.background_color);
+                self.hdr_buffer[index] = Vector3::zero();
+                self.glow_buffer[index] = Vector3::zero();
+                self.depth_buffer[index] = cleared;
+                self.velocity_buffer[index] = Vector2::zero();
+                if let Some(samples) = self.sample_depth.get_mut(index) {
+                    *samples = [cleared; MSAA_SAMPLES];
+                    self.sample_color[index] = [Vector3::zero(); MSAA_SAMPLES];
+                }
+            }
+        }
+    }
+
+    /// Draws `current_color` straight into the display buffer (call after `resolve`)
     pub fn set_pixel(&mut self, x: i32, y: i32) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+        if self.scissor.contains(x, y) {
             self.color_buffer.draw_pixel(x, y, self.current_color);
         }
     }
     
-    pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+    pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) -> DepthTest {
+        self.point_samples(x, y, color, depth, Vector2::zero(), FULL_COVERAGE)
+    }
+
+    /// Depth test of a fragment against what is already at (x, y), without writing
+    /// anything: lets the renderer skip the fragment shader for hidden fragments.
+    /// Takes the same arguments as `point_samples` and gives the same answer.
+    pub fn depth_test(&self, x: i32, y: i32, depth: f32, depth_slope: Vector2, coverage: u8) -> DepthTest {
+        if !self.scissor.contains(x, y) {
+            return DepthTest::OutOfBounds;
+        }
+        let index = (y * self.width + x) as usize;
+        let visible = if self.msaa() {
+            (0..MSAA_SAMPLES).any(|sample| {
+                coverage & (1 << sample) != 0 && self.depth_mode.nearer(sample_depth(depth, depth_slope, sample), self.sample_depth[index][sample])
+            })
+        } else {
+            self.depth_mode.nearer(depth, self.depth_buffer[index])
+        };
+        if visible { DepthTest::Passed } else { DepthTest::Rejected }
+    }
+
+    /// Writes a fragment that covers only the MSAA samples set in `coverage`; each
+    /// sample is depth-tested on its own, at `depth` plus `depth_slope` times its
+    /// offset from the pixel center. Without MSAA this is `point`.
+    pub fn point_samples(&mut self, x: i32, y: i32, color: Vector3, depth: f32, depth_slope: Vector2, coverage: u8) -> DepthTest {
+        if self.scissor.contains(x, y) {
             let index = (y * self.width + x) as usize;
 
-            if depth < self.depth_buffer[index] {
+            if self.msaa() {
+                let mut passed = false;
+                for sample in 0..MSAA_SAMPLES {
+                    let sample_depth = sample_depth(depth, depth_slope, sample);
+                    if coverage & (1 << sample) != 0 && self.depth_mode.nearer(sample_depth, self.sample_depth[index][sample]) {
+                        self.sample_depth[index][sample] = sample_depth;
+                        self.sample_color[index][sample] = color;
+                        passed = true;
+                    }
+                }
+                if !passed {
+                    return DepthTest::Rejected;
+                }
+                // El depth buffer de un solo valor guarda la muestra más cercana (brillos, destellos, post-proceso)
+                let mode = self.depth_mode;
+                self.depth_buffer[index] = self.sample_depth[index].iter().fold(mode.cleared(), |a, &b| mode.nearest(a, b));
+                self.hdr_buffer[index] = color;
+                DepthTest::Passed
+            } else if self.depth_mode.nearer(depth, self.depth_buffer[index]) {
                 self.depth_buffer[index] = depth;
-                let pixel_color = Color::new(
-                    (color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.z.clamp(0.0, 1.0) * 255.0) as u8,
-                    255,
-                );
-                self.color_buffer.draw_pixel(x, y, pixel_color);
+                self.hdr_buffer[index] = color;
+                DepthTest::Passed
+            } else {
+                DepthTest::Rejected
             }
+        } else {
+            DepthTest::OutOfBounds
         }
     }
     
+    /// Adds `color` as light on top of whatever ends up at (x, y), unless a
+    /// surface nearer than `depth` covers it. Does not write depth.
+    pub fn add_glow(&mut self, x: i32, y: i32, color: Vector3, depth: f32) {
+        if self.scissor.contains(x, y) {
+            let index = (y * self.width + x) as usize;
+            if self.depth_mode.nearer(depth, self.depth_buffer[index]) {
+                self.glow_buffer[index] += color;
+            }
+        }
+    }
+
+    /// Converts the HDR buffer plus glow to display colors in `color_buffer` (tone
+    /// mapping and gamma). Pixels no fragment reached keep the background color as
+    /// is, with any glow added in display space.
+    pub fn resolve(&mut self) {
+        self.resolve_with(self.display);
+    }
+
+    /// With MSAA, the samples a surface covers are averaged before the tone curve and
+    /// the result is blended with the background by the fraction of samples covered.
+    pub fn resolve_with(&mut self, display: DisplayTransform) {
+        for y in self.scissor.y0..self.scissor.y1 {
+            for x in self.scissor.x0..self.scissor.x1 {
+                let index = (y * self.width + x) as usize;
+                let glow = self.glow_buffer[index];
+                let color = if self.msaa() && self.depth_buffer[index].is_finite() {
+                    let (depths, colors) = (&self.sample_depth[index], &self.sample_color[index]);
+                    let covered = depths.iter().filter(|depth| depth.is_finite()).count();
+                    let sum = depths.iter().zip(colors).filter(|(depth, _)| depth.is_finite()).fold(Vector3::zero(), |sum, (_, &color)| sum + color);
+                    let surface = display.to_display(sum / covered as f32 + glow, x, y);
+                    let background = self.background_color;
+                    let t = covered as f32 / MSAA_SAMPLES as f32;
+                    let mix = |a: u8, b: u8| (a as f32 * t + b as f32 * (1.0 - t)).round() as u8;
+                    Color::new(mix(surface.r, background.r), mix(surface.g, background.g), mix(surface.b, background.b), 255)
+                } else if !self.depth_buffer[index].is_finite() {
+                    // El fondo se guarda ya en colores de pantalla: el brillo se suma encima
+                    let background = self.background_color;
+                    if glow == Vector3::zero() {
+                        background
+                    } else {
+                        let light = display.to_display(glow, x, y);
+                        Color::new(
+                            background.r.saturating_add(light.r),
+                            background.g.saturating_add(light.g),
+                            background.b.saturating_add(light.b),
+                            255,
+                        )
+                    }
+                } else {
+                    display.to_display(self.hdr_buffer[index] + glow, x, y)
+                };
+                self.color_buffer.draw_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Shows the depth buffer in grayscale instead of the colors: the nearest
+    /// written depth is white and the farthest is dark gray
+    pub fn resolve_depth(&mut self) {
+        let mode = self.depth_mode;
+        let Tile { x0, y0, x1, y1 } = self.scissor;
+        // Rango de profundidades de lo que hay dentro del scissor
+        let (depths, width) = (&self.depth_buffer, self.width);
+        let written = (y0..y1)
+            .flat_map(|y| (x0..x1).map(move |x| depths[(y * width + x) as usize]))
+            .filter(|depth| depth.is_finite());
+        let (near, far) = written.fold((mode.cleared(), -mode.cleared()), |(near, far), depth| {
+            (mode.nearest(near, depth), if mode.nearer(far, depth) { depth } else { far })
+        });
+        let range = (far - near).abs().max(1e-6);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let depth = self.depth_buffer[(y * self.width + x) as usize];
+                let color = if depth.is_finite() {
+                    let level = (255.0 - (depth - near).abs() / range * 200.0) as u8;
+                    Color::new(level, level, level, 255)
+                } else {
+                    Color::BLACK
+                };
+                self.color_buffer.draw_pixel(x, y, color);
+            }
+        }
+    }
+
     pub fn get_pixel_color(&mut self, x: i32, y: i32) -> Option<Color> {
         if x >= 0 && x < self.width && y >= 0 && y < self.height {
             Some(self.color_buffer.get_color(x, y))
@@ -61,6 +295,10 @@ impl Framebuffer {
         }
     }
 
+    pub fn background_color(&self) -> Color {
+        self.background_color
+    }
+
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
     }
@@ -69,11 +307,110 @@ impl Framebuffer {
         self.current_color = color;
     }
 
-    pub fn swap_buffers(&self, d: &mut RaylibHandle, thread: &RaylibThread) {
-        if let Ok(texture) = d.load_texture_from_image(thread, &self.color_buffer) {
-            let mut d = d.begin_drawing(thread);
-            d.clear_background(self.background_color);
-            d.draw_texture(&texture, 0, 0, Color::WHITE);
+    /// Copy of the color buffer, row by row (for post-processing)
+    pub fn pixels(&mut self) -> Vec<Color> {
+        let mut pixels = Vec::with_capacity((self.width * self.height) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                pixels.push(self.color_buffer.get_color(x, y));
+            }
         }
-    } 
+        pixels
+    }
+
+    /// Depth buffer laid out like `pixels()`: NDC z of the nearest surface, or
+    /// a non-finite value (`DepthMode::cleared`) where nothing was drawn
+    pub fn depth(&self) -> &[f32] {
+        &self.depth_buffer
+    }
+
+    /// Linear light at pixel `index` before the tone curve: the surface color (with
+    /// MSAA, its covered samples weighted by coverage) plus the additive glow.
+    /// The background counts as dark; bloom reads this instead of the clipped colors.
+    pub fn radiance(&self, index: usize) -> Vector3 {
+        let glow = self.glow_buffer[index];
+        if !self.depth_buffer[index].is_finite() {
+            return glow;
+        }
+        if self.msaa() {
+            let (depths, colors) = (&self.sample_depth[index], &self.sample_color[index]);
+            let sum = depths.iter().zip(colors).filter(|(depth, _)| depth.is_finite()).fold(Vector3::zero(), |sum, (_, &color)| sum + color);
+            return sum / MSAA_SAMPLES as f32 + glow;
+        }
+        self.hdr_buffer[index] + glow
+    }
+
+    /// Screen-space motion since the previous frame of the surface at each pixel,
+    /// laid out like `pixels()` (zero for the background)
+    pub fn velocity(&self) -> &[Vector2] {
+        &self.velocity_buffer
+    }
+
+    /// Records the motion of the surface that just passed the depth test at (x, y)
+    pub fn set_velocity(&mut self, x: i32, y: i32, velocity: Vector2) {
+        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+            self.velocity_buffer[(y * self.width + x) as usize] = velocity;
+        }
+    }
+
+    /// Replaces the color buffer with `pixels`, laid out like `pixels()`
+    pub fn set_pixels(&mut self, pixels: &[Color]) {
+        for (index, color) in pixels.iter().enumerate() {
+            let index = index as i32;
+            self.color_buffer.draw_pixel(index % self.width, index / self.width, *color);
+        }
+    }
+
+    /// Box-filters this framebuffer into `target`, which must be an integer
+    /// fraction of its size (used for supersampling: render big, present small).
+    /// The HDR light (`radiance`) is averaged too, so the target can be bloomed.
+    /// Each target pixel keeps the nearest depth of its block and its average
+    /// velocity, scaled to target pixels.
+    pub fn downsample_into(&mut self, target: &mut Framebuffer) {
+        let factor = self.width / target.width;
+        debug_assert_eq!(factor * target.height, self.height, "SSAA factor must match both axes");
+        let samples = (factor * factor) as u32;
+        let mode = self.depth_mode;
+        target.depth_mode = mode;
+
+        for y in 0..target.height {
+            for x in 0..target.width {
+                let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+                let mut depth = mode.cleared();
+                let mut velocity = Vector2::zero();
+                let mut radiance = Vector3::zero();
+                for sy in 0..factor {
+                    for sx in 0..factor {
+                        let color = self.color_buffer.get_color(x * factor + sx, y * factor + sy);
+                        r += color.r as u32;
+                        g += color.g as u32;
+                        b += color.b as u32;
+                        let index = ((y * factor + sy) * self.width + x * factor + sx) as usize;
+                        depth = mode.nearest(depth, self.depth_buffer[index]);
+                        velocity += self.velocity_buffer[index];
+                        radiance += self.radiance(index);
+                    }
+                }
+                let average = Color::new((r / samples) as u8, (g / samples) as u8, (b / samples) as u8, 255);
+                target.color_buffer.draw_pixel(x, y, average);
+                target.depth_buffer[(y * target.width + x) as usize] = depth;
+                target.velocity_buffer[(y * target.width + x) as usize] = velocity / samples as f32 / factor as f32;
+                // Todo como brillo aditivo: así cuenta también donde el destino quedó de fondo
+                target.hdr_buffer[(y * target.width + x) as usize] = Vector3::zero();
+                target.glow_buffer[(y * target.width + x) as usize] = radiance / samples as f32;
+            }
+        }
+    }
+
+    /// Writes the color buffer to an image file (format taken from the extension, e.g. .png)
+    #[cfg(feature = "native")]
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        self.color_buffer.export_image(path);
+        // export_image no devuelve errores: se comprueba que el archivo haya quedado escrito
+        if std::path::Path::new(path).exists() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("failed to write {}", path)))
+        }
+    }
 }
\ No newline at end of file
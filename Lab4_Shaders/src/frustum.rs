@@ -0,0 +1,43 @@
+// frustum.rs
+// Descarte de cuerpos completos: cada cuerpo se aproxima con su esfera envolvente y
+// se prueba contra los seis planos del volumen de visión antes del vertex shader
+use crate::math::*;
+
+/// The six planes of the view volume, each as (normal, distance) with the normal
+/// pointing inwards: a point p is inside when `normal · p + distance >= 0`
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [(Vector3, f32); 6],
+}
+
+impl Frustum {
+    /// Planes of the volume that `view_projection` maps onto the NDC cube, taken
+    /// straight from the rows of the matrix (Gribb-Hartmann); works for both
+    /// the perspective and the orthographic projection
+    pub fn from_matrix(view_projection: &Matrix) -> Self {
+        let m = view_projection;
+        // Filas de la matriz: clip.x = rows[0] · (p, 1), etc.
+        let rows = [
+            [m.m0, m.m4, m.m8, m.m12],
+            [m.m1, m.m5, m.m9, m.m13],
+            [m.m2, m.m6, m.m10, m.m14],
+            [m.m3, m.m7, m.m11, m.m15],
+        ];
+        // -w <= x, y, z <= w: cada desigualdad es un plano w ± coordenada >= 0
+        let plane = |row: usize, sign: f32| {
+            let [a, b, c, d] = std::array::from_fn(|i| rows[3][i] + sign * rows[row][i]);
+            let normal = Vector3::new(a, b, c);
+            let length = normal.length();
+            (normal / length, d / length)
+        };
+        Frustum {
+            planes: [plane(0, 1.0), plane(0, -1.0), plane(1, 1.0), plane(1, -1.0), plane(2, 1.0), plane(2, -1.0)],
+        }
+    }
+
+    /// False only when the sphere is entirely outside one of the planes; a sphere
+    /// near a corner may pass while being just outside, which only costs a draw
+    pub fn contains_sphere(&self, center: Vector3, radius: f32) -> bool {
+        self.planes.iter().all(|(normal, distance)| normal.dot(center) + distance >= -radius)
+    }
+}
@@ -0,0 +1,135 @@
+// gizmos.rs
+// Ayudas visuales para revisar la configuración: la órbita de cada cuerpo como una
+// polilínea y su eje de giro como una flecha que atraviesa los polos. Se suman como
+// luz sobre la escena con el depth buffer ya lleno, así lo que pasa por detrás del
+// planeta queda tapado y se ve de qué lado cruza el plano del ecuador.
+use crate::math::*;
+use crate::comet::{comet_model_matrix, comet_position, ORBIT_PERIOD as COMET_PERIOD};
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::matrix::multiply_matrix_vector4;
+use crate::picking::Body;
+use crate::planets::PlanetParams;
+use crate::shaders::planet_model_matrix;
+use crate::triangle::line;
+use crate::uniforms::GlobalUniforms;
+use crate::vertex::Vertex;
+
+const ORBIT_COLOR: Vector3 = Vector3::new(0.15, 0.45, 0.7);
+const AXIS_COLOR: Vector3 = Vector3::new(0.9, 0.5, 0.15);
+const ORBIT_SEGMENTS: usize = 128;
+const AXIS_LENGTH: f32 = 1.6;  // largo de cada mitad del eje, en radios del cuerpo
+const ARROW_HEAD: f32 = 0.25;  // largo de la punta, en la misma escala
+
+// Los segmentos que se estiran más que esto en pantalla (un extremo casi en el plano
+// cercano) se saltean en vez de recorrer miles de píxeles fuera de la imagen
+const MAX_SEGMENT_PIXELS: f32 = 4096.0;
+
+/// Which bodies show their gizmos, one bit per body
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Gizmos {
+    shown: u64,
+}
+
+impl Gizmos {
+    pub const NONE: Gizmos = Gizmos { shown: 0 };
+    pub const ALL: Gizmos = Gizmos { shown: u64::MAX };
+
+    fn bit(body: Body) -> u64 {
+        let index = match body {
+            Body::Planet => 0,
+            Body::Sun => 1,
+            Body::Comet => 2,
+            Body::Moon(index) => 3 + index as u32,
+        };
+        1u64.checked_shl(index).unwrap_or(0)
+    }
+
+    pub fn shows(self, body: Body) -> bool {
+        self.shown & Self::bit(body) != 0
+    }
+
+    pub fn any(self) -> bool {
+        self.shown != 0
+    }
+
+    /// Shows or hides the gizmos of `body`
+    pub fn toggle(&mut self, body: Body) {
+        self.shown ^= Self::bit(body);
+    }
+}
+
+/// Draws the gizmos of the bodies `gizmos` shows: orbits for the moons and the
+/// comet, spin axes for every body (the planet and the sun stay put, so they
+/// have no orbit). `planet` holds the moons and the axial tilt.
+pub fn draw_gizmos(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, planet: &PlanetParams, light: &Light, time: f32, gizmos: Gizmos) {
+    for body in Body::all(&planet.moons).filter(|&body| gizmos.shows(body)) {
+        let (center, radius) = body.sphere(time, light, &planet.moons);
+
+        let orbit = match body {
+            Body::Moon(index) => {
+                let moon = &planet.moons[index];
+                Some(orbit_points(|t| moon.position(time + t * moon.period)))
+            }
+            Body::Comet => Some(orbit_points(|t| comet_position(time + t * COMET_PERIOD))),
+            _ => None,
+        };
+        if let Some(points) = orbit {
+            for pair in points.windows(2) {
+                draw_segment(framebuffer, globals, pair[0], pair[1], ORBIT_COLOR);
+            }
+        }
+
+        // Eje: el +Y local del cuerpo llevado al mundo con su matriz de modelo
+        let model = match body {
+            Body::Planet => planet_model_matrix(time, planet),
+            Body::Moon(index) => planet.moons[index].model_matrix(time),
+            Body::Comet => comet_model_matrix(time),
+            Body::Sun => Matrix::identity(),
+        };
+        let up = multiply_matrix_vector4(&model, &Vector4::new(0.0, 1.0, 0.0, 0.0));
+        let axis = Vector3::new(up.x, up.y, up.z).normalized();
+        draw_arrow(framebuffer, globals, center - axis * (radius * AXIS_LENGTH), center + axis * (radius * AXIS_LENGTH), radius);
+    }
+}
+
+/// Closed polyline through `position(t)` for t from 0 to 1 (one full orbit)
+fn orbit_points(position: impl Fn(f32) -> Vector3) -> Vec<Vector3> {
+    (0..=ORBIT_SEGMENTS).map(|step| position(step as f32 / ORBIT_SEGMENTS as f32)).collect()
+}
+
+/// Axis from `tail` to `tip` with a two-stroke head at the tip, facing the camera
+fn draw_arrow(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, tail: Vector3, tip: Vector3, radius: f32) {
+    draw_segment(framebuffer, globals, tail, tip, AXIS_COLOR);
+
+    let direction = (tip - tail).normalized();
+    let side = direction.cross(globals.camera_position - tip);
+    if side.length() == 0.0 {
+        return; // el eje apunta a la cámara: la punta no se vería
+    }
+    let head = radius * ARROW_HEAD;
+    let side = side.normalized() * (head * 0.6);
+    let base = tip - direction * head;
+    draw_segment(framebuffer, globals, base + side, tip, AXIS_COLOR);
+    draw_segment(framebuffer, globals, base - side, tip, AXIS_COLOR);
+}
+
+/// One world-space segment through the line rasterizer, added as glow and hidden
+/// where the scene is in front; skipped if an end is behind the camera
+fn draw_segment(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, from: Vector3, to: Vector3, color: Vector3) {
+    let (Some(start), Some(end)) = (globals.project(from), globals.project(to)) else {
+        return;
+    };
+    if (end.x - start.x).abs().max((end.y - start.y).abs()) > MAX_SEGMENT_PIXELS {
+        return;
+    }
+
+    let endpoint = |world: Vector3, screen: Vector3| {
+        let mut vertex = Vertex::new_with_color(world, color);
+        vertex.set_transformed(screen, Vector3::zero());
+        vertex
+    };
+    for fragment in line(&endpoint(from, start), &endpoint(to, end)) {
+        framebuffer.add_glow(fragment.position.x as i32, fragment.position.y as i32, fragment.color, fragment.depth);
+    }
+}
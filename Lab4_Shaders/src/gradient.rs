@@ -0,0 +1,41 @@
+// gradient.rs
+// Gradientes de color con paradas (posición, color), definidos por planeta en
+// planets.toml: los shaders muestrean uno con un valor (elevación, profundidad,
+// banda...) en vez de elegir entre colores fijos con umbrales escritos en el código
+use crate::math::*;
+
+/// Piecewise-linear color ramp. Two stops at the same position make a hard edge;
+/// before the first stop and after the last one the color stays flat.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Vector3)>,
+}
+
+impl Gradient {
+    /// Gradient through `stops`; they are sorted by position, keeping the given
+    /// order of stops that share one
+    pub fn new(mut stops: Vec<(f32, Vector3)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Gradient { stops }
+    }
+
+    /// Color at `t`; black for a gradient without stops
+    pub fn sample(&self, t: f32) -> Vector3 {
+        // Primera parada que queda después de t: en un borde duro gana el color de la derecha
+        let next = self.stops.partition_point(|&(position, _)| position <= t);
+        match (next.checked_sub(1).map(|i| self.stops[i]), self.stops.get(next)) {
+            (Some((start, from)), Some(&(end, to))) => from.lerp(to, (t - start) / (end - start)),
+            (Some((_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => Vector3::zero(),
+        }
+    }
+
+    pub fn stops(&self) -> &[(f32, Vector3)] {
+        &self.stops
+    }
+
+    /// The same gradient with every stop color passed through `map`
+    pub fn map_colors(&self, map: impl Fn(Vector3) -> Vector3) -> Gradient {
+        Gradient { stops: self.stops.iter().map(|&(position, color)| (position, map(color))).collect() }
+    }
+}
@@ -0,0 +1,101 @@
+// headless.rs
+use crate::asteroids::AsteroidBelt;
+use crate::comet::Comet;
+use crate::camera::Camera;
+use crate::cli::{split_planet, Options};
+use crate::demo::Demo;
+use crate::light::Light;
+use crate::mesh::SceneMeshes;
+use crate::planets::PlanetType;
+use crate::cli::create_post_process;
+use crate::renderer::{frame_projection, FrameSettings, Renderer, Scene, Viewport, FOV_Y};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Paso de tiempo fijo para que la salida sea reproducible (30 fps)
+const HEADLESS_DT: f32 = 1.0 / 30.0;
+
+/// Renders `options.frames` frames without opening a window and writes them as
+/// numbered PNG files into `options.output_dir`. With `--replay` it renders one
+/// frame per demo frame instead, with the recorded camera, time and planet.
+/// With `--capture` each PNG gets a JSON dump of the frame's pipeline next to it.
+/// With `--split-shading` or `--split-planet` each image is a split screen.
+pub fn run(options: &Options, camera: &Camera, light: &Light, meshes: &SceneMeshes, planets: &[PlanetType], planet: usize) -> io::Result<()> {
+    let demo = match &options.replay {
+        Some(path) => Some(Demo::load(Path::new(path)).map_err(io::Error::other)?),
+        None => None,
+    };
+    let split_planet = split_planet(options, planets).map_err(io::Error::other)?;
+    fs::create_dir_all(&options.output_dir)?;
+
+    let mut renderer = Renderer::new(options.width, options.height, options.ssaa, options.display);
+    renderer.post_process = create_post_process(options);
+    let mut comet = Comet::new();
+    let mut belt = AsteroidBelt::new(7);
+
+    let mut last_view_projection = None; // para los vectores de movimiento del motion blur
+    let frame_count = demo.as_ref().map_or(options.frames as usize, |demo| demo.frames.len());
+    let mut time = 0.0;
+    for frame_index in 0..frame_count {
+        let (dt, replayed_camera, planet) = match &demo {
+            Some(demo) => {
+                let recorded = &demo.frames[frame_index];
+                time = recorded.time;
+                (recorded.dt, Some(recorded.camera()), &planets[recorded.planet.min(planets.len() - 1)])
+            }
+            None => {
+                time += HEADLESS_DT;
+                (HEADLESS_DT, None, &planets[planet])
+            }
+        };
+        let camera = replayed_camera.as_ref().unwrap_or(camera);
+        let light = &light.at(time);
+        let frame = FrameSettings {
+            time,
+            dt,
+            debug_view: options.debug_view,
+            render_mode: options.render_mode,
+            shading: options.shading,
+            projection: options.projection,
+            depth_mode: options.depth_mode,
+            depth_overlay: options.depth_overlay,
+            selected: None,
+            gizmos: options.gizmos,
+            fov_y: FOV_Y,
+            previous_view_projection: last_view_projection.filter(|_| options.motion_blur),
+            lens_flare: options.lens_flare,
+            msaa: options.msaa,
+            // En pantalla dividida render_split dibuja cada mitad con su viewport;
+            // la proyección del frame (y la del motion blur) es la de una mitad
+            viewport: if options.split() { Viewport::LEFT_HALF } else { Viewport::FULL },
+        };
+        comet.update(time, dt.abs(), light);
+        belt.update(time);
+        let scene = Scene { meshes, planet, comet: &comet, belt: &belt, fade_from: None };
+        let capture = if options.capture {
+            Some(renderer.capture_frame(camera, light, &scene, &frame))
+        } else if options.split() {
+            let right_frame = FrameSettings { shading: options.split_shading.unwrap_or(frame.shading), ..frame };
+            let right_scene = Scene { planet: split_planet.map_or(planet, |index| &planets[index]), ..scene };
+            renderer.render_split(camera, light, (&scene, &frame), (&right_scene, &right_frame));
+            None
+        } else {
+            renderer.render(camera, light, &scene, &frame);
+            None
+        };
+        let projection_matrix = frame_projection(camera, &frame, options.width as f32, options.height as f32);
+        last_view_projection = Some(camera.get_view_matrix() * projection_matrix);
+
+        let path = Path::new(&options.output_dir).join(format!("frame_{:04}.png", frame_index));
+        renderer.framebuffer.save(&path.to_string_lossy())?;
+        println!("Rendered {}", path.display());
+        if let Some(capture) = capture {
+            let path = path.with_extension("json");
+            capture.save(&path).map_err(io::Error::other)?;
+            println!("Captured {}", path.display());
+        }
+    }
+
+    Ok(())
+}
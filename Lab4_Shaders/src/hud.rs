@@ -0,0 +1,52 @@
+// hud.rs
+// Estadísticas del frame dibujadas en la esquina superior derecha (H oculta) y
+// avisos de eclipses y tránsitos abajo a la izquierda
+use raylib::prelude::*;
+use crate::stats::RenderStats;
+use crate::events::Event;
+use crate::time::TimeController;
+
+const HUD_WIDTH: i32 = 230;
+const LINE_HEIGHT: i32 = 18;
+const FONT_SIZE: i32 = 14;
+const MARGIN: i32 = 10;
+const EVENT_WIDTH: i32 = 400;
+
+const HUD_COLOR: Color = Color::new(20, 10, 35, 200);
+const TEXT_COLOR: Color = Color::new(240, 230, 250, 255);
+const EVENT_COLOR: Color = Color::new(255, 215, 90, 255);
+
+/// `selection` are extra lines about the picked body (empty when nothing is selected)
+pub fn draw_hud(d: &mut RaylibDrawHandle, stats: &RenderStats, clock: &TimeController, selection: &[String]) {
+    let mut lines = vec![
+        format!("FPS: {} ({:.1} ms)", d.get_fps(), d.get_frame_time() * 1000.0),
+        format!("Tiempo: {:.2} s {}", clock.time, clock.label()),
+        format!("Cuerpos descartados: {}", stats.bodies_culled),
+        format!("Vertices: {}", stats.vertices),
+        format!("Triangulos: {}", stats.triangles),
+        format!("  descartados: {}", stats.triangles_culled),
+        format!("  recortados: {}", stats.triangles_clipped),
+        format!("Fragmentos: {}", stats.fragments),
+        format!("  sombreados: {}", stats.fragments_shaded),
+        format!("  escritos: {}", stats.fragments_written),
+        format!("  rechazados (depth): {}", stats.depth_rejected),
+    ];
+    lines.extend_from_slice(selection);
+
+    let x = d.get_screen_width() - HUD_WIDTH - MARGIN;
+    let height = lines.len() as i32 * LINE_HEIGHT + MARGIN;
+    d.draw_rectangle(x, MARGIN, HUD_WIDTH, height, HUD_COLOR);
+    for (i, line) in lines.iter().enumerate() {
+        d.draw_text(line, x + 8, MARGIN + 6 + i as i32 * LINE_HEIGHT, FONT_SIZE, TEXT_COLOR);
+    }
+}
+
+/// Annotation at the bottom left for each event in progress (eclipses, transits)
+pub fn draw_events(d: &mut RaylibDrawHandle, events: &[Event]) {
+    let bottom = d.get_screen_height() - MARGIN;
+    for (i, event) in events.iter().enumerate() {
+        let y = bottom - (i as i32 + 1) * (LINE_HEIGHT + 6);
+        d.draw_rectangle(MARGIN, y, EVENT_WIDTH, LINE_HEIGHT + 2, HUD_COLOR);
+        d.draw_text(event.description(), MARGIN + 8, y + 3, FONT_SIZE, EVENT_COLOR);
+    }
+}
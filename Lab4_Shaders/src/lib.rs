@@ -0,0 +1,111 @@
+// lib.rs
+//! Software rasterizer with procedural planet shaders, usable as a dependency.
+//!
+//! A frame is drawn by a [`Renderer`] from a [`Camera`], a [`Light`] and a [`Scene`]:
+//! the meshes, the comet, the asteroid belt and the active planet type. Planet
+//! shaders are [`PlanetType`]s (a color function plus parameters) registered in a
+//! [`PlanetRegistry`]; `Config` turns the presets in planets.toml into them.
+//!
+//! ```no_run
+//! use ship::{Camera, Config, FrameSettings, LodMesh, PlanetRegistry, Renderer, Scene, SceneMeshes};
+//! use ship::asteroids::AsteroidBelt;
+//! use ship::comet::Comet;
+//! use ship::math::Vector3;
+//! use ship::tonemap::DisplayTransform;
+//!
+//! let registry = PlanetRegistry::with_builtins();
+//! let config = Config::builtin();
+//! let planets = config.planet_types(&registry).unwrap();
+//! let meshes = SceneMeshes::new(LodMesh::icospheres());
+//! let camera = Camera::new(Vector3::new(0.0, 0.0, 8.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+//! let light = config.light().at(0.0); // posición y color de la luz en ese instante
+//! let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+//!
+//! let mut renderer = Renderer::new(640, 480, 1, DisplayTransform::default());
+//! let scene = Scene { meshes: &meshes, planet: &planets[0], comet: &comet, belt: &belt, fade_from: None };
+//! let stats = renderer.render(&camera, &light, &scene, &FrameSettings::default());
+//! println!("{} fragments", stats.fragments);
+//! // El resultado queda en renderer.framebuffer
+//! ```
+//!
+//! With the default `native` feature the math types are raylib's and the crate also
+//! has the interactive window, the terminal backend and the file outputs; without it
+//! (the `web` feature) the same pipeline compiles to wasm32.
+
+pub mod math;
+pub mod framebuffer;
+pub mod depth;
+pub mod triangle;
+pub mod obj;
+pub mod matrix;
+pub mod fragment;
+pub mod vertex;
+pub mod camera;
+pub mod shaders;
+pub mod gradient;
+pub mod light;
+pub mod renderer;
+pub mod display;
+pub mod planets;
+pub mod plugins;
+pub mod config;
+pub mod stats;
+pub mod mesh;
+pub mod shadow;
+pub mod postprocess;
+pub mod tonemap;
+pub mod debug;
+pub mod tiles;
+pub mod arena;
+pub mod capture;
+pub mod uniforms;
+pub mod sun;
+pub mod noise;
+pub mod particles;
+pub mod comet;
+pub mod moons;
+pub mod asteroids;
+pub mod time;
+pub mod picking;
+pub mod gizmos;
+pub mod events;
+pub mod flare;
+pub mod frustum;
+#[cfg(feature = "native")]
+pub mod app;
+#[cfg(feature = "native")]
+pub mod cli;
+#[cfg(feature = "native")]
+pub mod recorder;
+#[cfg(feature = "native")]
+pub mod headless;
+#[cfg(feature = "native")]
+pub mod ui;
+#[cfg(feature = "native")]
+pub mod hud;
+#[cfg(feature = "native")]
+pub mod demo;
+#[cfg(feature = "native")]
+pub mod bake;
+#[cfg(feature = "native")]
+pub mod cubemap;
+#[cfg(feature = "native")]
+pub mod terminal;
+#[cfg(feature = "web")]
+pub mod web;
+
+#[cfg(not(any(feature = "native", feature = "web")))]
+compile_error!("enable the `native` feature (default) or build with --no-default-features --features web");
+
+#[cfg(all(test, feature = "native"))]
+mod tests;
+
+pub use camera::{Camera, Projection};
+pub use config::Config;
+pub use framebuffer::Framebuffer;
+pub use gradient::Gradient;
+pub use light::Light;
+pub use mesh::{LodMesh, SceneMeshes};
+pub use planets::{PlanetColorFn, PlanetParams, PlanetRegistry, PlanetType};
+pub use renderer::{create_framebuffers, frame_projection, render_scene, resize_framebuffers, FrameSettings, Renderer, Scene, Viewport, FOV_Y};
+pub use stats::RenderStats;
@@ -1,11 +1,99 @@
-use raylib::prelude::*;
+// light.rs
+// La luz puntual de la escena, que sale del sol. Tiene color e intensidad y puede
+// animarse: girar alrededor del planeta (el lado de día se mueve), latir y
+// parpadear. El color se puede dar como temperatura de cuerpo negro en kelvin.
+use crate::math::*;
+use crate::noise::value_noise;
+use crate::tonemap::ColorSpace;
+use std::f32::consts::PI;
 
+// Temperatura de una luz blanca neutra (luz de día)
+pub const DAYLIGHT_KELVIN: f32 = 6500.0;
+
+// Ritmo del parpadeo: cambios de intensidad por segundo
+const FLICKER_RATE: f32 = 12.0;
+
+/// How a light changes over time; the default leaves it still
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightAnimation {
+    pub orbit_speed: f32, // rad/s alrededor del eje Y del mundo; negativo = sentido horario
+    pub pulse: f32,       // amplitud del latido de la intensidad (0.2 = ±20%)
+    pub pulse_speed: f32, // latidos por segundo
+    pub flicker: f32,     // amplitud del parpadeo irregular, con ruido
+}
+
+impl Default for LightAnimation {
+    fn default() -> Self {
+        LightAnimation { orbit_speed: 0.0, pulse: 0.0, pulse_speed: 1.0, flicker: 0.0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct Light {
     pub position: Vector3,
+    pub color: Vector3, // RGB en [0, 1] escrito en sRGB, como los colores de los presets
+    pub intensity: f32,
+    pub animation: LightAnimation,
 }
 
 impl Light {
+    /// Still white light of intensity 1 at `position`
     pub fn new(position: Vector3) -> Self {
-        Light { position }
+        Light { position, color: Vector3::one(), intensity: 1.0, animation: LightAnimation::default() }
+    }
+
+    /// The same light colored like a black body at `kelvin` degrees
+    pub fn with_temperature(self, kelvin: f32) -> Self {
+        Light { color: color_temperature(kelvin), ..self }
+    }
+
+    pub fn with_animation(self, animation: LightAnimation) -> Self {
+        Light { animation, ..self }
     }
-}
\ No newline at end of file
+
+    /// Still light with the position and intensity the animation gives at `time`;
+    /// the frontends call it once per frame and draw with the result
+    pub fn at(&self, time: f32) -> Light {
+        let animation = self.animation;
+        let angle = time * animation.orbit_speed;
+        let (sin, cos) = angle.sin_cos();
+        let position = Vector3::new(
+            self.position.x * cos - self.position.z * sin,
+            self.position.y,
+            self.position.x * sin + self.position.z * cos,
+        );
+
+        let pulse = animation.pulse * (time * animation.pulse_speed * 2.0 * PI).sin();
+        let flicker = animation.flicker * value_noise(&Vector3::new(time * FLICKER_RATE, 0.5, 0.5));
+        let intensity = self.intensity * (1.0 + pulse + flicker).max(0.0);
+        Light { position, color: self.color, intensity, animation: LightAnimation::default() }
+    }
+
+    /// Copy whose color is converted from sRGB to `color_space`, like `PlanetType::in_color_space`
+    pub fn in_color_space(&self, color_space: ColorSpace) -> Light {
+        Light { color: color_space.decode(self.color), ..*self }
+    }
+
+    /// Color times intensity: what the shaders multiply the lit surfaces by
+    pub fn radiance(&self) -> Vector3 {
+        self.color * self.intensity
+    }
+}
+
+/// sRGB color of a black body at `kelvin` degrees (fit by Tanner Helland, valid
+/// from 1000 K to 40000 K), with the brightest channel at 1: red below ~6600 K
+/// (candles, red dwarfs), white around it, blue above
+pub fn color_temperature(kelvin: f32) -> Vector3 {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let red = if t <= 66.0 { 255.0 } else { 329.7 * (t - 60.0).powf(-0.1332) };
+    let green = if t <= 66.0 { 99.47 * t.ln() - 161.12 } else { 288.12 * (t - 60.0).powf(-0.07551) };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.52 * (t - 10.0).ln() - 305.04
+    };
+    let channel = |value: f32| value.clamp(0.0, 255.0) / 255.0;
+    Vector3::new(channel(red), channel(green), channel(blue))
+}
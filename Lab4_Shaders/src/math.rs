@@ -0,0 +1,289 @@
+// math.rs
+// Tipos de vectores, matrices, colores e imágenes del pipeline. En el build nativo
+// son los de raylib; sin raylib (el build web, wasm32-unknown-unknown) se usan copias
+// en Rust puro con la misma API y la misma semántica que raymath.
+#[cfg(feature = "native")]
+pub use raylib::prelude::{Color, Image, Matrix, Vector2, Vector3, Vector4};
+
+#[cfg(not(feature = "native"))]
+pub use portable::{Color, Image, Matrix, Vector2, Vector3, Vector4};
+
+/// Pure-Rust versions of the raylib types the pipeline uses; compiled in tests too
+/// so they can be checked against raylib's
+#[cfg(any(test, not(feature = "native")))]
+#[cfg_attr(feature = "native", allow(dead_code))]
+pub mod portable {
+    use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Vector2 {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Vector3 {
+        pub x: f32,
+        pub y: f32,
+        pub z: f32,
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Vector4 {
+        pub x: f32,
+        pub y: f32,
+        pub z: f32,
+        pub w: f32,
+    }
+
+    impl Vector2 {
+        pub const fn new(x: f32, y: f32) -> Self {
+            Vector2 { x, y }
+        }
+
+        pub const fn zero() -> Self {
+            Vector2::new(0.0, 0.0)
+        }
+
+        pub fn length(&self) -> f32 {
+            self.dot(*self).sqrt()
+        }
+
+        pub fn dot(&self, v: Vector2) -> f32 {
+            self.x * v.x + self.y * v.y
+        }
+    }
+
+    impl Vector3 {
+        pub const fn new(x: f32, y: f32, z: f32) -> Self {
+            Vector3 { x, y, z }
+        }
+
+        pub const fn zero() -> Self {
+            Vector3::new(0.0, 0.0, 0.0)
+        }
+
+        pub const fn one() -> Self {
+            Vector3::new(1.0, 1.0, 1.0)
+        }
+
+        pub fn length(&self) -> f32 {
+            self.dot(*self).sqrt()
+        }
+
+        pub fn dot(&self, v: Vector3) -> f32 {
+            self.x * v.x + self.y * v.y + self.z * v.z
+        }
+
+        pub fn cross(&self, v: Vector3) -> Vector3 {
+            Vector3::new(self.y * v.z - self.z * v.y, self.z * v.x - self.x * v.z, self.x * v.y - self.y * v.x)
+        }
+
+        /// Unit vector in the same direction; the zero vector stays zero
+        pub fn normalized(&self) -> Vector3 {
+            let length = self.length();
+            if length == 0.0 { *self } else { *self / length }
+        }
+
+        pub fn lerp(&self, v: Vector3, amount: f32) -> Vector3 {
+            *self + (v - *self) * amount
+        }
+
+        pub fn distance_to(&self, v: Vector3) -> f32 {
+            (*self - v).length()
+        }
+    }
+
+    impl Vector4 {
+        pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+            Vector4 { x, y, z, w }
+        }
+    }
+
+    // Operadores componente a componente y con escalares, como en raylib
+    macro_rules! vector_ops {
+        ($vector:ident, $($field:ident),*) => {
+            impl Add for $vector {
+                type Output = $vector;
+                fn add(self, v: $vector) -> $vector { $vector { $($field: self.$field + v.$field),* } }
+            }
+            impl Sub for $vector {
+                type Output = $vector;
+                fn sub(self, v: $vector) -> $vector { $vector { $($field: self.$field - v.$field),* } }
+            }
+            impl Mul for $vector {
+                type Output = $vector;
+                fn mul(self, v: $vector) -> $vector { $vector { $($field: self.$field * v.$field),* } }
+            }
+            impl Div for $vector {
+                type Output = $vector;
+                fn div(self, v: $vector) -> $vector { $vector { $($field: self.$field / v.$field),* } }
+            }
+            impl Mul<f32> for $vector {
+                type Output = $vector;
+                fn mul(self, s: f32) -> $vector { $vector { $($field: self.$field * s),* } }
+            }
+            impl Div<f32> for $vector {
+                type Output = $vector;
+                fn div(self, s: f32) -> $vector { $vector { $($field: self.$field / s),* } }
+            }
+            impl Neg for $vector {
+                type Output = $vector;
+                fn neg(self) -> $vector { $vector { $($field: -self.$field),* } }
+            }
+            impl AddAssign for $vector {
+                fn add_assign(&mut self, v: $vector) { $(self.$field += v.$field;)* }
+            }
+            impl SubAssign for $vector {
+                fn sub_assign(&mut self, v: $vector) { $(self.$field -= v.$field;)* }
+            }
+            impl MulAssign<f32> for $vector {
+                fn mul_assign(&mut self, s: f32) { $(self.$field *= s;)* }
+            }
+            impl DivAssign<f32> for $vector {
+                fn div_assign(&mut self, s: f32) { $(self.$field /= s;)* }
+            }
+        };
+    }
+    vector_ops!(Vector2, x, y);
+    vector_ops!(Vector3, x, y, z);
+    vector_ops!(Vector4, x, y, z, w);
+
+    /// 4x4 column-major matrix with raylib's field names: m0..m3 is the first column
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Matrix {
+        pub m0: f32, pub m4: f32, pub m8: f32, pub m12: f32,
+        pub m1: f32, pub m5: f32, pub m9: f32, pub m13: f32,
+        pub m2: f32, pub m6: f32, pub m10: f32, pub m14: f32,
+        pub m3: f32, pub m7: f32, pub m11: f32, pub m15: f32,
+    }
+
+    impl Matrix {
+        pub fn identity() -> Matrix {
+            Matrix { m0: 1.0, m5: 1.0, m10: 1.0, m15: 1.0, ..Matrix::default() }
+        }
+
+        /// Inverse by cofactors (raymath's MatrixInvert)
+        pub fn inverted(&self) -> Matrix {
+            let (a00, a01, a02, a03) = (self.m0, self.m1, self.m2, self.m3);
+            let (a10, a11, a12, a13) = (self.m4, self.m5, self.m6, self.m7);
+            let (a20, a21, a22, a23) = (self.m8, self.m9, self.m10, self.m11);
+            let (a30, a31, a32, a33) = (self.m12, self.m13, self.m14, self.m15);
+
+            let b00 = a00 * a11 - a01 * a10;
+            let b01 = a00 * a12 - a02 * a10;
+            let b02 = a00 * a13 - a03 * a10;
+            let b03 = a01 * a12 - a02 * a11;
+            let b04 = a01 * a13 - a03 * a11;
+            let b05 = a02 * a13 - a03 * a12;
+            let b06 = a20 * a31 - a21 * a30;
+            let b07 = a20 * a32 - a22 * a30;
+            let b08 = a20 * a33 - a23 * a30;
+            let b09 = a21 * a32 - a22 * a31;
+            let b10 = a21 * a33 - a23 * a31;
+            let b11 = a22 * a33 - a23 * a32;
+            let inv_det = 1.0 / (b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06);
+
+            Matrix {
+                m0: (a11 * b11 - a12 * b10 + a13 * b09) * inv_det,
+                m1: (-a01 * b11 + a02 * b10 - a03 * b09) * inv_det,
+                m2: (a31 * b05 - a32 * b04 + a33 * b03) * inv_det,
+                m3: (-a21 * b05 + a22 * b04 - a23 * b03) * inv_det,
+                m4: (-a10 * b11 + a12 * b08 - a13 * b07) * inv_det,
+                m5: (a00 * b11 - a02 * b08 + a03 * b07) * inv_det,
+                m6: (-a30 * b05 + a32 * b02 - a33 * b01) * inv_det,
+                m7: (a20 * b05 - a22 * b02 + a23 * b01) * inv_det,
+                m8: (a10 * b10 - a11 * b08 + a13 * b06) * inv_det,
+                m9: (-a00 * b10 + a01 * b08 - a03 * b06) * inv_det,
+                m10: (a30 * b04 - a31 * b02 + a33 * b00) * inv_det,
+                m11: (-a20 * b04 + a21 * b02 - a23 * b00) * inv_det,
+                m12: (-a10 * b09 + a11 * b07 - a12 * b06) * inv_det,
+                m13: (a00 * b09 - a01 * b07 + a02 * b06) * inv_det,
+                m14: (-a30 * b03 + a31 * b01 - a32 * b00) * inv_det,
+                m15: (a20 * b03 - a21 * b01 + a22 * b00) * inv_det,
+            }
+        }
+    }
+
+    // Igual que MatrixMultiply(left, right) de raymath: `a * b` aplica primero a y luego b
+    impl Mul for Matrix {
+        type Output = Matrix;
+
+        fn mul(self, right: Matrix) -> Matrix {
+            let l = self;
+            let r = right;
+            Matrix {
+                m0: l.m0 * r.m0 + l.m1 * r.m4 + l.m2 * r.m8 + l.m3 * r.m12,
+                m1: l.m0 * r.m1 + l.m1 * r.m5 + l.m2 * r.m9 + l.m3 * r.m13,
+                m2: l.m0 * r.m2 + l.m1 * r.m6 + l.m2 * r.m10 + l.m3 * r.m14,
+                m3: l.m0 * r.m3 + l.m1 * r.m7 + l.m2 * r.m11 + l.m3 * r.m15,
+                m4: l.m4 * r.m0 + l.m5 * r.m4 + l.m6 * r.m8 + l.m7 * r.m12,
+                m5: l.m4 * r.m1 + l.m5 * r.m5 + l.m6 * r.m9 + l.m7 * r.m13,
+                m6: l.m4 * r.m2 + l.m5 * r.m6 + l.m6 * r.m10 + l.m7 * r.m14,
+                m7: l.m4 * r.m3 + l.m5 * r.m7 + l.m6 * r.m11 + l.m7 * r.m15,
+                m8: l.m8 * r.m0 + l.m9 * r.m4 + l.m10 * r.m8 + l.m11 * r.m12,
+                m9: l.m8 * r.m1 + l.m9 * r.m5 + l.m10 * r.m9 + l.m11 * r.m13,
+                m10: l.m8 * r.m2 + l.m9 * r.m6 + l.m10 * r.m10 + l.m11 * r.m14,
+                m11: l.m8 * r.m3 + l.m9 * r.m7 + l.m10 * r.m11 + l.m11 * r.m15,
+                m12: l.m12 * r.m0 + l.m13 * r.m4 + l.m14 * r.m8 + l.m15 * r.m12,
+                m13: l.m12 * r.m1 + l.m13 * r.m5 + l.m14 * r.m9 + l.m15 * r.m13,
+                m14: l.m12 * r.m2 + l.m13 * r.m6 + l.m14 * r.m10 + l.m15 * r.m14,
+                m15: l.m12 * r.m3 + l.m13 * r.m7 + l.m14 * r.m11 + l.m15 * r.m15,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct Color {
+        pub r: u8,
+        pub g: u8,
+        pub b: u8,
+        pub a: u8,
+    }
+
+    impl Color {
+        pub const BLACK: Color = Color::new(0, 0, 0, 255);
+        pub const WHITE: Color = Color::new(255, 255, 255, 255);
+
+        pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+            Color { r, g, b, a }
+        }
+    }
+
+    /// RGBA image in memory (what raylib's `Image` is on the CPU side)
+    pub struct Image {
+        width: i32,
+        height: i32,
+        data: Vec<Color>,
+    }
+
+    impl Image {
+        pub fn gen_image_color(width: i32, height: i32, color: Color) -> Image {
+            Image { width, height, data: vec![color; (width.max(0) * height.max(0)) as usize] }
+        }
+
+        pub fn width(&self) -> i32 {
+            self.width
+        }
+
+        pub fn height(&self) -> i32 {
+            self.height
+        }
+
+        pub fn clear_background(&mut self, color: Color) {
+            self.data.fill(color);
+        }
+
+        /// Pixels outside the image are ignored, like in raylib
+        pub fn draw_pixel(&mut self, x: i32, y: i32, color: Color) {
+            if x >= 0 && x < self.width && y >= 0 && y < self.height {
+                self.data[(y * self.width + x) as usize] = color;
+            }
+        }
+
+        // &mut por compatibilidad con raylib, que lee los píxeles a través de un puntero mutable
+        pub fn get_color(&mut self, x: i32, y: i32) -> Color {
+            self.data[(y * self.width + x) as usize]
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //matrix.rs
 #![allow(dead_code)]
 
-use raylib::prelude::*;
+use crate::math::*;
+use wide::f32x4;
 
 pub fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
     Vector4::new(
@@ -12,6 +13,44 @@ pub fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
     )
 }
 
+/// Matrix with its four columns in SIMD registers, for transforming many vectors:
+/// each product is four lane-wide multiply-adds instead of sixteen scalar ones
+#[derive(Clone, Copy, Debug)]
+pub struct SimdMatrix {
+    columns: [f32x4; 4],
+}
+
+impl SimdMatrix {
+    pub fn new(matrix: &Matrix) -> Self {
+        SimdMatrix {
+            columns: [
+                f32x4::from([matrix.m0, matrix.m1, matrix.m2, matrix.m3]),
+                f32x4::from([matrix.m4, matrix.m5, matrix.m6, matrix.m7]),
+                f32x4::from([matrix.m8, matrix.m9, matrix.m10, matrix.m11]),
+                f32x4::from([matrix.m12, matrix.m13, matrix.m14, matrix.m15]),
+            ],
+        }
+    }
+
+    /// Same result as `multiply_matrix_vector4`
+    #[inline]
+    pub fn transform(&self, vector: Vector4) -> Vector4 {
+        let [c0, c1, c2, c3] = self.columns;
+        let result = c0 * f32x4::splat(vector.x)
+            + c1 * f32x4::splat(vector.y)
+            + c2 * f32x4::splat(vector.z)
+            + c3 * f32x4::splat(vector.w);
+        let [x, y, z, w] = result.to_array();
+        Vector4::new(x, y, z, w)
+    }
+}
+
+/// `multiply_matrix_vector4` over a whole array, appending the results to `out`
+pub fn multiply_matrix_vectors4(matrix: &Matrix, vectors: &[Vector4], out: &mut Vec<Vector4>) {
+    let simd = SimdMatrix::new(matrix);
+    out.extend(vectors.iter().map(|vector| simd.transform(*vector)));
+}
+
 /// Creates a 4x4 matrix from 16 float values, specified in traditional row-major order.
 pub fn new_matrix4(
     // Row 0
@@ -49,6 +88,17 @@ pub fn new_matrix3(
     )
 }
 
+/// Takes world-space normals back to the space of `model`: the transpose of its
+/// 3x3 part, which is the inverse-transpose of the matrix normals go out with
+pub fn world_to_model_normal_matrix(model: &Matrix) -> Matrix {
+    // Las columnas del modelo pasan a ser las filas; la traslación no afecta a normales
+    new_matrix3(
+        model.m0, model.m1, model.m2,
+        model.m4, model.m5, model.m6,
+        model.m8, model.m9, model.m10,
+    )
+}
+
 /// Creates a model matrix combining translation, scale, and rotation
 pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3) -> Matrix {
     let (sin_x, cos_x) = rotation.x.sin_cos();
@@ -160,6 +210,52 @@ pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) ->
     )
 }
 
+/// Creates an orthographic projection matrix (same depth convention as the perspective one)
+/// half_width, half_height: Half the size of the visible area, in world units
+/// near: Near clipping plane distance
+/// far: Far clipping plane distance
+pub fn create_orthographic_matrix(half_width: f32, half_height: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        1.0 / half_width, 0.0, 0.0, 0.0,
+        0.0, 1.0 / half_height, 0.0, 0.0,
+        0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Creates a reverse-Z perspective projection matrix with an infinite far plane:
+/// the near plane maps to NDC depth 1 and infinity to 0, so the depth values of
+/// far surfaces use the fine spacing floats have near zero
+pub fn create_reverse_projection_matrix(fov_y: f32, aspect: f32, near: f32) -> Matrix {
+    let tan_half_fov = (fov_y / 2.0).tan();
+
+    new_matrix4(
+        1.0 / (aspect * tan_half_fov), 0.0, 0.0, 0.0,
+        0.0, 1.0 / tan_half_fov, 0.0, 0.0,
+        0.0, 0.0, 0.0, near,
+        0.0, 0.0, -1.0, 0.0,
+    )
+}
+
+/// Creates a reverse-Z orthographic projection matrix: near maps to NDC depth 1
+/// and far to 0 (an orthographic view cannot have an infinite far plane)
+pub fn create_reverse_orthographic_matrix(half_width: f32, half_height: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        1.0 / half_width, 0.0, 0.0, 0.0,
+        0.0, 1.0 / half_height, 0.0, 0.0,
+        0.0, 0.0, 1.0 / (far - near), far / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Distance in front of the camera of a point whose NDC depth is `ndc_z`;
+/// undoes any of the projections above (perspective or orthographic, standard or reverse-Z)
+pub fn view_depth(ndc_z: f32, projection: &Matrix) -> f32 {
+    // clip.z = m10 * z + m14, clip.w = m11 * z + m15 y ndc_z = clip.z / clip.w: se despeja z
+    let z = (projection.m14 - ndc_z * projection.m15) / (ndc_z * projection.m11 - projection.m10);
+    -z
+}
+
 /// Creates a viewport matrix to transform NDC coordinates to screen space
 /// x, y: Viewport position (typically 0, 0)
 /// width, height: Viewport dimensions in pixels
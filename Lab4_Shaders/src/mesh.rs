@@ -0,0 +1,316 @@
+// mesh.rs
+// Esferas generadas por código, para elegir la densidad de la malla al ejecutar
+// en lugar de depender solo de models/sphere.obj
+use crate::noise::fbm;
+use crate::obj::Obj;
+use crate::shaders::{RING_INNER_RADIUS, RING_OUTER_RADIUS};
+use crate::vertex::Vertex;
+use crate::math::{Vector2, Vector3};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+// Mismo radio que models/sphere.obj, para que los shaders (que usan la posición) se vean igual
+pub const SPHERE_RADIUS: f32 = 0.5;
+
+/// Which sphere mesh to render: the bundled OBJ or a generated one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeshSpec {
+    Obj,
+    UvSphere { slices: u32, stacks: u32 },
+    Icosphere { subdivisions: u32 },
+}
+
+impl FromStr for MeshSpec {
+    type Err = String;
+
+    /// Accepts `obj`, `uv:<slices>x<stacks>` or `ico:<subdivisions>`
+    fn from_str(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid mesh: {} (expected obj, uv:<slices>x<stacks> or ico:<n>)", spec);
+
+        match spec.split_once(':') {
+            None if spec == "obj" => Ok(MeshSpec::Obj),
+            Some(("uv", size)) => {
+                let (slices, stacks) = size.split_once('x').ok_or_else(invalid)?;
+                let slices: u32 = slices.parse().map_err(|_| invalid())?;
+                let stacks: u32 = stacks.parse().map_err(|_| invalid())?;
+                if slices < 3 || stacks < 2 {
+                    return Err(format!("uv sphere needs at least 3 slices and 2 stacks: {}", spec));
+                }
+                Ok(MeshSpec::UvSphere { slices, stacks })
+            }
+            Some(("ico", subdivisions)) => {
+                let subdivisions: u32 = subdivisions.parse().map_err(|_| invalid())?;
+                // Cada subdivisión multiplica los triángulos por 4 (ico:7 ya son 327680)
+                if subdivisions > 7 {
+                    return Err(format!("icosphere subdivisions must be at most 7: {}", spec));
+                }
+                Ok(MeshSpec::Icosphere { subdivisions })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl MeshSpec {
+    /// Builds the triangle list (three vertices per triangle, like `Obj::get_vertex_array`)
+    pub fn build(&self) -> Result<Vec<Vertex>, String> {
+        match *self {
+            MeshSpec::Obj => Obj::load("./models/sphere.obj")
+                .map(|obj| obj.get_vertex_array())
+                .map_err(|e| format!("./models/sphere.obj: {}", e)),
+            MeshSpec::UvSphere { slices, stacks } => Ok(generate_uv_sphere(slices, stacks)),
+            MeshSpec::Icosphere { subdivisions } => Ok(generate_icosphere(subdivisions)),
+        }
+    }
+}
+
+/// Vertex on the sphere for a unit direction; the normal is the direction itself
+fn sphere_vertex(direction: Vector3, tex_coords: Vector2) -> Vertex {
+    let position = Vector3::new(
+        direction.x * SPHERE_RADIUS,
+        direction.y * SPHERE_RADIUS,
+        direction.z * SPHERE_RADIUS,
+    );
+    Vertex::new(position, direction, tex_coords)
+}
+
+/// Latitude/longitude sphere. `slices` divide the equator, `stacks` go from pole
+/// to pole. u follows the longitude and v goes from 0 at +Y to 1 at -Y.
+pub fn generate_uv_sphere(slices: u32, stacks: u32) -> Vec<Vertex> {
+    let slices = slices.max(3);
+    let stacks = stacks.max(2);
+
+    let point = |slice: u32, stack: u32| {
+        let u = slice as f32 / slices as f32;
+        let v = stack as f32 / stacks as f32;
+        let theta = v * PI;       // ángulo desde el polo norte
+        let phi = u * 2.0 * PI;   // longitud
+        let direction = Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+        sphere_vertex(direction, Vector2::new(u, v))
+    };
+
+    let mut vertices = Vec::with_capacity((slices * stacks * 6) as usize);
+    for stack in 0..stacks {
+        for slice in 0..slices {
+            let top_left = point(slice, stack);
+            let top_right = point(slice + 1, stack);
+            let bottom_left = point(slice, stack + 1);
+            let bottom_right = point(slice + 1, stack + 1);
+
+            // En los polos el quad se reduce a un triángulo
+            if stack != 0 {
+                vertices.extend([top_left.clone(), top_right, bottom_right.clone()]);
+            }
+            if stack != stacks - 1 {
+                vertices.extend([top_left, bottom_right, bottom_left]);
+            }
+        }
+    }
+    vertices
+}
+
+/// Geodesic sphere: an icosahedron whose faces are split into 4 `subdivisions`
+/// times, giving 20 * 4^n evenly sized triangles
+pub fn generate_icosphere(subdivisions: u32) -> Vec<Vertex> {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut points: Vec<Vector3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|&(x, y, z)| Vector3::new(x, y, z).normalized())
+    .collect();
+
+    let mut faces: Vec<[usize; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Los puntos medios se comparten entre caras vecinas
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, points: &mut Vec<Vector3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (pa, pb) = (points[a], points[b]);
+                points.push(Vector3::new(pa.x + pb.x, pa.y + pb.y, pa.z + pb.z).normalized());
+                points.len() - 1
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces {
+            let ab = midpoint(a, b, &mut points);
+            let bc = midpoint(b, c, &mut points);
+            let ca = midpoint(c, a, &mut points);
+            subdivided.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        faces = subdivided;
+    }
+
+    let mut vertices = Vec::with_capacity(faces.len() * 3);
+    for face in faces {
+        let directions = face.map(|index| points[index]);
+        let mut uvs = directions.map(spherical_uv);
+
+        // Costura: si el triángulo cruza u = 0/1, llevar los u pequeños al otro lado
+        let max_u = uvs.iter().fold(0.0_f32, |max, uv| max.max(uv.x));
+        if max_u > 0.75 {
+            for uv in uvs.iter_mut() {
+                if uv.x < 0.25 {
+                    uv.x += 1.0;
+                }
+            }
+        }
+
+        // En los polos u no está definido; usar el promedio de los otros dos vértices
+        for i in 0..3 {
+            if directions[i].y.abs() > 0.9999 {
+                let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                uvs[i].x = (uvs[j].x + uvs[k].x) / 2.0;
+            }
+        }
+
+        for i in 0..3 {
+            vertices.push(sphere_vertex(directions[i], uvs[i]));
+        }
+    }
+    vertices
+}
+
+/// Lumpy rock for small bodies like the comet's nucleus: an icosphere whose points
+/// are pushed in and out by up to `roughness` of the radius, with flat face normals
+pub fn generate_nucleus(subdivisions: u32, roughness: f32) -> Vec<Vertex> {
+    let mut vertices = generate_icosphere(subdivisions);
+    // Los vértices repetidos entre caras tienen la misma posición, así que reciben
+    // el mismo desplazamiento y la malla no se abre
+    for vertex in &mut vertices {
+        let bump = fbm(&(vertex.normal * 2.5), 3);
+        vertex.position *= 1.0 + roughness * bump;
+    }
+    for triangle in vertices.chunks_mut(3) {
+        let (a, b, c) = (triangle[0].position, triangle[1].position, triangle[2].position);
+        let normal = (b - a).cross(c - a).normalized();
+        for vertex in triangle {
+            vertex.normal = normal;
+        }
+    }
+    vertices
+}
+
+/// Same mapping as `generate_uv_sphere`: u from the longitude, v from the angle to +Y
+fn spherical_uv(direction: Vector3) -> Vector2 {
+    let mut u = direction.z.atan2(direction.x) / (2.0 * PI);
+    if u < 0.0 {
+        u += 1.0;
+    }
+    let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+    Vector2::new(u, v)
+}
+
+/// Flat annulus in the XZ plane between radii `inner` and `outer`, facing +Y.
+/// u goes around the ring and v from the inner (0) to the outer (1) edge.
+pub fn generate_ring(inner: f32, outer: f32, segments: u32) -> Vec<Vertex> {
+    let segments = segments.max(3);
+    let normal = Vector3::new(0.0, 1.0, 0.0);
+
+    let point = |segment: u32, radius: f32, v: f32| {
+        let u = segment as f32 / segments as f32;
+        let angle = u * 2.0 * PI;
+        let position = Vector3::new(radius * angle.cos(), 0.0, radius * angle.sin());
+        Vertex::new(position, normal, Vector2::new(u, v))
+    };
+
+    let mut vertices = Vec::with_capacity((segments * 6) as usize);
+    for segment in 0..segments {
+        let inner_a = point(segment, inner, 0.0);
+        let inner_b = point(segment + 1, inner, 0.0);
+        let outer_a = point(segment, outer, 1.0);
+        let outer_b = point(segment + 1, outer, 1.0);
+
+        // Antihorario visto desde +Y
+        vertices.extend([inner_a.clone(), inner_b.clone(), outer_b.clone()]);
+        vertices.extend([inner_a, outer_b, outer_a]);
+    }
+    vertices
+}
+
+/// Every mesh the scene draws, built once at startup
+pub struct SceneMeshes {
+    pub sphere: LodMesh,
+    pub ring: Vec<Vertex>,
+    pub nucleus: Vec<Vertex>,  // núcleo del cometa
+    pub asteroid: Vec<Vertex>, // roca base del cinturón y de las lunas capturadas
+}
+
+impl SceneMeshes {
+    /// The scene's meshes around `sphere`, which draws the planet, the round moons and the sun
+    pub fn new(sphere: LodMesh) -> Self {
+        SceneMeshes {
+            sphere,
+            ring: generate_ring(RING_INNER_RADIUS, RING_OUTER_RADIUS, 96),
+            nucleus: generate_nucleus(2, 0.35),
+            asteroid: generate_nucleus(1, 0.45),
+        }
+    }
+}
+
+/// One resolution of a `LodMesh`, used while the body covers at least `min_radius` pixels
+pub struct LodLevel {
+    pub min_radius: f32,
+    pub vertices: Vec<Vertex>,
+}
+
+/// Several resolutions of the same sphere; each body picks one per frame from
+/// its projected size on screen
+pub struct LodMesh {
+    levels: Vec<LodLevel>, // ordenados de menor a mayor min_radius
+}
+
+impl LodMesh {
+    /// A single resolution, used at every size (LOD disabled)
+    pub fn single(vertices: Vec<Vertex>) -> Self {
+        LodMesh { levels: vec![LodLevel { min_radius: 0.0, vertices }] }
+    }
+
+    pub fn new(mut levels: Vec<LodLevel>) -> Self {
+        assert!(!levels.is_empty(), "LodMesh needs at least one level");
+        levels.sort_by(|a, b| a.min_radius.total_cmp(&b.min_radius));
+        LodMesh { levels }
+    }
+
+    /// Default chain of icospheres: 80 triangles for tiny bodies, 320 for small
+    /// ones and 1280 once the body is larger than 40 px
+    pub fn icospheres() -> Self {
+        LodMesh::new(vec![
+            LodLevel { min_radius: 0.0, vertices: generate_icosphere(1) },
+            LodLevel { min_radius: 12.0, vertices: generate_icosphere(2) },
+            LodLevel { min_radius: 40.0, vertices: generate_icosphere(3) },
+        ])
+    }
+
+    /// Index of the finest level whose `min_radius` is covered by `projected_radius`
+    pub fn level_for(&self, projected_radius: f32) -> usize {
+        self.levels
+            .iter()
+            .rposition(|level| projected_radius >= level.min_radius)
+            .unwrap_or(0)
+    }
+
+    pub fn select(&self, projected_radius: f32) -> &[Vertex] {
+        &self.levels[self.level_for(projected_radius)].vertices
+    }
+}
+
+/// Approximate radius in pixels of a sphere seen through a perspective camera
+/// with vertical field of view `fov_y`, on a viewport `viewport_height` pixels tall
+pub fn projected_radius(center: Vector3, radius: f32, eye: Vector3, fov_y: f32, viewport_height: f32) -> f32 {
+    let distance = (center - eye).length();
+    if distance <= radius {
+        return f32::INFINITY; // la cámara está dentro de la esfera
+    }
+    radius / (distance * (fov_y / 2.0).tan()) * (viewport_height / 2.0)
+}
@@ -0,0 +1,121 @@
+// moons.rs
+// Lunas de un planeta: cada una con su tamaño, su órbita (elementos orbitales como
+// los del cometa) y su superficie. Todas se dibujan con la misma llamada de
+// render_scene; lo único propio de cada tipo es el shader y la malla.
+use crate::math::*;
+use crate::comet::eccentric_anomaly;
+use crate::matrix::create_model_matrix;
+use crate::mesh::SPHERE_RADIUS;
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+/// Surface of a moon: which color function shades it and which mesh it uses
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MoonShader {
+    #[default]
+    Rocky,    // cráteres y mares, como la Luna
+    Icy,      // hielo claro cruzado por grietas rojizas, como Europa
+    Captured, // asteroide capturado: roca oscura e irregular que da tumbos
+}
+
+impl MoonShader {
+    pub const ALL: [MoonShader; 3] = [MoonShader::Rocky, MoonShader::Icy, MoonShader::Captured];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MoonShader::Rocky => "rocky",
+            MoonShader::Icy => "icy",
+            MoonShader::Captured => "captured",
+        }
+    }
+
+    /// Whether it is drawn with the lumpy asteroid mesh instead of the sphere
+    pub fn irregular(self) -> bool {
+        self == MoonShader::Captured
+    }
+}
+
+impl FromStr for MoonShader {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        MoonShader::ALL
+            .into_iter()
+            .find(|shader| shader.name() == name)
+            .ok_or_else(|| format!("invalid moon shader: {} (expected rocky, icy or captured)", name))
+    }
+}
+
+/// One moon: its size relative to the sphere mesh, its orbit around the planet
+/// and its surface. Angles are in radians.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Moon {
+    pub name: String,
+    pub scale: f32,        // tamaño relativo a la malla del planeta
+    pub distance: f32,     // semieje mayor de la órbita
+    pub period: f32,       // segundos por vuelta; negativo = órbita retrógrada
+    pub eccentricity: f32, // 0 = circular
+    pub inclination: f32,  // inclinación del plano de la órbita respecto del ecuador (XZ)
+    pub node: f32,         // giro en Y de la línea donde la órbita cruza el ecuador
+    pub phase: f32,        // anomalía media a tiempo 0
+    pub shader: MoonShader,
+}
+
+impl Default for Moon {
+    /// The lab's original moon: rocky, 3 units away, one orbit every 4π seconds
+    fn default() -> Self {
+        Moon {
+            name: String::from("Luna"),
+            scale: 0.3,
+            distance: 3.0,
+            period: 4.0 * PI,
+            eccentricity: 0.0,
+            inclination: 0.17,
+            node: 0.0,
+            phase: 0.0,
+            shader: MoonShader::Rocky,
+        }
+    }
+}
+
+impl Moon {
+    /// Center of the moon on its orbit at time `time`, with the planet at a focus
+    pub fn position(&self, time: f32) -> Vector3 {
+        let eccentric = eccentric_anomaly(self.phase + time * 2.0 * PI / self.period, self.eccentricity);
+        let semi_minor = self.distance * (1.0 - self.eccentricity * self.eccentricity).sqrt();
+        let x = self.distance * (eccentric.cos() - self.eccentricity);
+        let z = semi_minor * eccentric.sin();
+
+        // Se inclina el plano alrededor de X y después se gira la línea de nodos en Y
+        let (y, z) = (z * self.inclination.sin(), z * self.inclination.cos());
+        let (sin_node, cos_node) = self.node.sin_cos();
+        Vector3::new(x * cos_node + z * sin_node, y, z * cos_node - x * sin_node)
+    }
+
+    /// Radius of the sphere that always contains the moon's mesh
+    pub fn radius(&self) -> f32 {
+        // La malla irregular sobresale de la esfera: se cubre con margen, como el cometa
+        let margin = if self.shader.irregular() { 1.5 } else { 1.0 };
+        SPHERE_RADIUS * self.scale * margin
+    }
+
+    /// Farthest the moon's surface gets from the planet
+    pub fn reach(&self) -> f32 {
+        self.distance * (1.0 + self.eccentricity) + self.radius()
+    }
+
+    /// Model matrix at `time`. Round moons are tidally locked: they turn once per
+    /// orbit so that their local +X side always faces the planet. Captured
+    /// asteroids tumble instead.
+    pub fn model_matrix(&self, time: f32) -> Matrix {
+        let position = self.position(time);
+        // El giro va aparte porque create_model_matrix rota después de trasladar y
+        // movería la luna por la órbita
+        let rotation = if self.shader.irregular() {
+            Vector3::new(time * 0.9 + self.phase, time * 0.5, time * 0.3)
+        } else {
+            Vector3::new(0.0, position.z.atan2(-position.x), 0.0)
+        };
+        create_model_matrix(Vector3::zero(), self.scale, rotation) * create_model_matrix(position, 1.0, Vector3::zero())
+    }
+}
@@ -0,0 +1,100 @@
+// noise.rs
+// Funciones de ruido procedural usadas por los shaders de los planetas y del sol.
+use crate::math::*;
+
+// Hash de la celda entera de `pos`, con signo: en (-1, 1) porque `%` conserva el
+// signo del seno. Es la base de value_noise y fractal_noise, que esperan valores centrados en 0
+fn signed_noise(pos: &Vector3) -> f32 {
+    let x = pos.x as i32;
+    let y = pos.y as i32;
+    let z = pos.z as i32;
+    
+    let n = (x.wrapping_add(y.wrapping_mul(57)).wrapping_add(z.wrapping_mul(113))) as f32;
+    ((n * n * 41597.5453).sin() * 43758.5453) % 1.0
+}
+
+// Función auxiliar para calcular ruido simple: un valor aleatorio en [0, 1) por celda
+// entera. Nunca es negativo, así se puede sumar a un color sin oscurecerlo
+pub fn noise(pos: &Vector3) -> f32 {
+    signed_noise(pos).abs()
+}
+
+// Función para generar ruido fractal (más suave). Con signo, en (-2, 2): quien lo
+// usa como color lo lleva a [0, 1] con `* 0.5 + 0.5`
+pub fn fractal_noise(pos: &Vector3, octaves: i32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    
+    for _ in 0..octaves {
+        value += signed_noise(&Vector3::new(pos.x * frequency, pos.y * frequency, pos.z * frequency)) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    
+    value
+}
+
+// Ruido de valor suave: interpola trilinealmente noise() entre los 8 vértices
+// enteros de la celda, así los patrones no salen en bloques. En (-1, 1)
+pub fn value_noise(pos: &Vector3) -> f32 {
+    let cell = Vector3::new(pos.x.floor(), pos.y.floor(), pos.z.floor());
+    let fade = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (fx, fy, fz) = (fade(pos.x - cell.x), fade(pos.y - cell.y), fade(pos.z - cell.z));
+    let corner = |dx: f32, dy: f32, dz: f32| signed_noise(&Vector3::new(cell.x + dx, cell.y + dy, cell.z + dz));
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let x00 = lerp(corner(0.0, 0.0, 0.0), corner(1.0, 0.0, 0.0), fx);
+    let x10 = lerp(corner(0.0, 1.0, 0.0), corner(1.0, 1.0, 0.0), fx);
+    let x01 = lerp(corner(0.0, 0.0, 1.0), corner(1.0, 0.0, 1.0), fx);
+    let x11 = lerp(corner(0.0, 1.0, 1.0), corner(1.0, 1.0, 1.0), fx);
+    lerp(lerp(x00, x10, fy), lerp(x01, x11, fy), fz)
+}
+
+// Movimiento browniano fraccional: octavas de value_noise, cada una al doble de
+// frecuencia y la mitad de amplitud. Resultado aproximadamente en [-1, 1]
+pub fn fbm(pos: &Vector3, octaves: i32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+
+    for _ in 0..octaves {
+        value += value_noise(&(*pos * frequency)) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+
+    value
+}
+
+/// Cellular (Worley) noise: distances from `pos` to the nearest and second
+/// nearest feature points, one jittered point per integer cell
+pub fn worley(pos: &Vector3) -> (f32, f32) {
+    let cell = Vector3::new(pos.x.floor(), pos.y.floor(), pos.z.floor());
+    let mut nearest = f32::MAX;
+    let mut second = f32::MAX;
+
+    // El punto más cercano siempre está en la celda o en una de sus 26 vecinas
+    for dz in -1..=1 {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let neighbor = cell + Vector3::new(dx as f32, dy as f32, dz as f32);
+                let distance = (feature_point(&neighbor) - *pos).length();
+                if distance < nearest {
+                    second = nearest;
+                    nearest = distance;
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+        }
+    }
+
+    (nearest, second)
+}
+
+// Punto característico de una celda: posición pseudoaleatoria (fija) dentro de ella
+fn feature_point(cell: &Vector3) -> Vector3 {
+    let jitter = |offset: f32| noise(&Vector3::new(cell.x + offset, cell.y, cell.z)).abs();
+    *cell + Vector3::new(jitter(0.0), jitter(1013.0), jitter(2029.0))
+}
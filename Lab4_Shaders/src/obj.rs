@@ -1,6 +1,6 @@
 // obj.rs
 use crate::vertex::Vertex;
-use raylib::math::{Vector2, Vector3};
+use crate::math::{Vector2, Vector3};
 use tobj;
 
 pub struct Obj {
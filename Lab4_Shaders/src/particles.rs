@@ -0,0 +1,112 @@
+// particles.rs
+// Sistema de partículas sencillo: cada partícula es un punto con velocidad y
+// tiempo de vida que se integra en la CPU y se dibuja como un disco difuso
+// (billboard) sumado al buffer de glow, así se mezcla de forma aditiva.
+use crate::math::*;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::GlobalUniforms;
+
+#[derive(Clone, Debug)]
+pub struct Particle {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub age: f32,      // segundos desde que nació
+    pub lifetime: f32, // segundos que vive en total
+    pub size: f32,     // radio en unidades del mundo
+}
+
+impl Particle {
+    /// 0 when just spawned, 1 when it dies
+    pub fn life_fraction(&self) -> f32 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+pub struct ParticleSystem {
+    pub particles: Vec<Particle>,
+    max_particles: usize,
+    rng: SmallRng,
+    spawn_debt: f32, // fracción de partícula pendiente entre frames
+}
+
+impl ParticleSystem {
+    /// Empty system; the seed makes the random jitter reproducible (headless, tests)
+    pub fn new(max_particles: usize, seed: u64) -> Self {
+        ParticleSystem {
+            particles: Vec::with_capacity(max_particles),
+            max_particles,
+            rng: SmallRng::seed_from_u64(seed),
+            spawn_debt: 0.0,
+        }
+    }
+
+    /// Uniform random vector in the cube [-1, 1]^3
+    pub fn random_vector(&mut self) -> Vector3 {
+        Vector3::new(
+            self.rng.random_range(-1.0..=1.0),
+            self.rng.random_range(-1.0..=1.0),
+            self.rng.random_range(-1.0..=1.0),
+        )
+    }
+
+    /// Adds a particle unless the system is full
+    pub fn spawn(&mut self, particle: Particle) {
+        if self.particles.len() < self.max_particles {
+            self.particles.push(particle);
+        }
+    }
+
+    /// How many particles to emit this frame for a steady `rate` per second;
+    /// the fractional remainder carries over to the next frame
+    pub fn emit_count(&mut self, rate: f32, dt: f32) -> usize {
+        self.spawn_debt += rate * dt.max(0.0);
+        let count = self.spawn_debt.floor();
+        self.spawn_debt -= count;
+        count as usize
+    }
+
+    /// Ages and moves every particle by `dt` under `acceleration`, dropping the dead ones
+    pub fn update(&mut self, dt: f32, acceleration: impl Fn(&Particle) -> Vector3) {
+        for particle in &mut self.particles {
+            particle.velocity += acceleration(particle) * dt;
+            particle.position += particle.velocity * dt;
+            particle.age += dt;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+    }
+
+    /// Draws each particle as a camera-facing soft disc, added to the glow buffer
+    /// behind the opaque geometry's depth test. `color` gives its light from the particle.
+    pub fn render(&self, framebuffer: &mut Framebuffer, globals: &GlobalUniforms, color: impl Fn(&Particle) -> Vector3) {
+        let view = &globals.view_matrix;
+        let right = Vector3::new(view.m0, view.m4, view.m8);
+
+        for particle in &self.particles {
+            let Some(center) = globals.project(particle.position) else {
+                continue;
+            };
+            let Some(edge) = globals.project(particle.position + right * particle.size) else {
+                continue;
+            };
+            // Al menos medio píxel para que las partículas lejanas no desaparezcan
+            let radius = (edge.x - center.x).hypot(edge.y - center.y).max(0.5);
+            let light = color(particle);
+
+            let min_x = (center.x - radius).floor().max(0.0) as i32;
+            let max_x = (center.x + radius).ceil().min(framebuffer.width as f32 - 1.0) as i32;
+            let min_y = (center.y - radius).floor().max(0.0) as i32;
+            let max_y = (center.y + radius).ceil().min(framebuffer.height as f32 - 1.0) as i32;
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let distance = (x as f32 + 0.5 - center.x).hypot(y as f32 + 0.5 - center.y) / radius;
+                    if distance < 1.0 {
+                        let falloff = (1.0 - distance * distance).powi(2);
+                        framebuffer.add_glow(x, y, light * falloff, center.z);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// picking.rs
+// Selección de cuerpos con el mouse: el píxel se lleva de vuelta al mundo con la
+// inversa de vista y proyección, y el rayo resultante se prueba contra la esfera
+// envolvente de cada cuerpo. El seleccionado se marca con un contorno.
+use crate::math::*;
+use crate::comet::{comet_position, COMET_SCALE};
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::matrix::multiply_matrix_vector4;
+use crate::mesh::SPHERE_RADIUS;
+use crate::moons::Moon;
+use crate::planets::PlanetType;
+use crate::sun::SUN_SCALE;
+use crate::uniforms::GlobalUniforms;
+
+const OUTLINE_COLOR: Vector3 = Vector3::new(1.0, 0.85, 0.3);
+const OUTLINE_WIDTH: f32 = 2.0; // píxeles
+const OUTLINE_GAP: f32 = 3.0;   // separación entre la silueta y el contorno, en píxeles
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Body {
+    Planet,
+    Moon(usize), // índice en la lista de lunas del planeta
+    Sun,
+    Comet,
+}
+
+impl Body {
+    /// Every body of a scene whose planet has `moons`
+    pub fn all(moons: &[Moon]) -> impl Iterator<Item = Body> {
+        [Body::Planet, Body::Sun, Body::Comet].into_iter().chain((0..moons.len()).map(Body::Moon))
+    }
+
+    /// Whether the body is still in a scene with `moons` (a reloaded preset may have fewer)
+    pub fn exists(self, moons: &[Moon]) -> bool {
+        match self {
+            Body::Moon(index) => index < moons.len(),
+            _ => true,
+        }
+    }
+
+    pub fn name(self, moons: &[Moon]) -> &str {
+        match self {
+            Body::Planet => "Planeta",
+            Body::Moon(index) => &moons[index].name,
+            Body::Sun => "Sol",
+            Body::Comet => "Cometa",
+        }
+    }
+
+    /// Center and radius of the body's bounding sphere at time `time`; a moon
+    /// must be one of `moons`
+    pub fn sphere(self, time: f32, light: &Light, moons: &[Moon]) -> (Vector3, f32) {
+        match self {
+            Body::Planet => (Vector3::zero(), SPHERE_RADIUS),
+            Body::Moon(index) => (moons[index].position(time), moons[index].radius()),
+            Body::Sun => (light.position, SPHERE_RADIUS * SUN_SCALE),
+            // El núcleo es irregular: la esfera lo cubre con algo de margen
+            Body::Comet => (comet_position(time), SPHERE_RADIUS * COMET_SCALE * 1.5),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3, // normalizada
+}
+
+impl Ray {
+    /// Ray from the camera through pixel (`x`, `y`) of a `width` x `height` screen:
+    /// the pixel is un-projected at the near and far planes with the inverse of
+    /// view * projection, so it works for both perspective and orthographic views
+    pub fn through_pixel(x: f32, y: f32, width: f32, height: f32, view: &Matrix, projection: &Matrix) -> Ray {
+        // Inversa de la matriz de viewport (que invierte el eje Y)
+        let ndc_x = 2.0 * x / width - 1.0;
+        let ndc_y = 1.0 - 2.0 * y / height;
+        let inverse = (*view * *projection).inverted();
+        let unproject = |ndc_z: f32| {
+            let world = multiply_matrix_vector4(&inverse, &Vector4::new(ndc_x, ndc_y, ndc_z, 1.0));
+            Vector3::new(world.x / world.w, world.y / world.w, world.z / world.w)
+        };
+
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        Ray { origin: near, direction: (far - near).normalized() }
+    }
+
+    /// Distance along the ray to a sphere (0 if the ray starts inside it)
+    pub fn intersect_sphere(&self, center: Vector3, radius: f32) -> Option<f32> {
+        let offset = self.origin - center;
+        let b = offset.dot(self.direction);
+        let c = offset.dot(offset) - radius * radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        if -b - root >= 0.0 {
+            Some(-b - root)
+        } else if -b + root >= 0.0 {
+            Some(0.0)
+        } else {
+            None
+        }
+    }
+}
+
+/// The nearest body the ray hits, if any
+pub fn pick(ray: &Ray, time: f32, light: &Light, moons: &[Moon]) -> Option<Body> {
+    Body::all(moons)
+        .filter_map(|body| {
+            let (center, radius) = body.sphere(time, light, moons);
+            ray.intersect_sphere(center, radius).map(|distance| (distance, body))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, body)| body)
+}
+
+/// HUD lines for the selected body: where it is and, for the planet and its
+/// moons, their parameters
+pub fn describe(body: Body, time: f32, light: &Light, camera_position: Vector3, planet: &PlanetType) -> Vec<String> {
+    let moons = &planet.params.moons;
+    let (center, radius) = body.sphere(time, light, moons);
+    let mut lines = vec![
+        format!("Seleccion: {}", body.name(moons)),
+        format!("  posicion: ({:.2}, {:.2}, {:.2})", center.x, center.y, center.z),
+        format!("  radio: {:.2}", radius),
+        format!("  distancia: {:.2}", (center - camera_position).length()),
+    ];
+    if body == Body::Planet {
+        let params = &planet.params;
+        let yes_no = |value: bool| if value { "si" } else { "no" };
+        lines.push(format!("  tipo: {}", planet.name));
+        lines.push(format!("  rotacion: {:.2} rad/s", params.rotation_speed));
+        lines.push(format!("  inclinacion: {:.1} grados", params.axial_tilt.to_degrees()));
+        lines.push(format!("  escala ruido: {:.2}", params.noise_scale));
+        lines.push(format!("  frec. bandas: {:.2}", params.band_frequency));
+        lines.push(format!("  anillos: {}  atmosfera: {}", yes_no(params.rings), yes_no(params.atmosphere)));
+        lines.push(format!("  lunas: {}", moons.len()));
+    }
+    if let Body::Moon(index) = body {
+        let moon = &moons[index];
+        lines.push(format!("  superficie: {}", moon.shader.name()));
+        lines.push(format!("  orbita: {:.2} de radio, {:.1} s por vuelta", moon.distance, moon.period.abs()));
+        lines.push(format!("  excentricidad: {:.2}  inclinacion: {:.1} grados", moon.eccentricity, moon.inclination.to_degrees()));
+    }
+    lines
+}
+
+/// Draws a thin ring just outside the silhouette of the sphere (`center`,
+/// `radius`); surfaces in front of the body hide it
+pub fn draw_outline(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, center: Vector3, radius: f32) {
+    let Some(screen_center) = globals.project(center) else {
+        return;
+    };
+    let view = &globals.view_matrix;
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let Some(screen_edge) = globals.project(center + right * radius) else {
+        return;
+    };
+
+    let inner = (screen_edge.x - screen_center.x).hypot(screen_edge.y - screen_center.y) + OUTLINE_GAP;
+    let outer = inner + OUTLINE_WIDTH;
+    let min_x = (screen_center.x - outer).floor().max(0.0) as i32;
+    let max_x = (screen_center.x + outer).ceil().min(framebuffer.width as f32 - 1.0) as i32;
+    let min_y = (screen_center.y - outer).floor().max(0.0) as i32;
+    let max_y = (screen_center.y + outer).ceil().min(framebuffer.height as f32 - 1.0) as i32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let distance = (x as f32 + 0.5 - screen_center.x).hypot(y as f32 + 0.5 - screen_center.y);
+            if (inner..outer).contains(&distance) {
+                framebuffer.add_glow(x, y, OUTLINE_COLOR, screen_center.z);
+            }
+        }
+    }
+}
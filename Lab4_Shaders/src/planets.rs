@@ -0,0 +1,220 @@
+// planets.rs
+use crate::math::*;
+use std::collections::HashMap;
+use crate::gradient::Gradient;
+use crate::moons::Moon;
+use crate::tonemap::ColorSpace;
+use crate::shaders::{
+    VertexEffect,
+    rocky_planet_color, rocky_planet_lava, gas_giant_color, rainbow_planet_color, glitter_planet_color, heart_planet_color,
+    earth_planet_color, earth_planet_gloss, ice_planet_color, ice_planet_glow, rocky_planet_height,
+};
+
+/// Color function of a planet type: (position on the sphere, time, parameters) -> color
+pub type PlanetColorFn = fn(&Vector3, f32, &PlanetParams) -> Vector3;
+
+/// Specular strength of a planet type in [0, 1], with the same inputs as its color function
+pub type PlanetGlossFn = fn(&Vector3, f32, &PlanetParams) -> f32;
+
+/// Relief of a planet type in [-1, 1] (valleys to peaks), with the same inputs as
+/// its color function; `PlanetParams::displacement` turns it into mesh units
+pub type PlanetHeightFn = fn(&Vector3, f32, &PlanetParams) -> f32;
+
+// Color usado cuando un shader pide un color que el preset no define
+const MISSING_COLOR: Vector3 = Vector3::new(1.0, 0.0, 1.0);
+
+/// Parameters a planet type is registered with; presets in planets.toml override them
+#[derive(Clone, Debug)]
+pub struct PlanetParams {
+    pub rotation_speed: f32, // velocidad de rotación axial (rad/s)
+    pub axial_tilt: f32,     // oblicuidad: inclinación del eje de giro (radianes)
+    pub precession_speed: f32, // giro del eje inclinado alrededor de la vertical (rad/s)
+    pub noise_scale: f32,    // multiplicador de la frecuencia de todos los ruidos
+    pub band_frequency: f32, // frecuencia de bandas / patrones
+    pub rings: bool,         // dibujar anillos alrededor del planeta
+    pub asteroids: bool,     // dibujar el cinturón de asteroides
+    pub atmosphere: bool,    // brillo atmosférico en el borde
+    pub aurora: bool,        // auroras en los polos, sobre el lado nocturno
+    pub displacement: f32,   // altura máxima del relieve en unidades de la malla; 0 = esfera lisa
+    pub triplanar: bool,     // textura de roca sobre el terreno, proyectada en tres planos con la normal
+    pub effect: VertexEffect, // deformación animada de la forma (pulso, ondas, torsión)
+    pub moons: Vec<Moon>,     // lunas en órbita, cada una con su shader
+    pub colors: HashMap<String, Vector3>,
+    pub gradients: HashMap<String, Gradient>, // rampas que los shaders muestrean con un valor en vez de umbrales fijos
+}
+
+impl PlanetParams {
+    /// Named color of the palette, magenta if the preset does not define it
+    pub fn color(&self, name: &str) -> Vector3 {
+        self.colors.get(name).copied().unwrap_or(MISSING_COLOR)
+    }
+
+    /// Named gradient sampled at `t`, magenta if the preset does not define it
+    pub fn gradient(&self, name: &str, t: f32) -> Vector3 {
+        self.gradients.get(name).map_or(MISSING_COLOR, |gradient| gradient.sample(t))
+    }
+}
+
+impl Default for PlanetParams {
+    fn default() -> Self {
+        PlanetParams {
+            rotation_speed: 0.3,
+            axial_tilt: 0.0,
+            precession_speed: 0.0,
+            noise_scale: 1.0,
+            band_frequency: 4.0,
+            rings: false,
+            asteroids: false,
+            atmosphere: false,
+            aurora: false,
+            displacement: 0.0,
+            triplanar: false,
+            effect: VertexEffect::None,
+            moons: vec![Moon::default()],
+            colors: HashMap::new(),
+            gradients: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PlanetType {
+    pub name: String,
+    pub color: PlanetColorFn,
+    pub gloss: Option<PlanetGlossFn>, // sin gloss la superficie es mate
+    pub emission: Option<PlanetColorFn>, // luz propia, sumada sin iluminar
+    pub height: Option<PlanetHeightFn>,  // relieve que desplaza los vértices (ver PlanetParams::displacement)
+    pub params: PlanetParams,
+}
+
+impl PlanetType {
+    /// Copy whose palette and gradients are converted from the authored sRGB colors
+    /// to `color_space`, so the shaders blend and light in that space
+    pub fn in_color_space(&self, color_space: ColorSpace) -> PlanetType {
+        let mut planet = self.clone();
+        for color in planet.params.colors.values_mut() {
+            *color = color_space.decode(*color);
+        }
+        for gradient in planet.params.gradients.values_mut() {
+            *gradient = gradient.map_colors(|color| color_space.decode(color));
+        }
+        planet
+    }
+}
+
+/// Ordered list of the planet types available to the renderer. The index of a
+/// type is what the number keys select.
+#[derive(Default)]
+pub struct PlanetRegistry {
+    types: Vec<PlanetType>,
+}
+
+impl PlanetRegistry {
+    pub fn new() -> Self {
+        PlanetRegistry { types: Vec::new() }
+    }
+
+    /// Registry with the seven planet shaders that ship with the lab. Their colors
+    /// come from the presets in planets.toml.
+    pub fn with_builtins() -> Self {
+        let mut registry = PlanetRegistry::new();
+        let params = |rotation_speed, band_frequency| PlanetParams {
+            rotation_speed,
+            band_frequency,
+            ..PlanetParams::default()
+        };
+
+        let rocky = registry.register("rocky", rocky_planet_color, params(0.3, 4.0));
+        registry.set_emission(rocky, rocky_planet_lava);
+        registry.set_height(rocky, rocky_planet_height);
+        registry.register("gas_giant", gas_giant_color, PlanetParams { asteroids: true, axial_tilt: 0.47, ..params(0.5, 4.0) });
+        registry.register("rainbow", rainbow_planet_color, params(0.4, 3.0));
+        registry.register("glitter", glitter_planet_color, PlanetParams { rings: true, axial_tilt: 0.4, precession_speed: 0.05, ..params(0.35, 4.0) });
+        registry.register("heart", heart_planet_color, params(0.45, 5.0));
+        let earth = registry.register("earth", earth_planet_color, PlanetParams { atmosphere: true, axial_tilt: 0.41, ..params(0.2, 4.0) });
+        registry.set_gloss(earth, earth_planet_gloss);
+        let ice = registry.register("ice", ice_planet_color, PlanetParams { axial_tilt: 1.71, ..params(0.15, 4.0) });
+        registry.set_emission(ice, ice_planet_glow);
+        registry
+    }
+
+    /// Adds a planet type and returns its index. Registering an existing name
+    /// replaces that entry in place, so plugins can override built-ins.
+    pub fn register(&mut self, name: &str, color: PlanetColorFn, params: PlanetParams) -> usize {
+        let planet = PlanetType { name: name.to_string(), color, gloss: None, emission: None, height: None, params };
+        match self.find(name) {
+            Some(index) => {
+                self.types[index] = planet;
+                index
+            }
+            None => {
+                self.types.push(planet);
+                self.types.len() - 1
+            }
+        }
+    }
+
+    /// Gives a registered type a specular highlight (e.g. water)
+    pub fn set_gloss(&mut self, index: usize, gloss: PlanetGlossFn) {
+        if let Some(planet) = self.types.get_mut(index) {
+            planet.gloss = Some(gloss);
+        }
+    }
+
+    /// Gives a registered type light of its own (e.g. glowing cracks), added after lighting
+    pub fn set_emission(&mut self, index: usize, emission: PlanetColorFn) {
+        if let Some(planet) = self.types.get_mut(index) {
+            planet.emission = Some(emission);
+        }
+    }
+
+    /// Gives a registered type a relief the vertex shader can raise as geometry
+    pub fn set_height(&mut self, index: usize, height: PlanetHeightFn) {
+        if let Some(planet) = self.types.get_mut(index) {
+            planet.height = Some(height);
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&PlanetType> {
+        self.types.get(index)
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.types.iter().position(|planet| planet.name == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.iter().map(|planet| planet.name.as_str())
+    }
+}
+
+// Duración del fundido al cambiar de planeta, en segundos reales
+pub const CROSSFADE_DURATION: f32 = 0.5;
+
+/// Switch between planet types in progress: for `CROSSFADE_DURATION` the old
+/// planet's shader is still evaluated and blended out (see `Scene::fade_from`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlanetCrossfade {
+    pub from: usize, // índice del planeta que se va
+    elapsed: f32,
+}
+
+impl PlanetCrossfade {
+    pub fn start(from: usize) -> Self {
+        PlanetCrossfade { from, elapsed: 0.0 }
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.elapsed = (self.elapsed + dt.max(0.0)).min(CROSSFADE_DURATION);
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= CROSSFADE_DURATION
+    }
+
+    /// How much of the old planet still shows: 1 at the start, 0 when finished
+    pub fn remaining(&self) -> f32 {
+        let t = self.elapsed / CROSSFADE_DURATION;
+        1.0 - t * t * (3.0 - 2.0 * t) // arranca y frena suave, como el enfoque de la cámara
+    }
+}
@@ -0,0 +1,17 @@
+// plugins.rs
+// Punto de extensión para tipos de planeta definidos por el usuario.
+//
+// Para añadir un planeta nuevo, escribe una función con la firma de
+// `PlanetColorFn` y regístrala aquí con un nombre y sus parámetros por defecto:
+//
+//     fn ocean_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 { ... }
+//
+//     registry.register("ocean", ocean_planet_color, PlanetParams::default());
+//
+// Los planetas registrados aparecen después de los incluidos y se seleccionan
+// con las teclas numéricas en el orden de registro.
+use crate::planets::PlanetRegistry;
+
+pub fn register_plugins(registry: &mut PlanetRegistry) {
+    let _ = registry;
+}
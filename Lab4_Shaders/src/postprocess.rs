@@ -0,0 +1,496 @@
+// postprocess.rs
+// Efectos sobre el color buffer final, aplicados antes de copiarlo a la textura de la ventana
+use crate::math::*;
+use std::borrow::Cow;
+use std::f32::consts::PI;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::view_depth;
+use crate::tiles::Tile;
+use crate::tonemap::DisplayTransform;
+
+// Contraste mínimo para considerar que un pixel está en un borde (relativo y absoluto)
+const FXAA_EDGE_THRESHOLD: f32 = 0.125;
+const FXAA_EDGE_THRESHOLD_MIN: f32 = 0.0312;
+// Cuánto se suavizan los detalles de un solo pixel (0 = nada, 1 = máximo)
+const FXAA_SUBPIXEL_QUALITY: f32 = 0.75;
+// Pasos máximos al buscar los extremos del borde en cada dirección
+const FXAA_SEARCH_STEPS: i32 = 8;
+
+/// Perceived brightness of a color in the 0..1 range
+fn luma(color: Color) -> f32 {
+    (0.299 * color.r as f32 + 0.587 * color.g as f32 + 0.114 * color.b as f32) / 255.0
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), 255)
+}
+
+/// What effects can read about the frame besides its colors
+pub struct PostInput<'a> {
+    pub depth: &'a [f32],        // profundidad NDC por pixel (INFINITY = fondo)
+    pub velocity: &'a [Vector2], // movimiento en pantalla desde el frame anterior, por pixel
+    pub radiance: &'a [Vector3], // luz lineal antes de la curva de tono (sin recortar a 1), por pixel
+    pub display: DisplayTransform, // la curva con que la luz se pasó a colores de pantalla
+    pub projection: Matrix,      // proyección con la que se renderizó, para recuperar distancias
+}
+
+impl PostInput<'_> {
+    /// Distance from the camera to the surface at pixel `index` (INFINITY for the background)
+    pub fn distance(&self, index: usize) -> f32 {
+        let depth = self.depth[index];
+        if depth.is_finite() { view_depth(depth, &self.projection) } else { f32::INFINITY }
+    }
+}
+
+/// One step of the post-process chain, working on the whole image in place
+pub trait PostEffect {
+    fn name(&self) -> &str;
+    fn apply(&self, pixels: &mut [Color], width: i32, height: i32, input: &PostInput);
+}
+
+/// Ordered list of effects, each of which can be switched on and off at runtime.
+/// The color buffer is read once, passed through every enabled effect and written back.
+#[derive(Default)]
+pub struct PostProcessPipeline {
+    effects: Vec<(Box<dyn PostEffect>, bool)>,
+}
+
+impl PostProcessPipeline {
+    pub fn new() -> Self {
+        PostProcessPipeline { effects: Vec::new() }
+    }
+
+    /// Appends an effect to the end of the chain
+    pub fn push<E: PostEffect + 'static>(&mut self, effect: E, enabled: bool) {
+        self.effects.push((Box::new(effect), enabled));
+    }
+
+    /// Flips an effect on/off by name; returns its new state, or None if there is no such effect
+    pub fn toggle(&mut self, name: &str) -> Option<bool> {
+        let (_, enabled) = self.effects.iter_mut().find(|(effect, _)| effect.name() == name)?;
+        *enabled = !*enabled;
+        Some(*enabled)
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.effects.iter().any(|(effect, enabled)| *enabled && effect.name() == name)
+    }
+
+    /// Runs the enabled effects over the part of `framebuffer` inside its scissor
+    /// (all of it unless scissored), which was rendered with `projection`. The
+    /// region is processed as an image of its own: in a split screen each half is
+    /// filtered only with its own pixels.
+    pub fn apply(&self, framebuffer: &mut Framebuffer, projection: &Matrix) {
+        if !self.effects.iter().any(|(_, enabled)| *enabled) {
+            return;
+        }
+        let rect = framebuffer.scissor();
+        let (width, height) = (rect.x1 - rect.x0, rect.y1 - rect.y0);
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let mut frame = framebuffer.pixels();
+        let mut pixels = crop(&frame, framebuffer.width, rect).into_owned();
+        let radiance: Vec<Vector3> = region(framebuffer.width, rect).map(|index| framebuffer.radiance(index)).collect();
+        let depth = crop(framebuffer.depth(), framebuffer.width, rect);
+        let velocity = crop(framebuffer.velocity(), framebuffer.width, rect);
+        let input = PostInput {
+            depth: &depth,
+            velocity: &velocity,
+            radiance: &radiance,
+            display: framebuffer.display,
+            projection: *projection,
+        };
+        for (effect, enabled) in &self.effects {
+            if *enabled {
+                effect.apply(&mut pixels, width, height, &input);
+            }
+        }
+
+        // La región procesada vuelve a su lugar en la imagen completa
+        for (row, y) in (rect.y0..rect.y1).enumerate() {
+            let start = (y * framebuffer.width + rect.x0) as usize;
+            let processed = row * width as usize;
+            frame[start..start + width as usize].copy_from_slice(&pixels[processed..processed + width as usize]);
+        }
+        framebuffer.set_pixels(&frame);
+    }
+}
+
+/// Indices of the pixels inside `rect` in an image `width` pixels wide, row by row
+fn region(width: i32, rect: Tile) -> impl Iterator<Item = usize> {
+    (rect.y0..rect.y1).flat_map(move |y| (rect.x0..rect.x1).map(move |x| (y * width + x) as usize))
+}
+
+/// The values of a per-pixel buffer inside `rect`, laid out as an image of that
+/// size; the buffer itself when `rect` covers all of it
+fn crop<T: Clone>(buffer: &[T], width: i32, rect: Tile) -> Cow<'_, [T]> {
+    if rect.x0 == 0 && rect.y0 == 0 && rect.x1 == width && (rect.y1 * width) as usize == buffer.len() {
+        return Cow::Borrowed(buffer);
+    }
+    Cow::Owned(region(width, rect).map(|index| buffer[index].clone()).collect())
+}
+
+/// Fast approximate anti-aliasing: finds high-contrast edges from the luma of
+/// each pixel's neighbours, walks along the edge to see how far the pixel is
+/// from its ends, and blends it with the neighbour across the edge accordingly
+pub struct Fxaa;
+
+impl PostEffect for Fxaa {
+    fn name(&self) -> &str {
+        "fxaa"
+    }
+
+    fn apply(&self, pixels: &mut [Color], width: i32, height: i32, _input: &PostInput) {
+        let output = fxaa(pixels, width, height);
+        pixels.copy_from_slice(&output);
+    }
+}
+
+fn fxaa(pixels: &[Color], width: i32, height: i32) -> Vec<Color> {
+    let lumas: Vec<f32> = pixels.iter().map(|&color| luma(color)).collect();
+
+    let at = |x: i32, y: i32| (y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize;
+    let luma_at = |x: i32, y: i32| lumas[at(x, y)];
+
+    let mut output = pixels.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let center = luma_at(x, y);
+            let north = luma_at(x, y - 1);
+            let south = luma_at(x, y + 1);
+            let east = luma_at(x + 1, y);
+            let west = luma_at(x - 1, y);
+
+            let luma_min = center.min(north).min(south).min(east).min(west);
+            let luma_max = center.max(north).max(south).max(east).max(west);
+            let range = luma_max - luma_min;
+            if range < FXAA_EDGE_THRESHOLD_MIN.max(luma_max * FXAA_EDGE_THRESHOLD) {
+                continue;
+            }
+
+            let north_east = luma_at(x + 1, y - 1);
+            let north_west = luma_at(x - 1, y - 1);
+            let south_east = luma_at(x + 1, y + 1);
+            let south_west = luma_at(x - 1, y + 1);
+
+            // ¿El borde es horizontal (cambia de arriba a abajo) o vertical?
+            let edge_horizontal = (north + south - 2.0 * center).abs() * 2.0
+                + (north_east + south_east - 2.0 * east).abs()
+                + (north_west + south_west - 2.0 * west).abs();
+            let edge_vertical = (east + west - 2.0 * center).abs() * 2.0
+                + (north_east + north_west - 2.0 * north).abs()
+                + (south_east + south_west - 2.0 * south).abs();
+            let horizontal = edge_horizontal >= edge_vertical;
+
+            // Lado del borde con mayor contraste: hacia ese vecino se mezcla el color
+            let (luma_negative, luma_positive) = if horizontal { (north, south) } else { (west, east) };
+            let gradient_negative = (luma_negative - center).abs();
+            let gradient_positive = (luma_positive - center).abs();
+            let (side, luma_side, gradient) = if gradient_negative >= gradient_positive {
+                (-1, luma_negative, gradient_negative)
+            } else {
+                (1, luma_positive, gradient_positive)
+            };
+            let (side_x, side_y) = if horizontal { (0, side) } else { (side, 0) };
+            let (step_x, step_y) = if horizontal { (1, 0) } else { (0, 1) };
+
+            // Recorrer el borde en ambos sentidos hasta que el promedio de las dos filas cambie
+            let local_average = (center + luma_side) / 2.0;
+            let edge_luma = |px: i32, py: i32| (luma_at(px, py) + luma_at(px + side_x, py + side_y)) / 2.0;
+            let search = |direction: i32| {
+                let mut steps = 1;
+                while steps < FXAA_SEARCH_STEPS {
+                    let (px, py) = (x + step_x * steps * direction, y + step_y * steps * direction);
+                    if (edge_luma(px, py) - local_average).abs() >= gradient / 4.0 {
+                        break;
+                    }
+                    steps += 1;
+                }
+                let (px, py) = (x + step_x * steps * direction, y + step_y * steps * direction);
+                (steps, edge_luma(px, py) - local_average)
+            };
+            let (distance_negative, delta_negative) = search(-1);
+            let (distance_positive, delta_positive) = search(1);
+
+            // Solo se mezcla si el extremo más cercano "pertenece" al otro lado del borde
+            let (distance, delta) = if distance_negative < distance_positive {
+                (distance_negative, delta_negative)
+            } else {
+                (distance_positive, delta_positive)
+            };
+            let center_smaller = center < local_average;
+            let edge_length = (distance_negative + distance_positive) as f32;
+            let edge_blend = if (delta < 0.0) != center_smaller {
+                0.5 - distance as f32 / edge_length
+            } else {
+                0.0
+            };
+
+            // Detalles de un pixel (puntos aislados) se suavizan con el promedio de los vecinos
+            let neighbours = (2.0 * (north + south + east + west) + north_east + north_west + south_east + south_west) / 12.0;
+            let subpixel = ((neighbours - center).abs() / range).clamp(0.0, 1.0);
+            let subpixel = (-2.0 * subpixel + 3.0) * subpixel * subpixel;
+            let subpixel_blend = subpixel * subpixel * FXAA_SUBPIXEL_QUALITY;
+
+            let blend = edge_blend.max(subpixel_blend);
+            let index = at(x, y);
+            output[index] = lerp_color(pixels[index], pixels[at(x + side_x, y + side_y)], blend);
+        }
+    }
+
+    output
+}
+
+/// Glow around bright areas (lava cracks, glitter sparkles): keeps the part of
+/// each pixel's HDR light above `threshold`, blurs it with a separable Gaussian
+/// at half resolution and adds it back on top of the image. It reads the light
+/// before the tone curve, so a surface at 5 blooms more than one at 1.2 even
+/// though both end up white on screen.
+pub struct Bloom {
+    pub threshold: f32, // luminancia (lineal, con la exposición) a partir de la cual un pixel brilla
+    pub intensity: f32, // cuánto del brillo difuminado se suma
+    pub radius: i32,    // radio del blur, en pixeles de la imagen reducida
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom {
+            threshold: 1.0,
+            intensity: 0.8,
+            radius: 6,
+        }
+    }
+}
+
+impl PostEffect for Bloom {
+    fn name(&self) -> &str {
+        "bloom"
+    }
+
+    fn apply(&self, pixels: &mut [Color], width: i32, height: i32, input: &PostInput) {
+        let half_width = (width / 2).max(1);
+        let half_height = (height / 2).max(1);
+
+        // Bright pass reducido a la mitad: promedio de cada bloque 2x2, solo lo que supera el umbral
+        let mut bright = vec![Vector3::zero(); (half_width * half_height) as usize];
+        for y in 0..half_height {
+            for x in 0..half_width {
+                let mut sum = Vector3::zero();
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (x * 2 + dx).min(width - 1);
+                    let sy = (y * 2 + dy).min(height - 1);
+                    let light = input.radiance[(sy * width + sx) as usize] * input.display.exposure;
+                    let brightness = 0.2126 * light.x + 0.7152 * light.y + 0.0722 * light.z;
+                    if brightness > self.threshold {
+                        sum += light * ((brightness - self.threshold) / brightness);
+                    }
+                }
+                bright[(y * half_width + x) as usize] = sum * 0.25;
+            }
+        }
+
+        let weights = gaussian_weights(self.radius);
+        let display = DisplayTransform { exposure: 1.0, dither: false, ..input.display };
+        let blurred = blur_axis(&bright, half_width, half_height, &weights, 1, 0);
+        let blurred = blur_axis(&blurred, half_width, half_height, &weights, 0, 1);
+
+        // Sumar el brillo de vuelta, muestreando la imagen reducida con interpolación bilineal
+        for y in 0..height {
+            for x in 0..width {
+                let fx = ((x as f32 + 0.5) / 2.0 - 0.5).clamp(0.0, (half_width - 1) as f32);
+                let fy = ((y as f32 + 0.5) / 2.0 - 0.5).clamp(0.0, (half_height - 1) as f32);
+                let (x0, y0) = (fx.floor() as i32, fy.floor() as i32);
+                let (x1, y1) = ((x0 + 1).min(half_width - 1), (y0 + 1).min(half_height - 1));
+                let (tx, ty) = (fx - x0 as f32, fy - y0 as f32);
+                let sample = |sx: i32, sy: i32| blurred[(sy * half_width + sx) as usize];
+                let top = sample(x0, y0).lerp(sample(x1, y0), tx);
+                let bottom = sample(x0, y1).lerp(sample(x1, y1), tx);
+                let glow = top.lerp(bottom, ty) * self.intensity;
+                if glow == Vector3::zero() {
+                    continue;
+                }
+
+                // El brillo pasa por la misma curva que la imagen (ya expuesto) y se suma encima,
+                // como el brillo aditivo sobre el fondo al resolver
+                let glow = display.to_display(glow, x, y);
+                let pixel = &mut pixels[(y * width + x) as usize];
+                pixel.r = pixel.r.saturating_add(glow.r);
+                pixel.g = pixel.g.saturating_add(glow.g);
+                pixel.b = pixel.b.saturating_add(glow.b);
+            }
+        }
+    }
+}
+
+// Muestras del disco de desenfoque, repartidas en espiral con el ángulo áureo
+const DOF_SAMPLES: usize = 24;
+
+/// Depth-aware blur: pixels whose surface is farther than `focus_range` from
+/// `focus_distance` are averaged over a disc that grows with the distance
+/// (the circle of confusion), up to `max_radius` pixels. A sharper surface in
+/// front is never smeared into the blur around it.
+pub struct DepthOfField {
+    pub focus_distance: f32, // distancia a la cámara que queda nítida
+    pub focus_range: f32,    // margen a cada lado que sigue nítido
+    pub transition: f32,     // distancia extra hasta llegar al desenfoque máximo
+    pub max_radius: f32,     // radio máximo del disco, en pixeles
+}
+
+impl Default for DepthOfField {
+    fn default() -> Self {
+        DepthOfField {
+            focus_distance: 8.0,
+            focus_range: 0.5,
+            transition: 3.0,
+            max_radius: 6.0,
+        }
+    }
+}
+
+impl DepthOfField {
+    /// Blur radius in pixels for a surface at `distance` from the camera
+    pub fn circle_of_confusion(&self, distance: f32) -> f32 {
+        let out_of_focus = (distance - self.focus_distance).abs() - self.focus_range;
+        (out_of_focus / self.transition.max(1e-3)).clamp(0.0, 1.0) * self.max_radius
+    }
+}
+
+impl PostEffect for DepthOfField {
+    fn name(&self) -> &str {
+        "dof"
+    }
+
+    fn apply(&self, pixels: &mut [Color], width: i32, height: i32, input: &PostInput) {
+        let distances: Vec<f32> = (0..pixels.len()).map(|index| input.distance(index)).collect();
+        let blur: Vec<f32> = distances.iter().map(|&distance| self.circle_of_confusion(distance)).collect();
+
+        // Disco unitario: radio sqrt(i/N) para que las muestras cubran el área de forma pareja
+        let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+        let disc: Vec<(f32, f32)> = (0..DOF_SAMPLES)
+            .map(|i| {
+                let radius = ((i as f32 + 0.5) / DOF_SAMPLES as f32).sqrt();
+                let angle = i as f32 * golden_angle;
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+
+        let source = pixels.to_vec();
+        for y in 0..height {
+            for x in 0..width {
+                let index = (y * width + x) as usize;
+                let radius = blur[index];
+                if radius < 0.5 {
+                    continue;
+                }
+
+                let color = source[index];
+                let mut sum = Vector3::new(color.r as f32, color.g as f32, color.b as f32);
+                let mut weight = 1.0;
+                for &(dx, dy) in &disc {
+                    let sx = (x + (dx * radius).round() as i32).clamp(0, width - 1);
+                    let sy = (y + (dy * radius).round() as i32).clamp(0, height - 1);
+                    let sample = (sy * width + sx) as usize;
+                    // Una superficie más cercana solo entra si ella misma está desenfocada
+                    // hasta aquí; así un objeto nítido no deja un halo en el fondo borroso
+                    let offset = (dx * dx + dy * dy).sqrt() * radius;
+                    if distances[sample] < distances[index] && blur[sample] < offset {
+                        continue;
+                    }
+                    let color = source[sample];
+                    sum += Vector3::new(color.r as f32, color.g as f32, color.b as f32);
+                    weight += 1.0;
+                }
+                let average = sum / weight;
+                pixels[index] = Color::new(average.x.round() as u8, average.y.round() as u8, average.z.round() as u8, 255);
+            }
+        }
+    }
+}
+
+/// Blur along each pixel's screen-space velocity, as if the shutter stayed open
+/// for `shutter` of the frame: fast orbits and spins streak instead of jumping
+/// from frame to frame. Needs the velocity buffer, which `render_scene` only
+/// fills when given the previous frame's view-projection.
+pub struct MotionBlur {
+    pub shutter: f32,    // fracción del movimiento del frame que se difumina
+    pub max_length: f32, // largo máximo del trazo, en pixeles
+    pub samples: usize,  // muestras a lo largo del trazo
+}
+
+impl Default for MotionBlur {
+    fn default() -> Self {
+        MotionBlur {
+            shutter: 0.5,
+            max_length: 32.0,
+            samples: 12,
+        }
+    }
+}
+
+impl PostEffect for MotionBlur {
+    fn name(&self) -> &str {
+        "motion_blur"
+    }
+
+    fn apply(&self, pixels: &mut [Color], width: i32, height: i32, input: &PostInput) {
+        let source = pixels.to_vec();
+        let samples = self.samples.max(2);
+        for y in 0..height {
+            for x in 0..width {
+                let index = (y * width + x) as usize;
+                let mut streak = input.velocity[index] * self.shutter;
+                let length = streak.length();
+                if length < 0.5 {
+                    continue;
+                }
+                if length > self.max_length {
+                    streak *= self.max_length / length;
+                }
+
+                // Muestras repartidas sobre el trazo, centrado en el pixel
+                let mut sum = Vector3::zero();
+                for i in 0..samples {
+                    let t = i as f32 / (samples - 1) as f32 - 0.5;
+                    let sx = (x as f32 + 0.5 - streak.x * t).floor().clamp(0.0, (width - 1) as f32) as i32;
+                    let sy = (y as f32 + 0.5 - streak.y * t).floor().clamp(0.0, (height - 1) as f32) as i32;
+                    let color = source[(sy * width + sx) as usize];
+                    sum += Vector3::new(color.r as f32, color.g as f32, color.b as f32);
+                }
+                let average = sum / samples as f32;
+                pixels[index] = Color::new(average.x.round() as u8, average.y.round() as u8, average.z.round() as u8, 255);
+            }
+        }
+    }
+}
+
+/// Normalized 1D Gaussian kernel covering -radius..=radius
+fn gaussian_weights(radius: i32) -> Vec<f32> {
+    let radius = radius.max(1);
+    let sigma = radius as f32 / 2.0;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights.iter().map(|weight| weight / total).collect()
+}
+
+/// One pass of a separable blur along (step_x, step_y), clamping at the image edges
+fn blur_axis(input: &[Vector3], width: i32, height: i32, weights: &[f32], step_x: i32, step_y: i32) -> Vec<Vector3> {
+    let radius = (weights.len() / 2) as i32;
+    let mut output = vec![Vector3::zero(); input.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Vector3::zero();
+            for (i, weight) in weights.iter().enumerate() {
+                let offset = i as i32 - radius;
+                let sx = (x + offset * step_x).clamp(0, width - 1);
+                let sy = (y + offset * step_y).clamp(0, height - 1);
+                sum += input[(sy * width + sx) as usize] * *weight;
+            }
+            output[(y * width + x) as usize] = sum;
+        }
+    }
+    output
+}
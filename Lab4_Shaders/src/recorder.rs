@@ -0,0 +1,182 @@
+// recorder.rs
+use crate::framebuffer::Framebuffer;
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordFormat {
+    PngSequence, // frame_0000.png, frame_0001.png, ...
+    Gif,         // un solo GIF animado
+}
+
+pub struct Recorder {
+    pub format: RecordFormat,
+    pub output_dir: PathBuf,
+    pub target_fps: f32,  // frames por segundo guardados (0 = todos los frames)
+    pub frame_skip: u32,  // guardar 1 de cada (frame_skip + 1) frames renderizados
+    pub gif_scale: i32,   // factor de reducción para el GIF (2 = mitad de resolución)
+
+    recording: bool,
+    session: u32,
+    session_dir: PathBuf,
+    frames_seen: u32,
+    frames_written: u32,
+    frames_kept: u32, // los que keep_frame dejó pasar (frames_written cuenta los ya escritos)
+    time_since_capture: f32,
+    time_since_written: f32, // tiempo real desde el último frame guardado: lo que dura en el GIF
+    gif_encoder: Option<gif::Encoder<File>>,
+}
+
+impl Recorder {
+    pub fn new(format: RecordFormat, output_dir: &str) -> Self {
+        Recorder {
+            format,
+            output_dir: PathBuf::from(output_dir),
+            target_fps: 15.0,
+            frame_skip: 0,
+            gif_scale: 2,
+            recording: false,
+            session: 0,
+            session_dir: PathBuf::new(),
+            frames_seen: 0,
+            frames_written: 0,
+            frames_kept: 0,
+            time_since_capture: 0.0,
+            time_since_written: 0.0,
+            gif_encoder: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn start(&mut self) -> io::Result<()> {
+        if self.recording {
+            return Ok(());
+        }
+
+        // Buscar la primera carpeta de sesión libre: recording_000, recording_001, ...
+        loop {
+            self.session_dir = self.output_dir.join(format!("recording_{:03}", self.session));
+            if !self.session_dir.exists() {
+                break;
+            }
+            self.session += 1;
+        }
+        fs::create_dir_all(&self.session_dir)?;
+
+        self.recording = true;
+        self.frames_seen = 0;
+        self.frames_written = 0;
+        self.frames_kept = 0;
+        self.time_since_capture = 0.0;
+        self.time_since_written = 0.0;
+        Ok(())
+    }
+
+    /// Stops recording and finishes the output (the GIF trailer is written when the encoder drops)
+    pub fn stop(&mut self) -> PathBuf {
+        self.recording = false;
+        self.gif_encoder = None;
+        self.session += 1;
+        self.session_dir.clone()
+    }
+
+    pub fn toggle(&mut self) -> io::Result<()> {
+        if self.recording {
+            let dir = self.stop();
+            println!("Recording stopped: {} frames written to {}", self.frames_written, dir.display());
+        } else {
+            self.start()?;
+            println!("Recording started ({:?})", self.format);
+        }
+        Ok(())
+    }
+
+    /// Called once per rendered frame; writes it out if `keep_frame` keeps it
+    pub fn capture(&mut self, framebuffer: &mut Framebuffer, dt: f32) -> io::Result<()> {
+        if !self.recording {
+            return Ok(());
+        }
+        let Some(duration) = self.keep_frame(dt) else {
+            return Ok(());
+        };
+
+        match self.format {
+            RecordFormat::PngSequence => self.write_png(framebuffer)?,
+            RecordFormat::Gif => self.write_gif_frame(framebuffer, duration)?,
+        }
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    /// Decides whether a rendered frame that took `dt` seconds is kept, based on
+    /// `frame_skip` and `target_fps`. A kept frame comes with how long it lasts
+    /// in the recording: the real time since the previous kept frame.
+    pub fn keep_frame(&mut self, dt: f32) -> Option<f32> {
+        let skip = !self.frames_seen.is_multiple_of(self.frame_skip + 1);
+        self.frames_seen += 1;
+        self.time_since_capture += dt;
+        self.time_since_written += dt;
+        if skip {
+            return None;
+        }
+
+        if self.target_fps > 0.0 {
+            let interval = 1.0 / self.target_fps;
+            if self.frames_kept > 0 && self.time_since_capture < interval {
+                return None;
+            }
+            self.time_since_capture = (self.time_since_capture - interval).max(0.0);
+        }
+
+        let elapsed = std::mem::take(&mut self.time_since_written);
+        self.frames_kept += 1;
+        if self.frames_kept > 1 {
+            return Some(elapsed);
+        }
+        // El primer frame no tiene uno anterior: dura lo que se espera entre dos guardados
+        Some(if self.target_fps > 0.0 { 1.0 / self.target_fps } else { dt * (self.frame_skip + 1) as f32 })
+    }
+
+    fn write_png(&self, framebuffer: &Framebuffer) -> io::Result<()> {
+        let path = self.session_dir.join(format!("frame_{:04}.png", self.frames_written));
+        framebuffer.save(&path.to_string_lossy())
+    }
+
+    fn write_gif_frame(&mut self, framebuffer: &mut Framebuffer, duration: f32) -> io::Result<()> {
+        let scale = self.gif_scale.max(1);
+        let width = framebuffer.width / scale;
+        let height = framebuffer.height / scale;
+
+        if self.gif_encoder.is_none() {
+            let file = File::create(self.session_dir.join("recording.gif"))?;
+            let mut encoder = gif::Encoder::new(file, width as u16, height as u16, &[])
+                .map_err(io::Error::other)?;
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+            self.gif_encoder = Some(encoder);
+        }
+
+        // Reducir la imagen tomando un pixel de cada bloque scale x scale
+        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let color = framebuffer
+                    .get_pixel_color(x * scale, y * scale)
+                    .unwrap_or(framebuffer.background_color());
+                rgba.extend_from_slice(&[color.r, color.g, color.b, 255]);
+            }
+        }
+
+        let mut frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut rgba, 10);
+        // El retraso del GIF se mide en centésimas de segundo
+        frame.delay = (duration * 100.0).round().max(1.0) as u16;
+
+        if let Some(encoder) = self.gif_encoder.as_mut() {
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
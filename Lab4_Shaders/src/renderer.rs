@@ -0,0 +1,561 @@
+// renderer.rs
+// Un frame completo del pipeline: render_scene dibuja la escena en un Framebuffer y
+// Renderer agrega lo que rodea a cada llamada (buffers reutilizados, SSAA y posproceso)
+use crate::arena::{DrawScratch, FrameArena};
+use crate::asteroids::{belt_model_matrix, AsteroidBelt, ASTEROID_COLOR, BELT_BOUNDING_RADIUS};
+use crate::camera::{Camera, Projection};
+use crate::capture::{DrawCapture, FrameCapture};
+use crate::comet::{comet_model_matrix, Comet, COMET_COLOR};
+use crate::debug::DebugView;
+use crate::depth::{draw_depth_precision, DepthMode};
+use crate::flare::draw_lens_flare;
+use crate::framebuffer::{DepthTest, Framebuffer};
+use crate::frustum::Frustum;
+use crate::gizmos::{draw_gizmos, Gizmos};
+use crate::light::Light;
+use crate::math::*;
+use crate::matrix::{create_model_matrix, create_viewport_matrix, multiply_matrix_vector4, world_to_model_normal_matrix};
+use crate::mesh::{SceneMeshes, SPHERE_RADIUS};
+use crate::picking::{draw_outline, Body};
+use crate::planets::PlanetType;
+use crate::postprocess::PostProcessPipeline;
+use crate::shaders::{vertex_shader, VertexTransform, planet_model_matrix, previous_planet_model_matrix, Material, ShadingMode, VertexEffect, RING_OUTER_RADIUS};
+use crate::shadow::ShadowMap;
+use crate::stats::RenderStats;
+use crate::sun::{draw_corona, sun_model_matrix, SUN_SCALE};
+use crate::tiles::Tile;
+use crate::tonemap::DisplayTransform;
+use crate::triangle::{assemble_triangles, classify_triangle, rasterize, RenderMode};
+use crate::uniforms::{DrawUniforms, GlobalUniforms};
+use crate::vertex::Vertex;
+use std::f32::consts::PI;
+
+/// Everything `render_scene` draws besides the sun: meshes, the active planet type,
+/// the comet and the asteroid belt
+pub struct Scene<'a> {
+    pub meshes: &'a SceneMeshes,
+    pub planet: &'a PlanetType,
+    pub comet: &'a Comet,
+    pub belt: &'a AsteroidBelt,
+    // Durante un cambio de planeta: el que se va y cuánto queda de él (1 → 0, ver PlanetCrossfade).
+    // Solo se funde la superficie; la forma, los anillos y las lunas ya son los del nuevo
+    pub fade_from: Option<(&'a PlanetType, f32)>,
+}
+
+/// Part of the target a frame is drawn into, as fractions of its size (so it
+/// holds with supersampling too); y grows downwards like the pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub const FULL: Viewport = Viewport { x: 0.0, y: 0.0, width: 1.0, height: 1.0 };
+    pub const LEFT_HALF: Viewport = Viewport { x: 0.0, y: 0.0, width: 0.5, height: 1.0 };
+    pub const RIGHT_HALF: Viewport = Viewport { x: 0.5, y: 0.0, width: 0.5, height: 1.0 };
+
+    /// Pixel rectangle it covers on a `width` x `height` target
+    pub fn pixels(self, width: i32, height: i32) -> Tile {
+        let (width, height) = (width as f32, height as f32);
+        Tile {
+            x0: (self.x * width).round() as i32,
+            y0: (self.y * height).round() as i32,
+            x1: ((self.x + self.width) * width).round() as i32,
+            y1: ((self.y + self.height) * height).round() as i32,
+        }
+    }
+}
+
+/// Per-frame inputs to `render_scene` besides the scene itself
+#[derive(Clone, Copy, Debug)]
+pub struct FrameSettings {
+    pub time: f32, // elapsed time in seconds
+    pub dt: f32,   // delta time in seconds
+    pub debug_view: DebugView,
+    pub render_mode: RenderMode,
+    pub shading: ShadingMode,
+    pub projection: Projection,
+    pub depth_mode: DepthMode,  // profundidad estándar o reverse-Z con el plano lejano en el infinito
+    pub depth_overlay: bool,    // panel con la resolución de la profundidad según la distancia
+    pub selected: Option<Body>, // cuerpo elegido con el mouse, dibujado con contorno
+    pub gizmos: Gizmos,         // cuerpos con su órbita y su eje dibujados encima
+    pub fov_y: f32,             // campo de visión vertical en radianes
+    pub previous_view_projection: Option<Matrix>, // vista * proyección del frame anterior; con ella se calculan velocidades
+    pub lens_flare: bool,       // destello de lente cuando el sol está en pantalla
+    pub msaa: bool,             // 4 muestras de cobertura por píxel en los bordes de los triángulos
+    pub viewport: Viewport,     // parte del framebuffer donde se dibuja (ver Renderer::render_split)
+}
+
+impl Default for FrameSettings {
+    fn default() -> Self {
+        FrameSettings {
+            time: 0.0,
+            dt: 0.0,
+            debug_view: DebugView::default(),
+            render_mode: RenderMode::default(),
+            shading: ShadingMode::default(),
+            projection: Projection::default(),
+            depth_mode: DepthMode::default(),
+            depth_overlay: false,
+            selected: None,
+            gizmos: Gizmos::NONE,
+            fov_y: FOV_Y,
+            previous_view_projection: None,
+            lens_flare: false,
+            msaa: false,
+            viewport: Viewport::FULL,
+        }
+    }
+}
+
+// La esfera envolvente del planeta se agranda para cubrir los efectos de vértices (el latido crece un 8%)
+const PLANET_BOUNDS_MARGIN: f32 = 1.2;
+
+// Línea entre las dos mitades de la pantalla dividida
+const SPLIT_LINE_COLOR: Color = Color::new(230, 230, 230, 255);
+
+// Campo de visión vertical por defecto y planos de recorte de la cámara
+pub const FOV_Y: f32 = PI / 3.0;
+pub const NEAR_PLANE: f32 = 0.1;
+pub const FAR_PLANE: f32 = 100.0;
+
+/// Projection matrix `render_scene` uses for `frame` on a `width` x `height`
+/// target; the aspect ratio is that of the frame's viewport
+pub fn frame_projection(camera: &Camera, frame: &FrameSettings, width: f32, height: f32) -> Matrix {
+    let aspect = (width * frame.viewport.width) / (height * frame.viewport.height);
+    frame.projection.matrix(camera, frame.fov_y, aspect, NEAR_PLANE, FAR_PLANE, frame.depth_mode)
+}
+
+/// Runs the whole pipeline for one mesh with the given per-draw uniforms
+fn draw_mesh(
+    framebuffer: &mut Framebuffer,
+    globals: &GlobalUniforms,
+    draw: &DrawUniforms,
+    vertex_array: &[Vertex],
+    shadow_map: &ShadowMap,
+    scratch: &mut DrawScratch,
+    stats: &mut RenderStats,
+) {
+    // Frustum culling: un cuerpo cuya esfera envolvente queda fuera de la vista no pasa por el pipeline
+    let (center, radius) = draw.bounds;
+    if !Frustum::from_matrix(&(globals.view_matrix * globals.projection_matrix)).contains_sphere(center, radius) {
+        stats.bodies_culled += 1;
+        if let Some(capture) = scratch.capture.as_mut() {
+            capture.draws.push(DrawCapture::culled(draw.mesh_id));
+        }
+        return;
+    }
+
+    let DrawScratch { transformed, triangles, bins, capture } = scratch;
+
+    // Vertex Shader Stage: con instancias, la misma malla se transforma una vez por
+    // copia y todas comparten el ensamblado, el binning y la rasterización
+    let single = [Matrix::identity()];
+    let instances = if draw.instances.is_empty() { &single[..] } else { draw.instances };
+    let previous_instances = if draw.previous_instances.len() == instances.len() { draw.previous_instances } else { instances };
+    let displacement = draw.material.displacement(globals.time);
+    transformed.clear();
+    for (instance, previous_instance) in instances.iter().zip(previous_instances) {
+        let transform = VertexTransform::new(globals, *instance * draw.model_matrix, *previous_instance * draw.previous_model_matrix)
+            .with_displacement(displacement)
+            .with_effect(draw.effect);
+        transformed.extend(vertex_array.iter().map(|vertex| vertex_shader(vertex, &transform)));
+    }
+
+    // Primitive Assembly Stage
+    stats.vertices += transformed.len();
+    assemble_triangles(transformed, framebuffer.width, framebuffer.height, framebuffer.depth_mode, stats, triangles);
+    // Para la captura se vuelve a clasificar cada triángulo, incluso los descartados
+    let mut draw_capture = capture.as_ref().map(|_| {
+        let statuses = transformed.chunks_exact(3)
+            .map(|tri| classify_triangle(tri, framebuffer.width, framebuffer.height, framebuffer.depth_mode));
+        DrawCapture::new(draw.mesh_id, transformed, statuses)
+    });
+    draw.material.light_vertices(triangles, globals, shadow_map);
+
+    // Rasterization + Fragment Processing por tiles: cada fragmento se sombrea y
+    // escribe apenas se genera, sin acumularlos en memoria
+    bins.bin(triangles, framebuffer.width, framebuffer.height);
+    for (tile, triangle_ids) in bins.iter() {
+        for &triangle_id in triangle_ids {
+            rasterize(&triangles[triangle_id], globals.render_mode, framebuffer.msaa(), tile, &mut |mut fragment| {
+                fragment.triangle_id = triangle_id;
+                stats.fragments += 1;
+
+                // Early-Z: el depth test va antes del fragment shader, así las funciones de
+                // color procedurales no se evalúan para lo que ya está tapado
+                let (x, y) = (fragment.position.x as i32, fragment.position.y as i32);
+                let early = framebuffer.depth_test(x, y, fragment.depth, fragment.depth_slope, fragment.coverage);
+                if early != DepthTest::Passed {
+                    stats.record_fragment(early);
+                    if let Some(draw_capture) = draw_capture.as_mut() {
+                        draw_capture.record_fragment(triangle_id, false);
+                    }
+                    return;
+                }
+                stats.fragments_shaded += 1;
+
+                let final_color = globals.debug_view.shade(&fragment, draw.mesh_id)
+                    .unwrap_or_else(|| draw.material.shade(&fragment, globals, shadow_map));
+
+                let result = framebuffer.point_samples(x, y, final_color, fragment.depth, fragment.depth_slope, fragment.coverage);
+                if result == DepthTest::Passed {
+                    framebuffer.set_velocity(x, y, fragment.velocity);
+                }
+                stats.record_fragment(result);
+                if let Some(draw_capture) = draw_capture.as_mut() {
+                    draw_capture.record_fragment(triangle_id, result == DepthTest::Passed);
+                }
+            });
+        }
+    }
+    if let (Some(capture), Some(draw_capture)) = (capture.as_mut(), draw_capture) {
+        capture.draws.push(draw_capture);
+    }
+}
+
+/// Renders one complete frame of the scene (planet, optional rings, moons and comet) into the
+/// framebuffer and returns the pipeline counters for that frame. `arena` holds the
+/// intermediate buffers and should be kept alive across frames.
+pub fn render_scene(
+    framebuffer: &mut Framebuffer,
+    camera: &Camera,
+    light: &Light,
+    scene: &Scene,
+    frame: &FrameSettings,
+    arena: &mut FrameArena,
+) -> RenderStats {
+    let mut stats = RenderStats::default();
+    let time = frame.time;
+    let Scene { meshes, planet, comet, belt, fade_from } = *scene;
+
+    // Parámetros de transformación del modelo (fijos)
+    let translation = Vector3::new(0.0, 0.0, 0.0);
+    let scale = 1.0;
+    let rotation = Vector3::new(0.0, 0.0, 0.0);
+
+    // Solo se limpia, se dibuja y se resuelve dentro del viewport del frame
+    let view_rect = frame.viewport.pixels(framebuffer.width, framebuffer.height);
+    let width = (view_rect.x1 - view_rect.x0) as f32;
+    let height = (view_rect.y1 - view_rect.y0) as f32;
+
+    framebuffer.depth_mode = frame.depth_mode;
+    framebuffer.set_msaa(frame.msaa);
+    framebuffer.set_scissor(Some(view_rect));
+    framebuffer.clear();
+    framebuffer.set_current_color(Color::new(68, 29, 102, 255));
+
+    // Crear matrices de transformación; el planeta lleva su eje inclinado (y los anillos con él)
+    let orientation = planet_model_matrix(time, &planet.params);
+    let model_matrix = orientation * create_model_matrix(translation, scale, rotation);
+    let view_matrix = camera.get_view_matrix();
+    let projection_matrix = frame_projection(camera, frame, framebuffer.width as f32, framebuffer.height as f32);
+    let viewport_matrix = create_viewport_matrix(view_rect.x0 as f32, view_rect.y0 as f32, width, height);
+
+    let globals = GlobalUniforms {
+        view_matrix,
+        projection_matrix,
+        viewport_matrix,
+        time,
+        previous_time: time - frame.dt,
+        camera_position: camera.eye,
+        light: light.in_color_space(framebuffer.display.color_space),
+        debug_view: frame.debug_view,
+        render_mode: frame.render_mode,
+        shading: frame.shading,
+        previous_view_projection: frame.previous_view_projection,
+        color_space: framebuffer.display.color_space,
+    };
+    // Paleta del planeta en el espacio de color del frame (los presets se escriben en sRGB)
+    let planet = &planet.in_color_space(globals.color_space);
+    let fade_from = fade_from.map(|(from, remaining)| (from.in_color_space(globals.color_space), remaining));
+    // Nivel de detalle de cada cuerpo según su tamaño en pantalla
+    let planet_radius = frame.projection.screen_radius(camera, translation, SPHERE_RADIUS * scale, frame.fov_y, height);
+    let planet_mesh = meshes.sphere.select(planet_radius);
+    // Cada luna con su matriz y su malla: la irregular para los asteroides capturados
+    let moons: Vec<(Matrix, &[Vertex])> = planet.params.moons.iter()
+        .map(|moon| {
+            let mesh = if moon.shader.irregular() {
+                &meshes.asteroid[..]
+            } else {
+                let screen_radius = frame.projection.screen_radius(camera, moon.position(time), moon.radius(), frame.fov_y, height);
+                meshes.sphere.select(screen_radius)
+            };
+            (moon.model_matrix(time), mesh)
+        })
+        .collect();
+
+    // Pasada de sombras: profundidad de planeta y lunas vista desde la luz, en una
+    // región que alcanza a la luna más lejana
+    let scene_radius = planet.params.moons.iter().map(|moon| moon.reach()).fold(RING_OUTER_RADIUS, f32::max);
+    let (shadow_map, scratch) = arena.begin_frame(light, scene_radius);
+    let to_world = |model: &Matrix, position: Vector3| {
+        let world = multiply_matrix_vector4(model, &Vector4::new(position.x, position.y, position.z, 1.0));
+        Vector3::new(world.x, world.y, world.z)
+    };
+    shadow_map.render(planet_mesh, |position| to_world(&model_matrix, position));
+    for (moon_model, moon_mesh) in &moons {
+        shadow_map.render(moon_mesh, |position| to_world(moon_model, position));
+    }
+
+    // Dónde estaba cada cuerpo un paso de simulación antes (para los vectores de movimiento)
+    let previous_time = time - frame.dt;
+    let previous_orientation = planet_model_matrix(previous_time, &planet.params);
+    let previous_planet = previous_planet_model_matrix(time, frame.dt, &planet.params) * create_model_matrix(translation, scale, rotation);
+
+    // Renderizar el planeta principal
+    let planet_draw = DrawUniforms {
+        model_matrix,
+        instances: &[],
+        previous_model_matrix: previous_planet,
+        previous_instances: &[],
+        effect: planet.params.effect,
+        bounds: (translation, (SPHERE_RADIUS + planet.params.displacement) * scale * PLANET_BOUNDS_MARGIN),
+        mesh_id: 0,
+        material: Material::Planet { planet, model: model_matrix, normal_to_planet: world_to_model_normal_matrix(&model_matrix), fade_from: fade_from.as_ref().map(|(from, remaining)| (from, *remaining)) },
+    };
+    draw_mesh(framebuffer, &globals, &planet_draw, planet_mesh, shadow_map, scratch, &mut stats);
+
+    // Renderizar anillos si el tipo de planeta los tiene
+    if planet.params.rings {
+        let ring_draw = DrawUniforms {
+            model_matrix: orientation,
+            instances: &[],
+            previous_model_matrix: previous_orientation,
+            previous_instances: &[],
+            effect: VertexEffect::None,
+            bounds: (translation, RING_OUTER_RADIUS * scale),
+            mesh_id: 1,
+            material: Material::Ring { model: orientation },
+        };
+        draw_mesh(framebuffer, &globals, &ring_draw, &meshes.ring, shadow_map, scratch, &mut stats);
+    }
+
+    // Renderizar las lunas, todas por el mismo camino; cambia el shader de cada una
+    for (index, (moon, &(moon_model, moon_mesh))) in planet.params.moons.iter().zip(&moons).enumerate() {
+        let moon_draw = DrawUniforms {
+            model_matrix: moon_model,
+            instances: &[],
+            previous_model_matrix: moon.model_matrix(previous_time),
+            previous_instances: &[],
+            effect: VertexEffect::None,
+            bounds: Body::Moon(index).sphere(time, light, &planet.params.moons),
+            mesh_id: 2,
+            material: Material::Moon { model: moon_model, shader: moon.shader },
+        };
+        draw_mesh(framebuffer, &globals, &moon_draw, moon_mesh, shadow_map, scratch, &mut stats);
+    }
+
+    // Cinturón de asteroides: una sola malla dibujada una vez por asteroide
+    if planet.params.asteroids {
+        let belt_draw = DrawUniforms {
+            model_matrix: belt_model_matrix(),
+            instances: &belt.instances,
+            previous_model_matrix: belt_model_matrix(),
+            previous_instances: &belt.previous_instances,
+            effect: VertexEffect::None,
+            bounds: (Vector3::zero(), BELT_BOUNDING_RADIUS),
+            mesh_id: 5,
+            material: Material::Flat(ASTEROID_COLOR),
+        };
+        draw_mesh(framebuffer, &globals, &belt_draw, &meshes.asteroid, shadow_map, scratch, &mut stats);
+    }
+
+    // Núcleo del cometa (la cola se suma al final, sobre la geometría)
+    let comet_draw = DrawUniforms {
+        model_matrix: comet_model_matrix(time),
+        instances: &[],
+        previous_model_matrix: comet_model_matrix(previous_time),
+        previous_instances: &[],
+        effect: VertexEffect::None,
+        bounds: Body::Comet.sphere(time, light, &planet.params.moons),
+        mesh_id: 4,
+        material: Material::Flat(COMET_COLOR),
+    };
+    draw_mesh(framebuffer, &globals, &comet_draw, &meshes.nucleus, shadow_map, scratch, &mut stats);
+
+    // Sol en la posición de la luz: emisivo y sin sombra propia, con su corona por encima
+    let sun_radius = frame.projection.screen_radius(camera, light.position, SPHERE_RADIUS * SUN_SCALE, frame.fov_y, height);
+    let sun_draw = DrawUniforms {
+        model_matrix: sun_model_matrix(light),
+        instances: &[],
+        previous_model_matrix: sun_model_matrix(light),
+        previous_instances: &[],
+        effect: VertexEffect::None,
+        bounds: Body::Sun.sphere(time, light, &planet.params.moons),
+        mesh_id: 3,
+        material: Material::Star { center: light.position },
+    };
+    draw_mesh(framebuffer, &globals, &sun_draw, meshes.sphere.select(sun_radius), shadow_map, scratch, &mut stats);
+    if frame.debug_view == DebugView::Shaded {
+        draw_corona(framebuffer, &globals, light.position);
+        comet.render_tail(framebuffer, &globals);
+        // El destello va al final: necesita el depth buffer completo para saber si algo tapa al sol
+        if frame.lens_flare {
+            draw_lens_flare(framebuffer, &globals, light.position, SPHERE_RADIUS * SUN_SCALE);
+        }
+    }
+
+    // Contorno del cuerpo elegido con el mouse
+    if let Some(body) = frame.selected.filter(|body| body.exists(&planet.params.moons)) {
+        let (center, radius) = body.sphere(time, light, &planet.params.moons);
+        draw_outline(framebuffer, &globals, center, radius);
+    }
+    if frame.gizmos.any() {
+        draw_gizmos(framebuffer, &globals, &planet.params, light, time, frame.gizmos);
+    }
+
+    // Pasar el color HDR a colores de pantalla (tone mapping + gamma); las vistas
+    // de depuración se muestran sin curva para que los valores se lean tal cual
+    match frame.debug_view {
+        DebugView::Shaded => framebuffer.resolve(),
+        DebugView::Depth => framebuffer.resolve_depth(),
+        _ => framebuffer.resolve_with(DisplayTransform::linear()),
+    }
+    framebuffer.set_scissor(None);
+
+    stats
+}
+
+/// Framebuffer that gets presented, plus a `ssaa` times larger one to render
+/// into when supersampling is enabled
+pub fn create_framebuffers(width: i32, height: i32, ssaa: i32, display: DisplayTransform) -> (Framebuffer, Option<Framebuffer>) {
+    let background = Color::new(68, 29, 102, 255);
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.set_background_color(background);
+    framebuffer.display = display;
+
+    let supersampled = (ssaa > 1).then(|| {
+        let mut supersampled = Framebuffer::new(width * ssaa, height * ssaa);
+        supersampled.set_background_color(background);
+        supersampled.display = display;
+        supersampled
+    });
+    (framebuffer, supersampled)
+}
+
+/// New framebuffers for a window resized to `width` x `height`, keeping the display
+/// settings of `current`; None when the size did not change or the window is minimized
+pub fn resize_framebuffers(current: &Framebuffer, width: i32, height: i32, ssaa: i32) -> Option<(Framebuffer, Option<Framebuffer>)> {
+    if width <= 0 || height <= 0 || (width, height) == (current.width, current.height) {
+        return None;
+    }
+    Some(create_framebuffers(width, height, ssaa, current.display))
+}
+
+/// Renders frames into `framebuffer`, keeping what lives across frames: the
+/// pipeline's scratch buffers, the `ssaa` times larger target when supersampling
+/// and the post-process chain
+pub struct Renderer {
+    pub framebuffer: Framebuffer,
+    pub post_process: PostProcessPipeline,
+    supersampled: Option<Framebuffer>,
+    ssaa: i32,
+    arena: FrameArena,
+}
+
+impl Renderer {
+    /// Renderer for `width` x `height` frames with an empty post-process chain
+    pub fn new(width: i32, height: i32, ssaa: i32, display: DisplayTransform) -> Self {
+        let (framebuffer, supersampled) = create_framebuffers(width, height, ssaa, display);
+        Renderer { framebuffer, post_process: PostProcessPipeline::new(), supersampled, ssaa, arena: FrameArena::default() }
+    }
+
+    /// Switches to `width` x `height` frames, keeping the display settings; false
+    /// when the size did not change or is not positive (a minimized window)
+    pub fn resize(&mut self, width: i32, height: i32) -> bool {
+        match resize_framebuffers(&self.framebuffer, width, height, self.ssaa) {
+            Some((framebuffer, supersampled)) => {
+                (self.framebuffer, self.supersampled) = (framebuffer, supersampled);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tone mapping, exposure and color space of the frames (also of the supersampled target)
+    pub fn set_display(&mut self, display: DisplayTransform) {
+        self.framebuffer.display = display;
+        if let Some(supersampled) = self.supersampled.as_mut() {
+            supersampled.display = display;
+        }
+    }
+
+    /// Renders one frame of `scene` and applies the post-process chain; the result
+    /// is left in `framebuffer`
+    pub fn render(&mut self, camera: &Camera, light: &Light, scene: &Scene, frame: &FrameSettings) -> RenderStats {
+        // Con SSAA se renderiza en el buffer grande y se promedia al tamaño de salida
+        let stats = match self.supersampled.as_mut() {
+            Some(supersampled) => {
+                let stats = render_scene(supersampled, camera, light, scene, frame, &mut self.arena);
+                supersampled.downsample_into(&mut self.framebuffer);
+                stats
+            }
+            None => render_scene(&mut self.framebuffer, camera, light, scene, frame, &mut self.arena),
+        };
+        self.finish(camera, frame);
+        stats
+    }
+
+    /// Post-process chain and depth panel over the part of the output inside its
+    /// scissor, which was rendered with `frame`
+    fn finish(&mut self, camera: &Camera, frame: &FrameSettings) {
+        let (width, height) = (self.framebuffer.width as f32, self.framebuffer.height as f32);
+        let projection_matrix = frame_projection(camera, frame, width, height);
+        self.post_process.apply(&mut self.framebuffer, &projection_matrix);
+        // El panel va después del posproceso para que el desenfoque y el FXAA no lo toquen
+        if frame.depth_overlay {
+            let other = FrameSettings { depth_mode: frame.depth_mode.toggle(), ..*frame };
+            let other_projection = frame_projection(camera, &other, width, height);
+            draw_depth_precision(&mut self.framebuffer, &projection_matrix, &other_projection, NEAR_PLANE, FAR_PLANE);
+        }
+    }
+
+    /// Renders two configurations side by side (e.g. two shading modes or two
+    /// planets), each in its own half of the frame with its own viewport, for A/B
+    /// comparisons. Each half gets the post-process chain and depth panel on its
+    /// own, with its projection, and a line marks the split. Returns the counters
+    /// of each half.
+    pub fn render_split(&mut self, camera: &Camera, light: &Light, left: (&Scene, &FrameSettings), right: (&Scene, &FrameSettings)) -> [RenderStats; 2] {
+        let target = match self.supersampled.as_mut() {
+            Some(supersampled) => supersampled,
+            None => &mut self.framebuffer,
+        };
+        let halves = [(left, Viewport::LEFT_HALF), (right, Viewport::RIGHT_HALF)];
+        let stats = halves.map(|((scene, frame), viewport)| {
+            render_scene(target, camera, light, scene, &FrameSettings { viewport, ..*frame }, &mut self.arena)
+        });
+        if let Some(supersampled) = self.supersampled.as_mut() {
+            supersampled.downsample_into(&mut self.framebuffer);
+        }
+
+        for ((_, frame), viewport) in halves {
+            let rect = viewport.pixels(self.framebuffer.width, self.framebuffer.height);
+            self.framebuffer.set_scissor(Some(rect));
+            self.finish(camera, &FrameSettings { viewport, ..*frame });
+        }
+        self.framebuffer.set_scissor(None);
+
+        let divider = Viewport::RIGHT_HALF.pixels(self.framebuffer.width, self.framebuffer.height).x0;
+        self.framebuffer.set_current_color(SPLIT_LINE_COLOR);
+        for y in 0..self.framebuffer.height {
+            self.framebuffer.set_pixel(divider, y);
+        }
+        stats
+    }
+
+    /// Renders one frame like `render` and also returns its intermediate data:
+    /// transformed vertices, what assembly did with each triangle and the
+    /// fragments each one produced. Slow and large; meant for one-off dumps.
+    pub fn capture_frame(&mut self, camera: &Camera, light: &Light, scene: &Scene, frame: &FrameSettings) -> FrameCapture {
+        let target = self.supersampled.as_ref().unwrap_or(&self.framebuffer);
+        let (width, height) = (target.width, target.height);
+        self.arena.draw.capture = Some(FrameCapture { width, height, time: frame.time, ..FrameCapture::default() });
+        let stats = self.render(camera, light, scene, frame);
+        let capture = self.arena.draw.capture.take().unwrap_or_default();
+        FrameCapture { stats, ..capture }
+    }
+}
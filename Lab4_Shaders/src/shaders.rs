@@ -1,60 +1,214 @@
 // shaders.rs (corregido completamente)
-use raylib::prelude::*;
+use crate::math::*;
 use crate::vertex::Vertex;
-use crate::Uniforms;
-use crate::matrix::multiply_matrix_vector4;
+use crate::uniforms::GlobalUniforms;
+use crate::matrix::{create_model_matrix, multiply_matrix_vector4, SimdMatrix};
+use crate::mesh::SPHERE_RADIUS;
 use crate::fragment::Fragment;
-use crate::framebuffer::Framebuffer;
-use crate::triangle;
 use crate::light::Light;
+use crate::moons::MoonShader;
+use crate::planets::{PlanetHeightFn, PlanetParams, PlanetType};
+use crate::shadow::ShadowMap;
+use crate::sun::star_color;
+use std::f32::consts::PI;
+use std::str::FromStr;
+use crate::noise::{fbm, fractal_noise, noise, value_noise, worley};
 
-pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
-    // Convert vertex position to homogeneous coordinates (Vec4) by adding a w-component of 1.0
-    let mut position_vec4 = Vector4::new(
-        vertex.position.x,
-        vertex.position.y,
-        vertex.position.z,
-        1.0
-    );
+// Radios de los anillos (el planeta mide 0.5); están en el ecuador, con la inclinación del planeta
+pub const RING_INNER_RADIUS: f32 = 0.8;
+pub const RING_OUTER_RADIUS: f32 = 1.5;
+
+pub const RING_COLOR: Vector3 = Vector3::new(0.8, 0.7, 0.6); // Color dorado para anillos
+
+// Luz que queda en la sombra de los anillos sobre el planeta y en la del planeta sobre los anillos
+const RING_SHADOW_LIGHT: f32 = 0.35;
+const PLANET_SHADOW_LIGHT: f32 = 0.15;
+const SHADOW_EDGE: f32 = 0.02; // ancho del borde suave de ambas sombras
+pub const MOON_COLOR: Vector3 = Vector3::new(0.9, 0.9, 0.8); // Color gris claro de las tierras altas de la luna
+
+const SPECULAR_EXPONENT: f32 = 60.0; // brillo de las superficies con gloss (agua)
+
+// Paso para estimar la normal de una superficie deformada con diferencias finitas (unidades de la malla)
+const DEFORM_STEP: f32 = 0.005;
+
+/// Moves `position` with `map` and estimates the normal of the deformed surface
+/// from two neighbors a small step away along the tangent plane
+fn deform(position: Vector3, normal: Vector3, map: impl Fn(Vector3) -> Vector3) -> (Vector3, Vector3) {
+    if normal.length() == 0.0 {
+        return (map(position), normal);
+    }
+    let normal = normal.normalized();
+    let helper = if normal.y.abs() < 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+    let tangent = normal.cross(helper).normalized();
+    let bitangent = normal.cross(tangent);
+
+    let moved = map(position);
+    let along_tangent = map(position + tangent * DEFORM_STEP) - moved;
+    let along_bitangent = map(position + bitangent * DEFORM_STEP) - moved;
+    let mut moved_normal = along_tangent.cross(along_bitangent);
+    if moved_normal.length() == 0.0 {
+        return (moved, normal);
+    }
+    // Del mismo lado que la normal original, sin importar el orden de las tangentes
+    if moved_normal.dot(normal) < 0.0 {
+        moved_normal = -moved_normal;
+    }
+    (moved, moved_normal.normalized())
+}
+
+/// Relief the vertex shader raises a surface with: each vertex moves along its
+/// normal by `height` (in [-1, 1]) times `scale`
+#[derive(Clone, Copy)]
+pub struct Displacement<'a> {
+    pub height: PlanetHeightFn,
+    pub params: &'a PlanetParams,
+    pub scale: f32,
+    pub time: f32,
+}
+
+impl Displacement<'_> {
+    /// Displaced position and the normal of the displaced surface there
+    pub fn apply(&self, position: Vector3, normal: Vector3) -> (Vector3, Vector3) {
+        deform(position, normal, |point| self.displace(point, normal))
+    }
 
-    // Modificar la posición si estamos renderizando anillos o luna
-    match uniforms.render_type {
-        1 => { // rings
-            // Generar posición para anillos - solo coordenadas X y Z, Y es cercano a 0
-            let angle = (vertex.position.x + vertex.position.z) * 3.0;
-            let radius = 1.5 + (vertex.position.y * 0.1);
-            position_vec4.x = radius * angle.cos();
-            position_vec4.z = radius * angle.sin();
-            position_vec4.y = vertex.position.y * 0.1;
+    /// Displaced position only: `position` raised along `normal`
+    pub fn displace(&self, position: Vector3, normal: Vector3) -> Vector3 {
+        let direction = if normal.length() > 0.0 { normal.normalized() } else { normal };
+        position + direction * ((self.height)(&position, self.time, self.params) * self.scale)
+    }
+}
+
+// Parámetros de los efectos de vértice (la malla del planeta mide 0.5 de radio)
+const PULSE_AMOUNT: f32 = 0.08; // fracción del tamaño que crece y encoge
+const PULSE_SPEED: f32 = 3.0;   // rad/s del latido
+const WOBBLE_AMOUNT: f32 = 0.03;
+const WOBBLE_FREQUENCY: f32 = 10.0; // ondas por unidad de la malla
+const WOBBLE_SPEED: f32 = 4.0;
+const TWIST_AMOUNT: f32 = 0.8;  // radianes de giro en los polos, en el máximo de la oscilación
+const TWIST_SPEED: f32 = 1.5;
+
+/// Shape-changing animation the vertex shader applies to a whole body. The
+/// surface pattern stays attached to each vertex, so it deforms with the shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VertexEffect {
+    #[default]
+    None,
+    Pulse,  // late: todo el cuerpo crece y encoge
+    Wobble, // gelatina: ondas que recorren la superficie
+    Twist,  // los hemisferios giran en sentidos opuestos alrededor del eje
+}
+
+impl VertexEffect {
+    pub const ALL: [VertexEffect; 4] = [VertexEffect::None, VertexEffect::Pulse, VertexEffect::Wobble, VertexEffect::Twist];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VertexEffect::None => "none",
+            VertexEffect::Pulse => "pulse",
+            VertexEffect::Wobble => "wobble",
+            VertexEffect::Twist => "twist",
+        }
+    }
+
+    /// Where a mesh-space `position` goes at `time`
+    pub fn transform_point(self, position: Vector3, time: f32) -> Vector3 {
+        match self {
+            VertexEffect::None => position,
+            VertexEffect::Pulse => position * (1.0 + PULSE_AMOUNT * (time * PULSE_SPEED).sin()),
+            VertexEffect::Wobble => {
+                let length = position.length();
+                if length == 0.0 {
+                    return position;
+                }
+                let wave = (position.y * WOBBLE_FREQUENCY + time * WOBBLE_SPEED).sin()
+                    * (position.x * WOBBLE_FREQUENCY * 0.8 - time * WOBBLE_SPEED * 0.7).cos();
+                position * (1.0 + WOBBLE_AMOUNT * wave / length)
+            }
+            VertexEffect::Twist => {
+                let angle = TWIST_AMOUNT * (time * TWIST_SPEED).sin() * position.y / SPHERE_RADIUS;
+                rotate_around(&position, &Vector3::new(0.0, 1.0, 0.0), angle)
+            }
+        }
+    }
+
+    /// Deformed position and normal at `time`
+    pub fn apply(self, position: Vector3, normal: Vector3, time: f32) -> (Vector3, Vector3) {
+        match self {
+            VertexEffect::None => (position, normal),
+            _ => deform(position, normal, |point| self.transform_point(point, time)),
         }
-        2 => { // moon
-            // Calcular posición orbital de la luna
-            let moon_orbit_time = uniforms.time * 0.5;
-            let moon_distance = 3.0;
-            let moon_x = moon_distance * moon_orbit_time.cos();
-            let moon_z = moon_distance * moon_orbit_time.sin();
-            let moon_y = (moon_orbit_time * 2.0).sin() * 0.5;
-            
-            // Posición base de la luna
-            let moon_base = Vector3::new(moon_x, moon_y, moon_z);
-            
-            // Añadir posición relativa del vértice
-            position_vec4.x = moon_base.x + vertex.position.x * 0.3;
-            position_vec4.y = moon_base.y + vertex.position.y * 0.3;
-            position_vec4.z = moon_base.z + vertex.position.z * 0.3;
+    }
+}
+
+impl FromStr for VertexEffect {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        VertexEffect::ALL
+            .into_iter()
+            .find(|effect| effect.name() == name)
+            .ok_or_else(|| format!("invalid vertex effect: {} (expected none, pulse, wobble or twist)", name))
+    }
+}
+
+/// The matrices of one draw call, combined once per draw: model, view and
+/// projection become a single SIMD product per vertex, plus one for the viewport
+pub struct VertexTransform<'a> {
+    model_view_projection: SimdMatrix,
+    viewport: SimdMatrix,
+    model: Matrix,
+    previous_model_view_projection: Option<SimdMatrix>, // para los vectores de movimiento
+    displacement: Option<Displacement<'a>>,            // relieve aplicado antes de transformar
+    effect: VertexEffect,                              // deformación animada, después del relieve
+    time: f32,
+    previous_time: f32, // el relieve y el efecto se evalúan también aquí para los vectores de movimiento
+}
+
+impl<'a> VertexTransform<'a> {
+    /// `previous_model_matrix` is where the body was last frame; the previous
+    /// screen positions are only computed when the globals ask for them
+    pub fn new(globals: &GlobalUniforms, model_matrix: Matrix, previous_model_matrix: Matrix) -> Self {
+        // En raylib a * b aplica primero a: modelo, luego vista, luego proyección
+        let model_view_projection = model_matrix * globals.view_matrix * globals.projection_matrix;
+        let previous_model_view_projection = globals
+            .previous_view_projection
+            .map(|view_projection| SimdMatrix::new(&(previous_model_matrix * view_projection)));
+        VertexTransform {
+            model_view_projection: SimdMatrix::new(&model_view_projection),
+            viewport: SimdMatrix::new(&globals.viewport_matrix),
+            model: model_matrix,
+            previous_model_view_projection,
+            displacement: None,
+            effect: VertexEffect::None,
+            time: globals.time,
+            previous_time: globals.previous_time,
         }
-        _ => {} // Planet - usar posición original
     }
 
-    // Apply Model transformation
-    let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
+    /// Same transform, raising every vertex with `displacement` first
+    pub fn with_displacement(self, displacement: Option<Displacement<'a>>) -> Self {
+        VertexTransform { displacement, ..self }
+    }
 
-    // Apply View transformation (camera)
-    let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
+    /// Same transform, deforming every vertex with `effect` (at the globals' time)
+    pub fn with_effect(self, effect: VertexEffect) -> Self {
+        VertexTransform { effect, ..self }
+    }
 
-    // Apply Projection transformation (perspective)
-    let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+    /// Mesh-space position the displacement and the effect gave `vertex` at the
+    /// previous frame's time, so animated shapes get their own motion vectors
+    fn previous_local_position(&self, vertex: &Vertex) -> Vector3 {
+        let position = match &self.displacement {
+            Some(displacement) => Displacement { time: self.previous_time, ..*displacement }.displace(vertex.position, vertex.normal),
+            None => vertex.position,
+        };
+        self.effect.transform_point(position, self.previous_time)
+    }
+}
 
+/// Clip space -> NDC (perspective division) -> screen coordinates
+fn to_screen(clip_position: Vector4, viewport: &SimdMatrix) -> Vector3 {
     // Perform perspective division to get NDC (Normalized Device Coordinates)
     let ndc = if clip_position.w != 0.0 {
         Vector3::new(
@@ -68,13 +222,45 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     
     // Apply Viewport transformation to get screen coordinates
     let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-    let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
+    let screen_position = viewport.transform(ndc_vec4);
     
-    let transformed_position = Vector3::new(
+    Vector3::new(
         screen_position.x,
         screen_position.y,
         screen_position.z,
+    )
+}
+
+pub fn vertex_shader(vertex: &Vertex, transform: &VertexTransform) -> Vertex {
+    // Relieve y efectos: solo mueven la geometría; `position` queda sobre la esfera
+    // porque es donde los shaders de color evalúan sus patrones
+    let (position, normal) = match &transform.displacement {
+        Some(displacement) => displacement.apply(vertex.position, vertex.normal),
+        None => (vertex.position, vertex.normal),
+    };
+    let (position, normal) = transform.effect.apply(position, normal, transform.time);
+
+    // Convert vertex position to homogeneous coordinates (Vec4) by adding a w-component of 1.0
+    let position_vec4 = Vector4::new(
+        position.x,
+        position.y,
+        position.z,
+        1.0
     );
+
+    // Apply Model, View and Projection transformations in one product
+    let clip_position = transform.model_view_projection.transform(position_vec4);
+    let transformed_position = to_screen(clip_position, &transform.viewport);
+
+    // Dónde estaba el vértice en el frame anterior (matrices, relieve y efecto de entonces):
+    // la diferencia es la velocidad en pantalla
+    let previous_position = match &transform.previous_model_view_projection {
+        Some(previous) => {
+            let before = transform.previous_local_position(vertex);
+            to_screen(previous.transform(Vector4::new(before.x, before.y, before.z, 1.0)), &transform.viewport)
+        }
+        None => transformed_position,
+    };
     
     // Create a new Vertex with the transformed position
     Vertex {
@@ -82,8 +268,11 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         normal: vertex.normal,
         tex_coords: vertex.tex_coords,
         color: vertex.color,
+        light: vertex.light,
         transformed_position,
-        transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
+        transformed_normal: transform_normal(&normal, &transform.model),
+        previous_position,
+        clip_w: clip_position.w,
     }
 }
 
@@ -105,33 +294,16 @@ fn transform_normal(normal: &Vector3, model_matrix: &Matrix) -> Vector3 {
     transformed_normal
 }
 
-// Función auxiliar para calcular ruido simple
-fn noise(pos: &Vector3) -> f32 {
-    let x = pos.x as i32;
-    let y = pos.y as i32;
-    let z = pos.z as i32;
-    
-    let n = (x.wrapping_add(y.wrapping_mul(57)).wrapping_add(z.wrapping_mul(113))) as f32;
-    ((n * n * 41597.5453).sin() * 43758.5453) % 1.0
-}
-
-// Función para generar ruido fractal (más suave)
-fn fractal_noise(pos: &Vector3, octaves: i32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 1.0;
-    let mut frequency = 1.0;
-    
-    for _ in 0..octaves {
-        value += noise(&Vector3::new(pos.x * frequency, pos.y * frequency, pos.z * frequency)) * amplitude;
-        amplitude *= 0.5;
-        frequency *= 2.0;
-    }
-    
-    value
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
 }
 
 // Función para simular iluminación basada en el normal
-fn simulate_lighting(normal: &Vector3, light_dir: &Vector3) -> f32 {
+// `shadow` es la fracción de luz que llega (1.0 = sin sombra); la luz ambiente no se oscurece.
+// `occlusion` es la oclusión ambiental (1.0 = a cielo abierto, ver cavity_occlusion): apaga
+// toda la luz ambiente y parte de la directa, que en una cavidad llega más rasante
+fn simulate_lighting(normal: &Vector3, light_dir: &Vector3, shadow: f32, occlusion: f32) -> f32 {
     let light_dir_length = (light_dir.x * light_dir.x + 
                            light_dir.y * light_dir.y + 
                            light_dir.z * light_dir.z).sqrt();
@@ -147,11 +319,95 @@ fn simulate_lighting(normal: &Vector3, light_dir: &Vector3) -> f32 {
                    normal.y * normalized_light_dir.y + 
                    normal.z * normalized_light_dir.z;
     
-    intensity.max(0.0).min(1.0) * 0.8 + 0.2 // Agrega algo de luz ambiente
+    let direct_occlusion = 1.0 - AO_DIRECT * (1.0 - occlusion);
+    intensity.max(0.0).min(1.0) * 0.8 * shadow * direct_occlusion + 0.2 * occlusion // Agrega algo de luz ambiente
+}
+
+// OCLUSIÓN AMBIENTAL: en vez de trazar rayos, se mira la altura del terreno en un
+// anillo de puntos alrededor del fragmento; si los vecinos están más altos, el punto
+// está en el fondo de una cavidad (cráter, cañón) y le llega menos luz del cielo
+const AO_SAMPLES: usize = 6;
+const AO_STRENGTH: f32 = 0.7; // oscurecimiento del fondo de una cavidad muy cerrada
+const AO_DIRECT: f32 = 0.75;  // fracción de la oclusión que también apaga la luz directa
+// Terreno de los planetas con función de altura: radio del anillo (radianes sobre la
+// esfera) y altura del relieve en unidades de la malla, aunque no se desplacen los vértices
+const TERRAIN_AO_RADIUS: f32 = 0.04;
+const TERRAIN_AO_RELIEF: f32 = 0.05;
+
+/// Ambient occlusion at the mesh point `pos` from a height field: `height` is
+/// sampled on a ring `radius` radians around it, at the same distance from the
+/// center, and the more the neighbors rise above the point (scaled by `relief`,
+/// the height of the field in mesh units) the darker it gets. 1 is fully open,
+/// lower is a cavity.
+pub fn cavity_occlusion(pos: &Vector3, radius: f32, relief: f32, height: impl Fn(&Vector3) -> f32) -> f32 {
+    let distance = pos.length();
+    if distance == 0.0 {
+        return 1.0;
+    }
+    let dir = *pos / distance;
+    // Base del plano tangente
+    let helper = if dir.y.abs() < 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+    let tangent = dir.cross(helper).normalized();
+    let bitangent = dir.cross(tangent);
+
+    let center = height(pos);
+    let mut cavity = 0.0;
+    for sample in 0..AO_SAMPLES {
+        let angle = sample as f32 * 2.0 * PI / AO_SAMPLES as f32;
+        let neighbor = (dir + (tangent * angle.cos() + bitangent * angle.sin()) * radius).normalized() * distance;
+        // Pendiente hacia el vecino: altura ganada sobre la distancia en la superficie
+        let rise = (height(&neighbor) - center) * relief / (radius * distance);
+        cavity += rise.clamp(0.0, 1.0);
+    }
+    1.0 - AO_STRENGTH * cavity / AO_SAMPLES as f32
+}
+
+/// Triplanar projection: `sample` (a 2D texture lookup) is read on the three
+/// planes perpendicular to the axes at the mesh point `pos` and blended by how
+/// much `normal` faces each axis, raised to `sharpness`. Unlike equirectangular
+/// UVs it does not stretch at the poles nor along the slopes of displaced terrain.
+pub fn triplanar(pos: &Vector3, normal: &Vector3, sharpness: f32, sample: impl Fn(Vector2) -> Vector3) -> Vector3 {
+    let weights = Vector3::new(normal.x.abs().powf(sharpness), normal.y.abs().powf(sharpness), normal.z.abs().powf(sharpness));
+    let total = weights.x + weights.y + weights.z;
+    if total == 0.0 {
+        return sample(Vector2::new(pos.x, pos.z));
+    }
+    // Cada eje proyecta sobre el plano de los otros dos
+    (sample(Vector2::new(pos.z, pos.y)) * weights.x
+        + sample(Vector2::new(pos.x, pos.z)) * weights.y
+        + sample(Vector2::new(pos.x, pos.y)) * weights.z)
+        / total
+}
+
+// TEXTURA DE ROCA: detalle 2D que un preset con relieve activa con `triplanar = true`
+const ROCK_TEXTURE_SCALE: f32 = 12.0;    // repeticiones de la textura por unidad de la malla
+const ROCK_TEXTURE_STRENGTH: f32 = 0.35; // cuánto aclara u oscurece el color del terreno
+const TRIPLANAR_SHARPNESS: f32 = 4.0;    // qué tan rápido se pasa de un plano al siguiente
+
+/// Rock grain and strata as a 2D texture at `uv` (mesh units), in 0..1
+fn rock_texture(uv: Vector2) -> f32 {
+    let uv = uv * ROCK_TEXTURE_SCALE;
+    let grain = fbm(&Vector3::new(uv.x, uv.y, 0.0), 3) * 0.5 + 0.5;
+    let strata = 0.5 + 0.5 * (uv.y * 0.7 + grain * 4.0).sin();
+    (grain * 0.7 + strata * 0.3).clamp(0.0, 1.0)
+}
+
+/// Brightness the rock texture gives a planet's surface at the mesh point `pos`,
+/// projected triplanarly on the world `normal` of the displaced terrain so it
+/// does not stretch on the slopes nor at the poles. `to_planet` takes world normals
+/// to the planet's space (see `world_to_model_normal_matrix`); the texture turns
+/// with the surface like the color functions.
+fn rock_texture_shade(pos: &Vector3, normal: &Vector3, to_planet: &Matrix, time: f32, params: &PlanetParams) -> f32 {
+    // La normal vuelve al espacio del planeta (sin inclinación) y ambas giran con él
+    let planet_normal = multiply_matrix_vector4(to_planet, &Vector4::new(normal.x, normal.y, normal.z, 0.0));
+    let planet_normal = rotate_planet_position(&Vector3::new(planet_normal.x, planet_normal.y, planet_normal.z), time, params.rotation_speed);
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    let texel = triplanar(&rotated_pos, &planet_normal, TRIPLANAR_SHARPNESS, |uv| Vector3::new(rock_texture(uv), 0.0, 0.0)).x;
+    1.0 + ROCK_TEXTURE_STRENGTH * (texel * 2.0 - 1.0)
 }
 
 // Función para aplicar rotación al planeta
-fn rotate_planet_position(pos: &Vector3, time: f32, rotation_speed: f32) -> Vector3 {
+pub fn rotate_planet_position(pos: &Vector3, time: f32, rotation_speed: f32) -> Vector3 {
     let angle = time * rotation_speed;
     let cos_a = angle.cos();
     let sin_a = angle.sin();
@@ -165,109 +421,163 @@ fn rotate_planet_position(pos: &Vector3, time: f32, rotation_speed: f32) -> Vect
 }
 
 // PLANETA ROCOSO CON CRÁTERES Y PATRONES (Tipo 0)
-fn rocky_planet_color(pos: &Vector3, time: f32) -> Vector3 {
-    let rotated_pos = rotate_planet_position(pos, time, 0.3);
+
+// Elevación del terreno rocoso (~0 a 1): la comparten el color y el relieve
+fn rocky_elevation(rotated_pos: &Vector3, params: &PlanetParams) -> f32 {
+    let ns = params.noise_scale;
+
+    let base_noise = fractal_noise(&Vector3::new(rotated_pos.x * ns, rotated_pos.y * ns, rotated_pos.z * ns), 4);
+    let detail_noise = fractal_noise(&Vector3::new(rotated_pos.x * 8.0 * ns, rotated_pos.y * 8.0 * ns, rotated_pos.z * 8.0 * ns), 2);
+
+    (base_noise + detail_noise * 0.3) * 0.5 + 0.5
+}
+
+pub fn rocky_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
     
-    let base_noise = fractal_noise(&rotated_pos, 4);
-    let detail_noise = fractal_noise(&Vector3::new(rotated_pos.x * 8.0, rotated_pos.y * 8.0, rotated_pos.z * 8.0), 2);
+    // Colores de planeta con lava (definidos en planets.toml): el gradiente "terrain"
+    // da el color de cada altura (ceniza abajo, roca arriba)
+    let lava_color = params.color("lava");
     
-    // Colores de planeta con lava (tonos rojos y naranjas)
-    let base_color = Vector3::new(0.8, 0.3, 0.1);  // Rojo intenso
-    let lava_color = Vector3::new(1.0, 0.4, 0.1);  // Naranja brillante
-    let rock_color = Vector3::new(0.6, 0.2, 0.05); // Marrón oscuro
-    let ash_color = Vector3::new(0.3, 0.1, 0.05);  // Gris oscuro
+    let elevation = rocky_elevation(&rotated_pos, params);
     
-    let elevation = (base_noise + detail_noise * 0.3) * 0.5 + 0.5;
+    // Las zonas bajas se aclaran un poco para que la ceniza no quede negra
+    let shade = if elevation < 0.4 { elevation + 0.3 } else { elevation };
+    let mut final_color = params.gradient("terrain", elevation) * shade;
     
-    // Crear patrones de lava
-    let lava_pattern = (fractal_noise(&Vector3::new(rotated_pos.x * 10.0, rotated_pos.y * 10.0, rotated_pos.z * 10.0), 1) * 2.0 - 1.0).abs();
+    // Añadir efectos de lava: la roca se tiñe junto a las grietas (el brillo va en rocky_planet_lava)
+    let lava = lava_flow(&rotated_pos, time, params);
+    final_color = Vector3::new(
+        final_color.x + lava_color.x * 0.5 * lava,
+        final_color.y + lava_color.y * 0.3 * lava,
+        final_color.z + lava_color.z * 0.2 * lava
+    );
     
-    let mut final_color = if elevation > 0.7 {
-        // Zonas altas
-        Vector3::new(
-            rock_color.x * elevation,
-            rock_color.y * elevation,
-            rock_color.z * elevation
-        )
-    } else if elevation < 0.4 {
-        // Zonas bajas
-        Vector3::new(
-            ash_color.x * (elevation + 0.3),
-            ash_color.y * (elevation + 0.3),
-            ash_color.z * (elevation + 0.3)
-        )
+    final_color
+}
+
+/// Relief of the rocky planet: the same elevation that picks its colors, so the
+/// rock-colored highlands are the peaks and the ash lowlands the valleys
+pub fn rocky_planet_height(pos: &Vector3, time: f32, params: &PlanetParams) -> f32 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    (rocky_elevation(&rotated_pos, params) * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+const ERUPTION_RATE: f32 = 0.4;    // ventanas de erupción por segundo
+const ERUPTION_CHANCE: f32 = 0.85; // umbral del hash: ~15% de las regiones entran en erupción en cada ventana
+
+// Grietas de lava que fluyen: una red de crestas de FBM cuyo dominio se deforma con
+// otro FBM que se desplaza con el tiempo, así los ríos serpentean en vez de parpadear
+fn lava_flow(rotated_pos: &Vector3, time: f32, params: &PlanetParams) -> f32 {
+    let p = *rotated_pos * (4.0 * params.noise_scale);
+    let warp = Vector3::new(
+        fbm(&(p + Vector3::new(time * 0.15, 0.0, 0.0)), 3),
+        fbm(&(p + Vector3::new(5.2, -time * 0.12, 1.3)), 3),
+        fbm(&(p + Vector3::new(1.7, 9.2, time * 0.1)), 3),
+    );
+    let ridge = 1.0 - (fbm(&(p * 1.5 + warp * 2.0), 4) * 2.0).abs();
+    smoothstep(0.9, 0.98, ridge)
+}
+
+/// Light of the rocky planet's lava: flowing cracks with pulsing hotspots plus
+/// short-lived eruption patches that pop up at random places and times
+pub fn rocky_planet_lava(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    let lava_color = params.color("lava");
+
+    // Puntos calientes: la intensidad de la lava late lento, con fase distinta en cada zona
+    let ns = params.noise_scale;
+    let hotspot = value_noise(&(rotated_pos * (3.0 * ns) + Vector3::new(0.0, time * 0.05, 0.0))) * 0.5 + 0.5;
+    let pulse = 0.7 + 0.3 * (time * 2.5 + hotspot * 6.0).sin();
+    let flow = lava_flow(&rotated_pos, time, params) * (0.6 + 0.8 * hotspot) * pulse;
+
+    // Erupciones: cada ventana de tiempo, el hash de (región, ventana) decide qué
+    // regiones estallan; el parche se enciende de golpe y se apaga al final de la ventana
+    let window = (time * ERUPTION_RATE).floor();
+    let age = time * ERUPTION_RATE - window;
+    let region = rotated_pos * (5.0 * ns);
+    let cell = Vector3::new(region.x.floor(), region.y.floor(), region.z.floor());
+    let chance = noise(&(cell + Vector3::new(window * 7.0, window * 13.0, window * 3.0)));
+    let eruption = if chance > ERUPTION_CHANCE {
+        let center = cell + Vector3::new(0.5, 0.5, 0.5);
+        let reach = (region - center).length();
+        smoothstep(0.5, 0.1, reach) * (1.0 - age) * (1.0 - age)
     } else {
-        // Zonas medias
-        Vector3::new(
-            base_color.x * elevation,
-            base_color.y * elevation,
-            base_color.z * elevation
-        )
+        0.0
     };
-    
-    // Añadir efectos de lava
-    if lava_pattern > 0.7 {
-        final_color = Vector3::new(
-            final_color.x + lava_color.x * 0.5,
-            final_color.y + lava_color.y * 0.3,
-            final_color.z + lava_color.z * 0.2
-        );
-    }
-    
-    final_color
+
+    lava_color * (flow * 1.5 + eruption * 3.0)
 }
 
 // GIGANTE GASEOSO CON PATRON DE NEBULOSA (Tipo 1)
-fn gas_giant_color(pos: &Vector3, time: f32) -> Vector3 {
-    let rotated_pos = rotate_planet_position(pos, time, 0.5);
+// La tormenta es un vórtice fijo en latitud que deriva despacio en longitud
+const STORM_LATITUDE: f32 = -0.35; // radianes (hemisferio sur, como la gran mancha roja)
+const STORM_RADIUS: f32 = 0.4;     // radio angular del ojo de la tormenta
+const STORM_DRIFT: f32 = 0.03;     // rad/s de deriva en longitud respecto a las bandas
+const STORM_SPIN: f32 = 0.6;       // rad/s de giro del ojo (rígido, para que la espiral no se enrosque sin fin)
+const STORM_TWIST: f32 = 2.5;      // radianes de torsión en el centro que dan la forma de espiral
+
+/// Center of the gas giant's storm, as a unit vector in the planet's rotating frame
+fn storm_center(time: f32) -> Vector3 {
+    let longitude = time * STORM_DRIFT;
+    Vector3::new(STORM_LATITUDE.cos() * longitude.cos(), STORM_LATITUDE.sin(), STORM_LATITUDE.cos() * longitude.sin())
+}
+
+// Rotación de `v` alrededor del eje unitario `axis` (fórmula de Rodrigues)
+fn rotate_around(v: &Vector3, axis: &Vector3, angle: f32) -> Vector3 {
+    let (sin_a, cos_a) = angle.sin_cos();
+    *v * cos_a + axis.cross(*v) * sin_a + *axis * (axis.dot(*v) * (1.0 - cos_a))
+}
+
+pub fn gas_giant_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    
+    let ns = params.noise_scale;
+    
+    // Vórtice: alrededor del centro de la tormenta el dominio se tuerce más cuanto más
+    // cerca, así nubes y bandas se enroscan en una espiral alrededor del ojo
+    let center = storm_center(time);
+    let length = rotated_pos.length();
+    let distance = if length > 0.0 { (rotated_pos.dot(center) / length).clamp(-1.0, 1.0).acos() } else { PI };
+    let influence = smoothstep(STORM_RADIUS * 2.0, 0.0, distance);
+    let swirl = influence * influence * STORM_TWIST;
+    let rotated_pos = rotate_around(&rotated_pos, &center, swirl);
     
     // Patrones de nebulosa para gigante gaseoso
     let cloud_base = fractal_noise(&Vector3::new(
-        rotated_pos.x * 3.0 + time * 0.1,
-        rotated_pos.y * 3.0,
-        rotated_pos.z * 3.0
+        rotated_pos.x * 3.0 * ns + time * 0.1,
+        rotated_pos.y * 3.0 * ns,
+        rotated_pos.z * 3.0 * ns
     ), 3);
     
     let cloud_detail = fractal_noise(&Vector3::new(
-        rotated_pos.x * 8.0 + time * 0.2,
-        rotated_pos.y * 8.0,
-        rotated_pos.z * 8.0
+        rotated_pos.x * 8.0 * ns + time * 0.2,
+        rotated_pos.y * 8.0 * ns,
+        rotated_pos.z * 8.0 * ns
     ), 2);
     
-    let band_pattern = (rotated_pos.y * 4.0 + time * 0.05).sin() * 0.5 + 0.5;
-    
-    // Colores típicos de nebulosa (tonos púrpura y azul)
-    let base_color = Vector3::new(0.6, 0.4, 0.8); // Púrpura claro
-    let band_color1 = Vector3::new(0.4, 0.6, 0.9); // Azul claro
-    let band_color2 = Vector3::new(0.7, 0.3, 0.9); // Violeta
-    let storm_color = Vector3::new(0.9, 0.5, 0.8); // Rosa intenso
+    let band_pattern = (rotated_pos.y * params.band_frequency + time * 0.05).sin() * 0.5 + 0.5;
     
-    // Crear bandas atmosféricas
-    let band_mix = if band_pattern > 0.7 {
-        band_color1
-    } else if band_pattern < 0.3 {
-        band_color2
-    } else {
-        base_color
-    };
+    // Colores de nebulosa (definidos en planets.toml); el gradiente "bands" da el
+    // color de cada franja atmosférica
+    let storm_color = params.color("storm");
+    let band_mix = params.gradient("bands", band_pattern);
     
-    // Añadir patrones de nubes
-    let cloud_intensity = (cloud_base + cloud_detail * 0.5) * 0.5 + 0.5;
+    // Añadir patrones de nubes (el ruido fractal tiene signo: sin el max los huecos
+    // entre nubes quedaban con color negativo)
+    let cloud_intensity = ((cloud_base + cloud_detail * 0.5) * 0.5 + 0.5).max(0.0);
     let mut final_color = Vector3::new(
         band_mix.x * cloud_intensity,
         band_mix.y * cloud_intensity,
         band_mix.z * cloud_intensity
     );
     
-    // Añadir tormenta (como la gran mancha roja)
-    let storm_noise = fractal_noise(&Vector3::new(
-        rotated_pos.x * 2.0 + time * 0.05,
-        rotated_pos.y * 2.0,
-        rotated_pos.z * 2.0
-    ), 2);
-    
-    if storm_noise > 0.8 && rotated_pos.y.abs() < 0.3 {
-        let storm_strength = (storm_noise - 0.8) * 5.0;
+    // Añadir tormenta (como la gran mancha roja): el ojo del vórtice, con brazos de
+    // nube leídos del dominio enroscado y girando como un bloque alrededor del centro
+    let spinning = rotate_around(&rotated_pos, &center, -time * STORM_SPIN);
+    let storm_arms = fbm(&(spinning * (10.0 * ns)), 3) * 0.5 + 0.5;
+    let storm_strength = smoothstep(STORM_RADIUS, STORM_RADIUS * 0.4, distance) * (0.6 + 0.4 * storm_arms);
+    if storm_strength > 0.0 {
         final_color = Vector3::new(
             final_color.x * (1.0 - storm_strength) + storm_color.x * storm_strength,
             final_color.y * (1.0 - storm_strength) + storm_color.y * storm_strength,
@@ -279,34 +589,27 @@ fn gas_giant_color(pos: &Vector3, time: f32) -> Vector3 {
 }
 
 // PLANETA ARCOIRIS CON MOVIMIENTO (Tipo 2)
-fn rainbow_planet_color(pos: &Vector3, time: f32) -> Vector3 {
-    let rotated_pos = rotate_planet_position(pos, time, 0.4);
+pub fn rainbow_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
     
     // Coordenadas esféricas para crear bandas de arcoiris
     let theta = rotated_pos.y.atan2(rotated_pos.x);
     let _phi = rotated_pos.z.atan2((rotated_pos.x * rotated_pos.x + rotated_pos.y * rotated_pos.y).sqrt());
     
     // Crear bandas de arcoiris basadas en ángulos
-    let rainbow_bands = ((theta * 3.0 + time * 0.5).sin() * 0.5 + 0.5) * 6.0;
-    
-    // Colores del arcoiris
-    let color = match rainbow_bands as i32 {
-        0 => Vector3::new(1.0, 0.0, 0.0),     // Rojo
-        1 => Vector3::new(1.0, 0.5, 0.0),     // Naranja
-        2 => Vector3::new(1.0, 1.0, 0.0),     // Amarillo
-        3 => Vector3::new(0.0, 1.0, 0.0),     // Verde
-        4 => Vector3::new(0.0, 0.0, 1.0),     // Azul
-        5 => Vector3::new(0.3, 0.0, 0.5),     // Índigo
-        _ => Vector3::new(0.5, 0.0, 0.5),     // Violeta
-    };
+    let rainbow_bands = ((theta * params.band_frequency + time * 0.5).sin() * 0.5 + 0.5) * 6.0;
+    
+    // Colores del arcoiris: el gradiente "bands" de planets.toml, de 0 a 6
+    let color = params.gradient("bands", rainbow_bands);
     
-    // Añadir efecto brillante y pulsante
+    // Añadir efecto brillante y pulsante; noise() nunca es negativo, así el brillo solo aclara
     let pulse = (time * 2.0).sin() * 0.2 + 0.8;
-    let sparkle = fractal_noise(&Vector3::new(
-        rotated_pos.x * 20.0 + time,
-        rotated_pos.y * 20.0,
-        rotated_pos.z * 20.0
-    ), 1);
+    let ns = params.noise_scale;
+    let sparkle = noise(&Vector3::new(
+        rotated_pos.x * 20.0 * ns + time,
+        rotated_pos.y * 20.0 * ns,
+        rotated_pos.z * 20.0 * ns
+    ));
     
     Vector3::new(
         color.x * pulse + sparkle * 0.3,
@@ -316,19 +619,19 @@ fn rainbow_planet_color(pos: &Vector3, time: f32) -> Vector3 {
 }
 
 // PLANETA GLITTER (Tipo 3) - Girly con brillo
-fn glitter_planet_color(pos: &Vector3, time: f32) -> Vector3 {
-    let rotated_pos = rotate_planet_position(pos, time, 0.35);
+pub fn glitter_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
     
     // Patrones suaves y femeninos
-    let pattern1 = (rotated_pos.x * 4.0 + time * 0.3).sin();
-    let pattern2 = (rotated_pos.y * 4.0 + time * 0.2).cos();
-    let _pattern3 = (rotated_pos.z * 4.0 + time * 0.4).sin();
+    let pattern1 = (rotated_pos.x * params.band_frequency + time * 0.3).sin();
+    let pattern2 = (rotated_pos.y * params.band_frequency + time * 0.2).cos();
+    let _pattern3 = (rotated_pos.z * params.band_frequency + time * 0.4).sin();
     
-    // Colores pastel suaves
-    let base_pink = Vector3::new(1.0, 0.8, 0.9);  // Rosa claro
-    let lavender = Vector3::new(0.8, 0.8, 1.0);   // Lavanda
-    let mint = Vector3::new(0.8, 1.0, 0.9);       // Menta
-    let peach = Vector3::new(1.0, 0.9, 0.8);      // Melocotón
+    // Colores pastel suaves (definidos en planets.toml)
+    let base_pink = params.color("pink");
+    let lavender = params.color("lavender");
+    let mint = params.color("mint");
+    let peach = params.color("peach");
     
     // Crear patrones suaves que se mezclan
     let mix1 = (pattern1 * 0.5 + 0.5).powf(2.0);
@@ -349,10 +652,11 @@ fn glitter_planet_color(pos: &Vector3, time: f32) -> Vector3 {
     };
     
     // Añadir destellos de "glitter"
+    let ns = params.noise_scale;
     let glitter = fractal_noise(&Vector3::new(
-        rotated_pos.x * 40.0 + time * 4.0,
-        rotated_pos.y * 40.0,
-        rotated_pos.z * 40.0
+        rotated_pos.x * 40.0 * ns + time * 4.0,
+        rotated_pos.y * 40.0 * ns,
+        rotated_pos.z * 40.0 * ns
     ), 1);
     
     if glitter > 0.95 {
@@ -367,8 +671,8 @@ fn glitter_planet_color(pos: &Vector3, time: f32) -> Vector3 {
 }
 
 // PLANETA CORAZÓN (Tipo 4) - Muy girly
-fn heart_planet_color(pos: &Vector3, time: f32) -> Vector3 {
-    let rotated_pos = rotate_planet_position(pos, time, 0.45);
+pub fn heart_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
     
     // Coordenadas para formar patrones de corazón
     let x = rotated_pos.x;
@@ -379,24 +683,25 @@ fn heart_planet_color(pos: &Vector3, time: f32) -> Vector3 {
     let heart_shape = (x*x + 9.0/4.0 * y*y + z*z - 1.0).powf(3.0) - 
                       (x*x * z.powf(3.0)) - (9.0/80.0 * y*y * z.powf(3.0));
     
-    // Colores pastel intensos
-    let main_color = Vector3::new(1.0, 0.6, 0.8);  // Rosa intenso
-    let accent_color = Vector3::new(0.9, 0.5, 0.9); // Magenta claro
-    let background_color = Vector3::new(1.0, 0.9, 0.95); // Rosa muy claro
+    // Colores pastel intensos (definidos en planets.toml)
+    let main_color = params.color("main");
+    let accent_color = params.color("accent");
+    let background_color = params.color("background");
     
     // Patrones girly
-    let pattern1 = (x * 5.0 + time * 0.5).sin();
-    let pattern2 = (y * 5.0 + time * 0.3).cos();
-    let _pattern3 = (z * 5.0 + time * 0.4).sin();
+    let pattern1 = (x * params.band_frequency + time * 0.5).sin();
+    let pattern2 = (y * params.band_frequency + time * 0.3).cos();
+    let _pattern3 = (z * params.band_frequency + time * 0.4).sin();
     
     let pattern = (pattern1 + pattern2) / 2.0; // Usamos solo 2 patrones
     
-    // Efecto de brillo
-    let shine = fractal_noise(&Vector3::new(
-        rotated_pos.x * 30.0 + time * 3.0,
-        rotated_pos.y * 30.0,
-        rotated_pos.z * 30.0
-    ), 1);
+    // Efecto de brillo (en [0, 1): solo aclara)
+    let ns = params.noise_scale;
+    let shine = noise(&Vector3::new(
+        rotated_pos.x * 30.0 * ns + time * 3.0,
+        rotated_pos.y * 30.0 * ns,
+        rotated_pos.z * 30.0 * ns
+    ));
     
     // Elegir color basado en la forma del corazón
     let base_color = if heart_shape < 0.0 {
@@ -418,32 +723,302 @@ fn heart_planet_color(pos: &Vector3, time: f32) -> Vector3 {
     )
 }
 
-pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
-    let world_pos = fragment.world_position;
-    let normal = Vector3::new(
-        fragment.world_position.x,
-        fragment.world_position.y,
-        fragment.world_position.z
-    );
-    
-    // Normalizar el vector normal manualmente
-    let length = (normal.x * normal.x + normal.y * normal.y + normal.z * normal.z).sqrt();
-    let normal = if length > 0.0 {
-        Vector3::new(
-            normal.x / length,
-            normal.y / length,
-            normal.z / length
-        )
+// PLANETA TIPO TIERRA CON OCÉANOS, CONTINENTES Y CASQUETES POLARES (Tipo 5)
+const EARTH_SEA_LEVEL: f32 = 0.62;
+const EARTH_FOAM_WIDTH: f32 = 0.015; // ancho de la espuma de la costa, en unidades de altura
+
+/// Height field of the earth planet: (height in [0, 1], ice cover in [0, 1])
+fn earth_surface(pos: &Vector3, time: f32, params: &PlanetParams) -> (f32, f32) {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    let length = rotated_pos.length();
+    let dir = if length > 0.0 { rotated_pos / length } else { Vector3::new(0.0, 1.0, 0.0) };
+
+    // Capas de FBM: continentes grandes, luego cordilleras más finas encima
+    let ns = params.noise_scale;
+    let continents = fbm(&(dir * (2.0 * ns)), 4);
+    let mountains = fbm(&(dir * (9.0 * ns) + Vector3::new(17.0, 3.0, 8.0)), 3);
+    let height = (0.5 + continents + mountains * 0.25 * continents.max(0.0)).clamp(0.0, 1.0);
+
+    // Hielo en las latitudes altas (con borde irregular) y en los picos
+    let latitude = dir.y.abs();
+    let ragged = latitude + fbm(&(dir * (6.0 * ns)), 2) * 0.15;
+    let ice = smoothstep(0.78, 0.86, ragged).max(smoothstep(0.82, 0.9, height));
+    (height, ice)
+}
+
+pub fn earth_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let (height, ice) = earth_surface(pos, time, params);
+
+    // Colores (definidos en planets.toml): "water" va de la costa (0) a lo profundo (1)
+    // y "land" de las llanuras (0) a las montañas (1)
+    let foam_color = params.color("foam");
+    let ice_color = params.color("ice");
+
+    let color = if height < EARTH_SEA_LEVEL {
+        // Agua: más oscura cuanto más profunda, con olas que se mueven con el tiempo
+        let depth = (EARTH_SEA_LEVEL - height) / EARTH_SEA_LEVEL;
+        let water = params.gradient("water", (depth * 4.0).min(1.0));
+        let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+        let waves = value_noise(&(rotated_pos * (40.0 * params.noise_scale) + Vector3::new(time * 0.6, 0.0, time * 0.4)));
+        let water = water * (1.0 + waves * 0.08);
+
+        // Espuma donde la altura cruza el nivel del mar, latiendo como la marea
+        let shore = 1.0 - (EARTH_SEA_LEVEL - height) / EARTH_FOAM_WIDTH;
+        let tide = 0.6 + 0.4 * (time * 2.0 + height * 300.0).sin();
+        water.lerp(foam_color, (shore * tide).clamp(0.0, 1.0))
     } else {
-        Vector3::new(0.0, 0.0, 1.0) // Vector por defecto
+        // Tierra: verde en las zonas bajas, café hacia las montañas
+        let elevation = (height - EARTH_SEA_LEVEL) / (1.0 - EARTH_SEA_LEVEL);
+        params.gradient("land", smoothstep(0.05, 0.45, elevation))
     };
-    
-    // Dirección de luz fija
-    let light_dir = Vector3::new(1.0, 1.0, 1.0);
+
+    color.lerp(ice_color, ice)
+}
+
+/// Specular strength of the earth planet: open water reflects, land and ice do not
+pub fn earth_planet_gloss(pos: &Vector3, time: f32, params: &PlanetParams) -> f32 {
+    let (height, ice) = earth_surface(pos, time, params);
+    smoothstep(EARTH_SEA_LEVEL, EARTH_SEA_LEVEL - EARTH_FOAM_WIDTH, height) * (1.0 - ice)
+}
+
+// PLANETA DE HIELO CON GRIETAS Y BRILLO INTERNO (Tipo 6)
+// Grietas: bordes de las celdas de Worley (donde las dos distancias casi empatan), en dos escalas
+fn ice_cracks(rotated_pos: &Vector3, params: &PlanetParams) -> f32 {
+    let ns = params.noise_scale;
+    let (near, second) = worley(&(*rotated_pos * (6.0 * ns)));
+    let (fine_near, fine_second) = worley(&(*rotated_pos * (15.0 * ns) + Vector3::new(31.0, 7.0, 19.0)));
+    let large = 1.0 - smoothstep(0.0, 0.08, second - near);
+    let fine = 1.0 - smoothstep(0.0, 0.05, fine_second - fine_near);
+    large.max(fine * 0.6)
+}
+
+pub fn ice_planet_color(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+
+    // Colores fríos (definidos en planets.toml); "surface" va de la base (0) a la escarcha (1)
+    let crack_color = params.color("crack");
+
+    // Escarcha suave sobre la base azul pálida y grietas nítidas encima
+    let frost = fbm(&(rotated_pos * (10.0 * params.noise_scale)), 3) * 0.5 + 0.5;
+    let surface = params.gradient("surface", frost * 0.6);
+    surface.lerp(crack_color, ice_cracks(&rotated_pos, params))
+}
+
+/// Light the ice gives off by itself: a faint glow from inside, strongest in the
+/// cracks, that pulses slowly with `time`
+pub fn ice_planet_glow(pos: &Vector3, time: f32, params: &PlanetParams) -> Vector3 {
+    let rotated_pos = rotate_planet_position(pos, time, params.rotation_speed);
+    let glow_color = params.color("glow");
+
+    // Cada región late con su propia fase para que el brillo no parpadee todo a la vez
+    let phase = value_noise(&(rotated_pos * (3.0 * params.noise_scale))) * 3.0;
+    let pulse = 0.6 + 0.4 * (time * 1.5 + phase).sin();
+    glow_color * ((0.08 + 0.5 * ice_cracks(&rotated_pos, params)) * pulse)
+}
+
+// LUNAS: superficies en el espacio de la malla; las lunas redondas están acopladas
+// por marea (ver Moon::model_matrix) y el mismo lado (+X) mira siempre al planeta
+const MOON_MARE: Vector3 = Vector3::new(0.35, 0.35, 0.38); // gris oscuro de los mares
+// Cráteres en dos escalas: (frecuencia del Worley, radio en celdas, intensidad)
+const MOON_CRATERS: [(f32, f32, f32); 2] = [(4.0, 0.32, 1.0), (11.0, 0.26, 0.6)];
+const MOON_RIM_WIDTH: f32 = 0.18; // ancho del borde iluminado, en radios del cráter
+
+const ICE_MOON_COLOR: Vector3 = Vector3::new(0.85, 0.9, 0.95);  // hielo limpio
+const ICE_MOON_CRACK: Vector3 = Vector3::new(0.55, 0.35, 0.25); // sales rojizas en las grietas
+// Pocos cráteres chicos: el hielo se rehace y los borra
+const ICE_MOON_CRATERS: [(f32, f32, f32); 1] = [(9.0, 0.2, 0.35)];
+
+const CAPTURED_COLOR: Vector3 = Vector3::new(0.3, 0.27, 0.24); // roca carbonosa oscura
+const CAPTURED_CRATERS: [(f32, f32, f32); 2] = [(5.0, 0.35, 0.8), (13.0, 0.3, 0.6)];
+
+// Oclusión de los cráteres: anillo del tamaño de un cráter chico y la profundidad
+// del más grande en unidades de la malla (ver crater_height)
+const MOON_AO_RADIUS: f32 = 0.03;
+const MOON_AO_RELIEF: f32 = 0.04;
+
+/// Profile of the crater of one `craters` scale nearest to `dir`: how deep into
+/// the bowl (1 at the center, 0 past the edge) and how close to the rim (1 on it)
+fn crater_profile(dir: Vector3, index: usize, frequency: f32, radius: f32) -> (f32, f32) {
+    // Cada escala con su desplazamiento para que los cráteres chicos no caigan sobre los grandes
+    let cells = dir * frequency + Vector3::new(index as f32 * 17.0, 5.0, 0.0);
+    let (nearest, _) = worley(&cells);
+    // El radio cambia por regiones: zonas con cráteres grandes y otras casi lisas
+    let size = smoothstep(-0.6, 0.5, value_noise(&(cells * 0.7)));
+    let t = nearest / (radius * size).max(1e-3);
+    ((1.0 - t * t).max(0.0), (-((t - 1.0) / MOON_RIM_WIDTH).powi(2)).exp())
+}
+
+/// Brightness factor of Worley craters over the unit direction `dir`: a sunken
+/// dark floor and a bright raised rim at each of the `craters` scales
+fn crater_shading(dir: Vector3, craters: &[(f32, f32, f32)]) -> f32 {
+    let mut shading = 1.0;
+    for (index, &(frequency, radius, strength)) in craters.iter().enumerate() {
+        let (floor, rim) = crater_profile(dir, index, frequency, radius);
+        shading *= (1.0 - floor * 0.4 * strength) * (1.0 + rim * 0.45 * strength);
+    }
+    shading
+}
+
+/// Height of the cratered surface at `dir`, around 0 with bowls down to about -1:
+/// the same craters as `crater_shading`, deeper the larger they are
+fn crater_height(dir: Vector3, craters: &[(f32, f32, f32)]) -> f32 {
+    let largest = craters.iter().map(|&(frequency, _, _)| frequency).fold(f32::MAX, f32::min);
+    craters.iter().enumerate()
+        .map(|(index, &(frequency, radius, strength))| {
+            let (floor, rim) = crater_profile(dir, index, frequency, radius);
+            (rim * 0.3 - floor) * strength * largest / frequency
+        })
+        .sum()
+}
+
+/// Albedo of a rocky moon at a point of its mesh: dark maria, more common on the
+/// near side (local +X, the side that faces the planet), under Worley craters
+pub fn moon_surface_color(pos: &Vector3) -> Vector3 {
+    let dir = if pos.length() > 0.0 { pos.normalized() } else { Vector3::new(1.0, 0.0, 0.0) };
+
+    // Mares: manchas grandes y suaves de lava antigua
+    let patches = fbm(&(dir * 1.8 + Vector3::new(7.0, 3.0, 11.0)), 3) + dir.x * 0.2;
+    MOON_COLOR.lerp(MOON_MARE, smoothstep(0.05, 0.3, patches)) * crater_shading(dir, &MOON_CRATERS)
+}
+
+/// Albedo of an icy moon: bright ice crossed by a web of reddish cracks (the
+/// edges between Worley cells) and only a few small craters
+pub fn icy_moon_color(pos: &Vector3) -> Vector3 {
+    let dir = if pos.length() > 0.0 { pos.normalized() } else { Vector3::new(1.0, 0.0, 0.0) };
+
+    // Grietas en dos escalas: largas y finas, más otras menores entre ellas
+    let mut cracks: f32 = 0.0;
+    for (frequency, width) in [(3.0, 0.05), (7.0, 0.03)] {
+        let (nearest, second) = worley(&(dir * frequency + Vector3::new(3.0, 1.0, 9.0)));
+        cracks = cracks.max(1.0 - smoothstep(0.0, width, second - nearest));
+    }
+    let frost = fbm(&(dir * 5.0), 2) * 0.06;
+    (ICE_MOON_COLOR * (1.0 + frost)).lerp(ICE_MOON_CRACK, cracks * 0.8) * crater_shading(dir, &ICE_MOON_CRATERS)
+}
+
+/// Albedo of a captured asteroid: dark carbon-rich rock with lighter dust in
+/// patches, heavily cratered
+pub fn captured_moon_color(pos: &Vector3) -> Vector3 {
+    let dir = if pos.length() > 0.0 { pos.normalized() } else { Vector3::new(1.0, 0.0, 0.0) };
+    let dust = smoothstep(0.1, 0.5, fbm(&(dir * 3.0 + Vector3::new(2.0, 8.0, 4.0)), 3));
+    CAPTURED_COLOR * (1.0 + dust * 0.4) * crater_shading(dir, &CAPTURED_CRATERS)
+}
+
+/// Light on a moon at a point (`local` in mesh space, `world_pos` in the world)
+/// with world `normal`; moons are matte, so there is no specular
+fn moon_light(local: Vector3, world_pos: Vector3, normal: Vector3, globals: &GlobalUniforms, shader: MoonShader, shadow_map: &ShadowMap) -> SurfaceLight {
+    let light_dir = light_direction(world_pos, &globals.light);
+    let n_dot_l = normal.dot(light_dir);
+    let shadow = shadow_map.visibility(world_pos, n_dot_l);
+    let craters: &[(f32, f32, f32)] = match shader {
+        MoonShader::Rocky => &MOON_CRATERS,
+        MoonShader::Icy => &ICE_MOON_CRATERS,
+        MoonShader::Captured => &CAPTURED_CRATERS,
+    };
+    let occlusion = cavity_occlusion(&local, MOON_AO_RADIUS, MOON_AO_RELIEF, |pos| crater_height(pos.normalized(), craters));
+    SurfaceLight { n_dot_l, diffuse: simulate_lighting(&normal, &light_dir, shadow, occlusion), specular: 0.0, shadow }
+}
+
+/// Lit color of a moon: its surface color under diffuse light and the shadow map,
+/// so the planet's shadow darkens it during a lunar eclipse. Lit where the shading
+/// mode says, like the planet. `model` is the moon's model matrix.
+fn moon_shader(fragment: &Fragment, globals: &GlobalUniforms, model: &Matrix, shader: MoonShader, shadow_map: &ShadowMap) -> Vector3 {
+    let local = fragment.world_position;
+    let world = multiply_matrix_vector4(model, &Vector4::new(local.x, local.y, local.z, 1.0));
+    let world_pos = Vector3::new(world.x, world.y, world.z);
+    let normal = if fragment.normal.length() > 0.0 { fragment.normal.normalized() } else { Vector3::new(0.0, 0.0, 1.0) };
+
+    let albedo = match shader {
+        MoonShader::Rocky => moon_surface_color(&local),
+        MoonShader::Icy => icy_moon_color(&local),
+        MoonShader::Captured => captured_moon_color(&local),
+    };
+    // Igual que en el planeta: por fragmento o la interpolada desde los vértices
+    let diffuse = match globals.shading {
+        ShadingMode::Phong => moon_light(local, world_pos, normal, globals, shader, shadow_map).diffuse,
+        ShadingMode::Flat | ShadingMode::Gouraud => fragment.light.x,
+    };
+    globals.color_space.decode(albedo) * globals.light.radiance() * diffuse
+}
+
+// AURORAS POLARES: capa que cualquier preset puede activar con `aurora = true`
+const AURORA_LATITUDE: f32 = 0.8; // seno de la latitud del centro del óvalo auroral
+const AURORA_WIDTH: f32 = 0.12;   // medio ancho del óvalo, en la misma escala
+
+/// Aurora light at a point with planet-space `normal` (tilted with the planet but
+/// not spinning): curtains around both poles that ripple with `time`, visible
+/// only on the night side
+fn aurora(normal: &Vector3, n_dot_l: f32, time: f32, params: &PlanetParams) -> Vector3 {
+    // Las auroras siguen al campo magnético, no a la superficie: usan la normal sin rotar
+    let night = smoothstep(0.1, -0.25, n_dot_l);
+    let band = smoothstep(AURORA_WIDTH, 0.0, (normal.y.abs() - AURORA_LATITUDE).abs());
+    if night <= 0.0 || band <= 0.0 {
+        return Vector3::zero();
+    }
+
+    // Cortinas: ruido sobre la dirección horizontal que se desplaza con el tiempo,
+    // con pliegues finos que recorren el óvalo
+    let around = Vector3::new(normal.x, 0.0, normal.z);
+    let around = if around.length() > 0.0 { around.normalized() } else { around };
+    let hemisphere = if normal.y > 0.0 { 0.0 } else { 10.0 };
+    let folds = fbm(&Vector3::new(around.x * 5.0 + time * 0.2, around.z * 5.0, time * 0.15 + hemisphere), 3);
+    let ripples = 0.6 + 0.4 * (around.x * 20.0 + around.z * 13.0 + time * 2.0 + folds * 8.0).sin();
+    let curtains = smoothstep(-0.1, 0.4, folds) * ripples;
+
+    params.color("aurora") * (band * night * curtains)
+}
+
+/// Where lit surfaces evaluate their lighting
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ShadingMode {
+    Flat,    // una iluminación por triángulo, con la normal promedio de sus vértices
+    Gouraud, // iluminación en los vértices, interpolada por el rasterizador (Vertex::light)
+    #[default]
+    Phong,   // iluminación en cada fragmento con la normal interpolada
+}
+
+impl ShadingMode {
+    pub fn next(self) -> Self {
+        match self {
+            ShadingMode::Flat => ShadingMode::Gouraud,
+            ShadingMode::Gouraud => ShadingMode::Phong,
+            ShadingMode::Phong => ShadingMode::Flat,
+        }
+    }
+}
+
+impl FromStr for ShadingMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "flat" => Ok(ShadingMode::Flat),
+            "gouraud" => Ok(ShadingMode::Gouraud),
+            "phong" => Ok(ShadingMode::Phong),
+            _ => Err(format!("invalid shading mode: {} (expected flat, gouraud or phong)", name)),
+        }
+    }
+}
+
+/// Light reaching one point of a planet's surface
+struct SurfaceLight {
+    n_dot_l: f32,
+    diffuse: f32,  // difusa con sombra más ambiente, como la da simulate_lighting
+    specular: f32, // pico de Blinn-Phong antes del gloss y de la sombra; 0 sin gloss
+    shadow: f32,   // fracción de luz que llega (1.0 = sin sombra)
+}
+
+/// Unit direction from `world_pos` towards the light
+fn light_direction(world_pos: Vector3, light: &Light) -> Vector3 {
+    let light_dir = Vector3::new(
+        light.position.x - world_pos.x,
+        light.position.y - world_pos.y,
+        light.position.z - world_pos.z
+    );
     
     // Normalizar la dirección de la luz manualmente
     let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
-    let light_dir = if light_length > 0.0 {
+    if light_length > 0.0 {
         Vector3::new(
             light_dir.x / light_length,
             light_dir.y / light_length,
@@ -451,100 +1026,296 @@ pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
         )
     } else {
         Vector3::new(1.0, 0.0, 0.0) // Vector por defecto
-    };
+    }
+}
+
+/// Lighting of `planet` at a point (`local` in mesh space, `world_pos` in the
+/// world) with world `normal`: per fragment in Phong mode, per vertex or per
+/// triangle in the other modes
+fn surface_light(local: Vector3, world_pos: Vector3, normal: Vector3, globals: &GlobalUniforms, planet: &PlanetType, model: &Matrix, shadow_map: &ShadowMap) -> SurfaceLight {
+    // Dirección hacia la luz (posición configurada en planets.toml)
+    let light_dir = light_direction(world_pos, &globals.light);
     
+    // Sombra de la luna (u otros cuerpos) según el shadow map
+    let n_dot_l = normal.x * light_dir.x + normal.y * light_dir.y + normal.z * light_dir.z;
+    let mut shadow = shadow_map.visibility(world_pos, n_dot_l);
+    if planet.params.rings {
+        shadow *= ring_shadow(world_pos, globals.light.position, model);
+    }
+    
+    // Oclusión ambiental de los planetas con relieve (valles y cráteres más oscuros)
+    let occlusion = planet.height.map_or(1.0, |height| {
+        cavity_occlusion(&local, TERRAIN_AO_RADIUS, TERRAIN_AO_RELIEF, |pos| height(pos, globals.time, &planet.params))
+    });
+
     // Calcular iluminación básica
-    let light_intensity = simulate_lighting(&normal, &light_dir);
-    
-    // Seleccionar color basado en el tipo de planeta
-    let base_color = match uniforms.planet_type {
-        0 => rocky_planet_color(&world_pos, uniforms.time),      // Planeta rocoso
-        1 => gas_giant_color(&world_pos, uniforms.time),        // Gigante gaseoso
-        2 => rainbow_planet_color(&world_pos, uniforms.time),   // Planeta arcoiris
-        3 => glitter_planet_color(&world_pos, uniforms.time),   // Planeta glitter (girly)
-        4 => heart_planet_color(&world_pos, uniforms.time),     // Planeta corazón (girly)
-        _ => rocky_planet_color(&world_pos, uniforms.time),     // Default
+    let diffuse = simulate_lighting(&normal, &light_dir, shadow, occlusion);
+
+    // Reflejo especular (Blinn-Phong), solo para las superficies que lo piden
+    let mut specular = 0.0;
+    if planet.gloss.is_some() && n_dot_l > 0.0 {
+        let view_dir = globals.camera_position - world_pos;
+        let half_dir = light_dir + if view_dir.length() > 0.0 { view_dir.normalized() } else { normal };
+        if half_dir.length() > 0.0 {
+            specular = normal.dot(half_dir.normalized()).max(0.0).powf(SPECULAR_EXPONENT);
+        }
+    }
+    SurfaceLight { n_dot_l, diffuse, specular, shadow }
+}
+
+// Con Flat y Gouraud la luz viaja en Vertex::light: (difusa, especular, sombra)
+fn packed_light(light: &SurfaceLight) -> Vector3 {
+    Vector3::new(light.diffuse, light.specular, light.shadow)
+}
+
+/// Flat and Gouraud shading: evaluates `light_at` (mesh-space position, world
+/// position, world normal) per triangle or per vertex and stores the packed result
+/// in `Vertex::light`, which the rasterizer interpolates for the fragment shaders
+fn light_faces(triangles: &mut [[Vertex; 3]], shading: ShadingMode, model: &Matrix, light_at: impl Fn(Vector3, Vector3, Vector3) -> Vector3) {
+    let to_world = |local: Vector3| {
+        let world = multiply_matrix_vector4(model, &Vector4::new(local.x, local.y, local.z, 1.0));
+        Vector3::new(world.x, world.y, world.z)
     };
+    match shading {
+        ShadingMode::Phong => {}
+        ShadingMode::Gouraud => {
+            for vertex in triangles.iter_mut().flatten() {
+                vertex.light = light_at(vertex.position, to_world(vertex.position), vertex.transformed_normal);
+            }
+        }
+        ShadingMode::Flat => {
+            for triangle in triangles.iter_mut() {
+                // Centro y normal promedio de la cara: toda ella recibe la misma luz
+                let center = (triangle[0].position + triangle[1].position + triangle[2].position) / 3.0;
+                let normal = triangle[0].transformed_normal + triangle[1].transformed_normal + triangle[2].transformed_normal;
+                let normal = if normal.length() > 0.0 { normal.normalized() } else { triangle[0].transformed_normal };
+                let light = light_at(center, to_world(center), normal);
+                for vertex in triangle.iter_mut() {
+                    vertex.light = light;
+                    vertex.transformed_normal = normal; // el borde atmosférico también queda facetado
+                }
+            }
+        }
+    }
+}
+
+/// Flat and Gouraud shading of a planet's assembled `triangles`: `fragment_shader`
+/// reads the interpolated light instead of lighting each fragment
+pub fn light_triangles(triangles: &mut [[Vertex; 3]], globals: &GlobalUniforms, planet: &PlanetType, model: &Matrix, shadow_map: &ShadowMap) {
+    light_faces(triangles, globals.shading, model, |local, world_pos, normal| {
+        packed_light(&surface_light(local, world_pos, normal, globals, planet, model, shadow_map))
+    });
+}
+
+/// `model` is the planet's model matrix: the fragment position is in the planet's
+/// own (untilted) space, where the surface patterns live, and `model` takes it
+/// to the world for lighting. `normal_to_planet` is the way back for normals,
+/// computed once per draw
+pub fn fragment_shader(fragment: &Fragment, globals: &GlobalUniforms, planet: &PlanetType, model: &Matrix, normal_to_planet: &Matrix, shadow_map: &ShadowMap) -> Vector3 {
+    let local_pos = fragment.world_position;
+    let world = multiply_matrix_vector4(model, &Vector4::new(local_pos.x, local_pos.y, local_pos.z, 1.0));
+    let world_pos = Vector3::new(world.x, world.y, world.z);
+    // Normal interpolada por el rasterizador, ya en el mundo: sirve para cualquier
+    // malla (OBJ, deformada), no solo para una esfera unitaria en el origen
+    let normal = if fragment.normal.length() > 0.0 { fragment.normal.normalized() } else { Vector3::new(0.0, 0.0, 1.0) };
+
+    // Luz por fragmento, o la interpolada desde los vértices (ver light_triangles)
+    let light = match globals.shading {
+        ShadingMode::Phong => surface_light(local_pos, world_pos, normal, globals, planet, model, shadow_map),
+        ShadingMode::Flat | ShadingMode::Gouraud => SurfaceLight {
+            n_dot_l: normal.dot(light_direction(world_pos, &globals.light)),
+            diffuse: fragment.light.x,
+            specular: fragment.light.y,
+            shadow: fragment.light.z,
+        },
+    };
+    let light_intensity = light.diffuse;
+    // Color e intensidad de la luz (temperatura, latido): tiñen todo lo que ilumina
+    let light_color = globals.light.radiance();
+    
+    // Color del tipo de planeta registrado (ver planets.rs)
+    let mut base_color = (planet.color)(&local_pos, globals.time, &planet.params) * light_color;
+    if planet.params.triplanar {
+        base_color *= rock_texture_shade(&local_pos, &normal, normal_to_planet, globals.time, &planet.params);
+    }
     
     // Aplicar iluminación
-    Vector3::new(
+    let mut final_color = Vector3::new(
         base_color.x * light_intensity,
         base_color.y * light_intensity,
         base_color.z * light_intensity
-    )
-}
-
-// Funciones para renderizar anillos y luna (sin cambios)
-pub fn render_rings(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], light: &Light) {
-    let mut ring_uniforms = uniforms.clone();
-    ring_uniforms.render_type = 1;
+    );
     
-    let mut transformed_vertices = Vec::new();
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, &ring_uniforms);
-        transformed_vertices.push(transformed);
+    // Luz propia de la superficie: no depende de la iluminación ni de las sombras
+    if let Some(emission) = planet.emission {
+        final_color += emission(&local_pos, globals.time, &planet.params);
     }
-    
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+
+    // Reflejo especular (Blinn-Phong) en las superficies que lo piden, p. ej. el agua
+    if let Some(gloss) = planet.gloss {
+        let strength = gloss(&local_pos, globals.time, &planet.params);
+        if strength > 0.0 && light.specular > 0.0 {
+            let specular = light.specular * strength * light.shadow;
+            final_color += light_color * specular;
         }
     }
-    
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], light));
+
+    // Brillo atmosférico en el borde del planeta (más fuerte donde la normal es perpendicular a la vista)
+    if planet.params.atmosphere {
+        let view_dir = Vector3::new(
+            globals.camera_position.x - world_pos.x,
+            globals.camera_position.y - world_pos.y,
+            globals.camera_position.z - world_pos.z
+        );
+        let view_length = view_dir.length();
+        if view_length > 0.0 {
+            let facing = (normal.x * view_dir.x + normal.y * view_dir.y + normal.z * view_dir.z) / view_length;
+            let rim = (1.0 - facing.max(0.0)).powf(3.0) * light_intensity;
+            let atmosphere_color = planet.params.color("atmosphere") * light_color;
+            final_color = Vector3::new(
+                final_color.x + atmosphere_color.x * rim,
+                final_color.y + atmosphere_color.y * rim,
+                final_color.z + atmosphere_color.z * rim
+            );
+        }
+    }
+
+    // Auroras sumadas sobre el lado nocturno, alrededor de los polos del eje inclinado
+    if planet.params.aurora && local_pos.length() > 0.0 {
+        final_color += aurora(&local_pos.normalized(), light.n_dot_l, globals.time, &planet.params);
     }
     
-    for fragment in fragments {
-        let ring_color = Vector3::new(0.8, 0.7, 0.6); // Color dorado para anillos
-        framebuffer.point(
-            fragment.position.x as i32,
-            fragment.position.y as i32,
-            ring_color,
-            fragment.depth,
-        );
+    final_color
+}
+
+/// Center and spin axis of a body in the world, from its model matrix
+fn body_frame(model: &Matrix) -> (Vector3, Vector3) {
+    let center = multiply_matrix_vector4(model, &Vector4::new(0.0, 0.0, 0.0, 1.0));
+    let axis = multiply_matrix_vector4(model, &Vector4::new(0.0, 1.0, 0.0, 0.0));
+    (Vector3::new(center.x, center.y, center.z), Vector3::new(axis.x, axis.y, axis.z).normalized())
+}
+
+/// Light reaching a planet point past its rings: the segment toward the light is
+/// intersected with the ring plane (the planet's equator) and dimmed where it
+/// crosses the annulus. `model` is the planet's model matrix.
+pub fn ring_shadow(world_pos: Vector3, light_position: Vector3, model: &Matrix) -> f32 {
+    let (center, axis) = body_frame(model);
+    let to_light = light_position - world_pos;
+    let facing = axis.dot(to_light);
+    if facing.abs() < 1e-6 {
+        return 1.0; // luz rasante, paralela al plano de los anillos
     }
+
+    // Solo cuenta si el plano queda entre el punto y la luz
+    let t = axis.dot(center - world_pos) / facing;
+    if t <= 0.0 || t >= 1.0 {
+        return 1.0;
+    }
+    let radius = (world_pos + to_light * t - center).length();
+    let coverage = smoothstep(RING_INNER_RADIUS - SHADOW_EDGE, RING_INNER_RADIUS + SHADOW_EDGE, radius)
+        * smoothstep(RING_OUTER_RADIUS + SHADOW_EDGE, RING_OUTER_RADIUS - SHADOW_EDGE, radius);
+    1.0 - coverage * (1.0 - RING_SHADOW_LIGHT)
 }
 
-pub fn render_moon(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], light: &Light) {
-    let mut moon_uniforms = uniforms.clone();
-    moon_uniforms.render_type = 2;
-    
-    let mut transformed_vertices = Vec::new();
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, &moon_uniforms);
-        transformed_vertices.push(transformed);
+/// Light reaching a ring point past the planet: the planet casts a cylinder of
+/// shadow away from the light. `model` is the planet's (and rings') model matrix.
+pub fn planet_shadow_on_ring(world_pos: Vector3, light_position: Vector3, model: &Matrix) -> f32 {
+    let (center, _) = body_frame(model);
+    let light_dir = (light_position - center).normalized();
+    let offset = world_pos - center;
+    let along = offset.dot(light_dir);
+    if along >= 0.0 {
+        return 1.0; // del lado iluminado del planeta
     }
-    
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+    let distance = (offset - light_dir * along).length();
+    let lit = smoothstep(SPHERE_RADIUS - SHADOW_EDGE, SPHERE_RADIUS + SHADOW_EDGE, distance);
+    PLANET_SHADOW_LIGHT + (1.0 - PLANET_SHADOW_LIGHT) * lit
+}
+
+/// How a draw call colors its fragments
+pub enum Material<'a> {
+    // Shader procedural del planeta con luz, sombras y atmósfera; durante un cambio de
+    // planeta también se evalúa el anterior y se mezcla con el peso que le queda.
+    // normal_to_planet se calcula una vez por dibujo (ver world_to_model_normal_matrix)
+    Planet { planet: &'a PlanetType, model: Matrix, normal_to_planet: Matrix, fade_from: Option<(&'a PlanetType, f32)> },
+    Flat(Vector3),          // color fijo sin iluminación
+    Moon { model: Matrix, shader: MoonShader }, // superficie de una luna, iluminada (ver moon_shader)
+    Ring { model: Matrix },   // color de los anillos, oscurecido en la sombra del planeta
+    Star { center: Vector3 }, // superficie emisiva del sol (ver sun.rs)
+}
+
+impl<'a> Material<'a> {
+    /// Relief the vertex shader should apply for this material at `time`, if any
+    pub fn displacement(&self, time: f32) -> Option<Displacement<'a>> {
+        match self {
+            // El relieve del planeta nuevo crece a medida que se va el anterior
+            Material::Planet { planet, fade_from, .. } if planet.params.displacement != 0.0 => planet.height.map(|height| Displacement {
+                height,
+                params: &planet.params,
+                scale: planet.params.displacement * (1.0 - fade_from.map_or(0.0, |(_, remaining)| remaining)),
+                time,
+            }),
+            _ => None,
         }
     }
-    
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], light));
+
+    /// Flat and Gouraud shading: lights the assembled triangles of lit materials
+    /// ahead of rasterization (see `light_faces`); unlit and emissive ones have
+    /// nothing to light
+    pub fn light_vertices(&self, triangles: &mut [[Vertex; 3]], globals: &GlobalUniforms, shadow_map: &ShadowMap) {
+        match self {
+            Material::Planet { planet, model, .. } => light_triangles(triangles, globals, planet, model, shadow_map),
+            Material::Moon { model, shader } => light_faces(triangles, globals.shading, model, |local, world_pos, normal| {
+                packed_light(&moon_light(local, world_pos, normal, globals, *shader, shadow_map))
+            }),
+            // Los anillos no dependen de la normal: solo de la sombra del planeta
+            Material::Ring { model } => light_faces(triangles, globals.shading, model, |_, world_pos, _| {
+                let shadow = planet_shadow_on_ring(world_pos, globals.light.position, model);
+                Vector3::new(shadow, 0.0, shadow)
+            }),
+            Material::Flat(_) | Material::Star { .. } => {}
+        }
     }
-    
-    for fragment in fragments {
-        let moon_color = Vector3::new(0.9, 0.9, 0.8); // Color gris claro para la luna
-        framebuffer.point(
-            fragment.position.x as i32,
-            fragment.position.y as i32,
-            moon_color,
-            fragment.depth,
-        );
+
+    pub fn shade(&self, fragment: &Fragment, globals: &GlobalUniforms, shadow_map: &ShadowMap) -> Vector3 {
+        match self {
+            Material::Planet { planet, model, normal_to_planet, fade_from } => {
+                let color = fragment_shader(fragment, globals, planet, model, normal_to_planet, shadow_map);
+                match fade_from {
+                    Some((from, remaining)) => color.lerp(fragment_shader(fragment, globals, from, model, normal_to_planet, shadow_map), *remaining),
+                    None => color,
+                }
+            }
+            Material::Flat(color) => globals.color_space.decode(*color),
+            Material::Moon { model, shader } => moon_shader(fragment, globals, model, *shader, shadow_map),
+            Material::Ring { model } => {
+                let local = fragment.world_position;
+                let world = multiply_matrix_vector4(model, &Vector4::new(local.x, local.y, local.z, 1.0));
+                let shadow = match globals.shading {
+                    ShadingMode::Phong => planet_shadow_on_ring(Vector3::new(world.x, world.y, world.z), globals.light.position, model),
+                    ShadingMode::Flat | ShadingMode::Gouraud => fragment.light.z,
+                };
+                globals.color_space.decode(RING_COLOR) * globals.light.radiance() * shadow
+            }
+            Material::Star { center } => star_color(fragment, globals, *center),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Orientation of the planet: its spin axis tilted `axial_tilt` radians away from
+/// world Y, with the tilt direction turning around Y at `precession_speed`. The
+/// rings use it too, so they stay on the equator.
+pub fn planet_model_matrix(time: f32, params: &PlanetParams) -> Matrix {
+    let tilt = create_model_matrix(Vector3::zero(), 1.0, Vector3::new(params.axial_tilt, 0.0, 0.0));
+    let precession = create_model_matrix(Vector3::zero(), 1.0, Vector3::new(0.0, time * params.precession_speed, 0.0));
+    tilt * precession
+}
+
+/// Where the planet's surface was `dt` seconds ago, as a model matrix: its
+/// orientation back then, plus the spin the color functions apply with
+/// `rotate_planet_position`, so the surface pattern gets motion vectors too
+pub fn previous_planet_model_matrix(time: f32, dt: f32, params: &PlanetParams) -> Matrix {
+    // rotate_planet_position(p, a) es un giro de -a alrededor de Y en create_model_matrix
+    let spin = create_model_matrix(Vector3::zero(), 1.0, Vector3::new(0.0, -params.rotation_speed * dt, 0.0));
+    spin * planet_model_matrix(time - dt, params)
+}
@@ -0,0 +1,155 @@
+// shadow.rs
+// Shadow mapping: se rasteriza la profundidad de la escena vista desde la luz y
+// cada fragmento compara su distancia a la luz con la guardada en el mapa
+use crate::math::*;
+use crate::light::Light;
+use crate::matrix::{create_projection_matrix, create_view_matrix, multiply_matrix_vector4};
+use crate::vertex::Vertex;
+
+pub const SHADOW_MAP_SIZE: usize = 512;
+
+// Sesgo de profundidad (unidades de mundo) para evitar que una superficie se sombree a sí misma;
+// crece en ángulos rasantes, donde la profundidad cambia más dentro de un mismo texel
+const BIAS: f32 = 0.02;
+const SLOPE_BIAS: f32 = 0.08;
+
+pub struct ShadowMap {
+    size: usize,
+    depth: Vec<f32>, // z en NDC de la luz, INFINITY donde no hay nada
+    view_projection: Matrix,
+    near: f32,
+    far: f32,
+}
+
+impl ShadowMap {
+    /// Empty shadow map for a point light at `light.position` looking at the
+    /// origin, with a frustum that fits a sphere of `scene_radius` around it
+    pub fn new(size: usize, light: &Light, scene_radius: f32) -> Self {
+        let mut shadow_map = ShadowMap {
+            size,
+            depth: vec![f32::INFINITY; size * size],
+            view_projection: Matrix::identity(),
+            near: 0.0,
+            far: 0.0,
+        };
+        shadow_map.reset(light, scene_radius);
+        shadow_map
+    }
+
+    /// Clears the map and aims it at a (possibly moved) light, keeping the depth buffer's memory
+    pub fn reset(&mut self, light: &Light, scene_radius: f32) {
+        let distance = light.position.length();
+        let near = (distance - scene_radius).max(0.1);
+        let far = distance + scene_radius;
+        let fov = if distance > scene_radius {
+            2.0 * (scene_radius / distance).asin()
+        } else {
+            std::f32::consts::PI * 0.9 // luz dentro de la escena: cubrir lo posible
+        };
+
+        // Evitar un "up" paralelo a la dirección de la luz
+        let up = if distance > 0.0 && (light.position.y / distance).abs() > 0.99 {
+            Vector3::new(0.0, 0.0, 1.0)
+        } else {
+            Vector3::new(0.0, 1.0, 0.0)
+        };
+        let view = create_view_matrix(light.position, Vector3::zero(), up);
+        let projection = create_projection_matrix(fov, 1.0, near, far);
+
+        self.depth.fill(f32::INFINITY);
+        self.view_projection = view * projection; // en raylib a * b aplica primero a (vista, luego proyección)
+        self.near = near;
+        self.far = far;
+    }
+
+    /// Light-space position: map texel coordinates in x/y, NDC depth in z.
+    /// Returns None for points behind the light.
+    fn project(&self, world: Vector3) -> Option<Vector3> {
+        let clip = multiply_matrix_vector4(&self.view_projection, &Vector4::new(world.x, world.y, world.z, 1.0));
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let half = self.size as f32 / 2.0;
+        Some(Vector3::new(
+            (clip.x / clip.w + 1.0) * half,
+            (1.0 - clip.y / clip.w) * half,
+            clip.z / clip.w,
+        ))
+    }
+
+    /// NDC depth back to distance along the light's view axis
+    fn linear_depth(&self, ndc_z: f32) -> f32 {
+        2.0 * self.far * self.near / (self.far + self.near - ndc_z * (self.far - self.near))
+    }
+
+    /// Adds a mesh (three vertices per triangle) as a shadow caster. `to_world`
+    /// maps each vertex position to world space, like the vertex shader does.
+    pub fn render<F: Fn(Vector3) -> Vector3>(&mut self, vertices: &[Vertex], to_world: F) {
+        for tri in vertices.chunks_exact(3) {
+            let projected = [
+                self.project(to_world(tri[0].position)),
+                self.project(to_world(tri[1].position)),
+                self.project(to_world(tri[2].position)),
+            ];
+            if let [Some(a), Some(b), Some(c)] = projected {
+                self.rasterize_depth(a, b, c);
+            }
+        }
+    }
+
+    fn rasterize_depth(&mut self, a: Vector3, b: Vector3, c: Vector3) {
+        let area = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+        if area.abs() < 1e-10 {
+            return;
+        }
+
+        let max = self.size as f32 - 1.0;
+        let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as usize;
+        let max_x = a.x.max(b.x).max(c.x).ceil().min(max) as usize;
+        let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as usize;
+        let max_y = a.y.max(b.y).max(c.y).ceil().min(max) as usize;
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let w1 = ((b.x - px) * (c.y - py) - (c.x - px) * (b.y - py)) / area;
+                let w2 = ((c.x - px) * (a.y - py) - (a.x - px) * (c.y - py)) / area;
+                let w3 = 1.0 - w1 - w2;
+                if w1 < 0.0 || w2 < 0.0 || w3 < 0.0 {
+                    continue;
+                }
+
+                let depth = w1 * a.z + w2 * b.z + w3 * c.z;
+                let index = y * self.size + x;
+                if depth < self.depth[index] {
+                    self.depth[index] = depth;
+                }
+            }
+        }
+    }
+
+    /// Fraction of light reaching `world_pos` (0 = fully shadowed, 1 = lit),
+    /// averaged over a 3x3 block of texels to soften the shadow edge.
+    /// `n_dot_l` is the cosine between the surface normal and the light direction.
+    pub fn visibility(&self, world_pos: Vector3, n_dot_l: f32) -> f32 {
+        let Some(point) = self.project(world_pos) else {
+            return 1.0;
+        };
+        let depth = self.linear_depth(point.z);
+        let bias = BIAS + SLOPE_BIAS * (1.0 - n_dot_l.clamp(0.0, 1.0));
+
+        let (cx, cy) = (point.x.floor() as i64, point.y.floor() as i64);
+        let mut lit = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (x, y) = (cx + dx, cy + dy);
+                let inside = x >= 0 && y >= 0 && (x as usize) < self.size && (y as usize) < self.size;
+                let occluder = if inside { self.depth[y as usize * self.size + x as usize] } else { f32::INFINITY };
+                if occluder == f32::INFINITY || depth - bias <= self.linear_depth(occluder) {
+                    lit += 1;
+                }
+            }
+        }
+        lit as f32 / 9.0
+    }
+}
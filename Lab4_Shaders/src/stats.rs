@@ -0,0 +1,28 @@
+// stats.rs
+// Contadores del pipeline para el HUD; se reinician en cada frame
+use crate::framebuffer::DepthTest;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct RenderStats {
+    pub bodies_culled: usize,     // cuerpos enteros fuera del frustum, sin pasar por el pipeline
+    pub vertices: usize,          // vértices procesados por el vertex shader
+    pub triangles: usize,         // triángulos ensamblados
+    pub triangles_culled: usize,  // descartados: fuera de pantalla o degenerados
+    pub triangles_clipped: usize, // parcialmente fuera de pantalla
+    pub fragments: usize,         // generados por el rasterizador
+    pub fragments_shaded: usize,  // pasaron el early-Z y llegaron al fragment shader
+    pub fragments_written: usize, // pasaron el depth test
+    pub depth_rejected: usize,    // fallaron el depth test
+}
+
+impl RenderStats {
+    /// Counts the outcome of one framebuffer write
+    pub fn record_fragment(&mut self, result: DepthTest) {
+        match result {
+            DepthTest::Passed => self.fragments_written += 1,
+            DepthTest::Rejected => self.depth_rejected += 1,
+            DepthTest::OutOfBounds => {}
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// sun.rs
+// El sol: una esfera emisiva en la posición de la luz (así la iluminación de la
+// escena sale de donde se ve el sol), con granulación animada, oscurecimiento
+// hacia el borde y una corona aditiva alrededor.
+use crate::math::*;
+use std::f32::consts::PI;
+use crate::framebuffer::Framebuffer;
+use crate::fragment::Fragment;
+use crate::light::Light;
+use crate::matrix::create_model_matrix;
+use crate::noise::fractal_noise;
+use crate::uniforms::GlobalUniforms;
+
+// Escala de la malla de esfera (radio 0.5) para el sol
+pub const SUN_SCALE: f32 = 0.8;
+
+// Color lineal del sol; mayor que 1 para que el tone mapping y el bloom lo traten como una fuente de luz
+const SUN_HOT: Vector3 = Vector3::new(4.0, 3.4, 2.2);
+const SUN_COOL: Vector3 = Vector3::new(2.6, 1.3, 0.4);
+const CORONA_COLOR: Vector3 = Vector3::new(1.2, 0.8, 0.35);
+
+const LIMB_DARKENING: f32 = 0.6; // coeficiente de la ley lineal I(μ) = 1 - u (1 - μ)
+const CORONA_EXTENT: f32 = 3.0;  // radio exterior de la corona, en radios del sol
+
+pub fn sun_model_matrix(light: &Light) -> Matrix {
+    create_model_matrix(light.position, SUN_SCALE, Vector3::zero())
+}
+
+/// Emissive surface color of the sun; `center` is its position in the world
+pub fn star_color(fragment: &Fragment, globals: &GlobalUniforms, center: Vector3) -> Vector3 {
+    // La posición del fragmento está en el espacio de la malla, centrada en el origen
+    let local = fragment.world_position;
+    let normal = if local.length() > 0.0 { local.normalized() } else { Vector3::new(0.0, 0.0, 1.0) };
+
+    // Granulación: celdas de convección que hierven lentamente
+    let t = globals.time * 0.4;
+    let cells = fractal_noise(&Vector3::new(normal.x * 24.0 + t, normal.y * 24.0 - t, normal.z * 24.0 + t * 0.5), 3);
+    let granulation = (cells * 0.5 + 0.5).clamp(0.0, 1.0);
+    let surface = SUN_COOL.lerp(SUN_HOT, 0.55 + 0.45 * granulation);
+
+    // Oscurecimiento del limbo: el borde se ve más frío y oscuro que el centro; usa
+    // la normal interpolada, que ya está en el mundo
+    let surface_normal = if fragment.normal.length() > 0.0 { fragment.normal.normalized() } else { normal };
+    let point = center + surface_normal * (0.5 * SUN_SCALE);
+    let to_camera = globals.camera_position - point;
+    let mu = if to_camera.length() > 0.0 { surface_normal.dot(to_camera.normalized()).max(0.0) } else { 1.0 };
+    // El sol se ve del color de la luz que da, y late con ella
+    surface * globals.light.radiance() * (1.0 - LIMB_DARKENING * (1.0 - mu))
+}
+
+/// Adds the corona: a halo billboard facing the camera around the sun's disc,
+/// with slowly turning streamers. Surfaces in front of the sun hide it.
+pub fn draw_corona(framebuffer: &mut Framebuffer, globals: &GlobalUniforms, center: Vector3) {
+    let Some(screen_center) = globals.project(center) else {
+        return;
+    };
+
+    // Radio del disco en píxeles: proyectar un punto desplazado a lo largo del "derecha" de la cámara
+    let view = &globals.view_matrix;
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let Some(screen_edge) = globals.project(center + right * (0.5 * SUN_SCALE)) else {
+        return;
+    };
+    let radius = (screen_edge.x - screen_center.x).hypot(screen_edge.y - screen_center.y);
+    if radius < 0.5 {
+        return;
+    }
+
+    let extent = radius * CORONA_EXTENT;
+    let min_x = (screen_center.x - extent).floor().max(0.0) as i32;
+    let max_x = (screen_center.x + extent).ceil().min(framebuffer.width as f32 - 1.0) as i32;
+    let min_y = (screen_center.y - extent).floor().max(0.0) as i32;
+    let max_y = (screen_center.y + extent).ceil().min(framebuffer.height as f32 - 1.0) as i32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = x as f32 + 0.5 - screen_center.x;
+            let dy = y as f32 + 0.5 - screen_center.y;
+            let distance = dx.hypot(dy) / radius;
+            if !(1.0..CORONA_EXTENT).contains(&distance) {
+                continue;
+            }
+
+            // Cae con la distancia y llega a cero justo en el borde exterior
+            let fade = (CORONA_EXTENT - distance) / (CORONA_EXTENT - 1.0);
+            let falloff = fade * fade / (distance * distance);
+            let angle = dy.atan2(dx);
+            let streamers = 0.75 + 0.25 * (angle * 7.0 + globals.time * 0.3).sin() * (angle * 3.0 - globals.time * 0.2 + PI).cos();
+
+            framebuffer.add_glow(x, y, CORONA_COLOR * (falloff * streamers), screen_center.z);
+        }
+    }
+}
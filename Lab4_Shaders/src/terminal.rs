@@ -0,0 +1,205 @@
+// terminal.rs
+// Backend de terminal: cada carácter es el medio bloque "▀" con el color de arriba
+// como texto y el de abajo como fondo, así una celda muestra dos píxeles casi
+// cuadrados. La escena se renderiza al doble de resolución y se promedia a celdas.
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use raylib::prelude::*;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::cli::Options;
+use crate::comet::Comet;
+use crate::display::Presenter;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::mesh::SceneMeshes;
+use crate::planets::PlanetType;
+use crate::time::TimeController;
+use crate::cli::create_post_process;
+use crate::renderer::{FrameSettings, Renderer, Scene, FOV_Y};
+
+// Píxeles renderizados por píxel de terminal en cada eje (suaviza los bordes al promediar)
+const TERMINAL_SUPERSAMPLE: i32 = 2;
+const STATUS_LINES: u16 = 1;
+const HALF_BLOCK: char = '▀';
+const ORBIT_STEP: f32 = 0.1; // radianes por tecla
+const ZOOM_STEP: f32 = 0.25;
+
+/// Box-filters `pixels` (`width` x `height`) down to `columns` x `rows`; each
+/// target pixel averages the source pixels its area covers
+pub fn downsample(pixels: &[Color], width: i32, height: i32, columns: i32, rows: i32) -> Vec<Color> {
+    let mut output = Vec::with_capacity((columns * rows).max(0) as usize);
+    for row in 0..rows {
+        let (y0, y1) = (row * height / rows, ((row + 1) * height / rows).max(row * height / rows + 1));
+        for column in 0..columns {
+            let (x0, x1) = (column * width / columns, ((column + 1) * width / columns).max(column * width / columns + 1));
+            let (mut r, mut g, mut b, mut count) = (0u32, 0u32, 0u32, 0u32);
+            for y in y0..y1.min(height) {
+                for x in x0..x1.min(width) {
+                    let color = pixels[(y * width + x) as usize];
+                    r += color.r as u32;
+                    g += color.g as u32;
+                    b += color.b as u32;
+                    count += 1;
+                }
+            }
+            let count = count.max(1);
+            output.push(Color::new((r / count) as u8, (g / count) as u8, (b / count) as u8, 255));
+        }
+    }
+    output
+}
+
+/// Writes `cells` (`columns` x `2 * rows` pixels, row by row) as `rows` lines of
+/// half blocks, changing colors only when they differ from the previous cell
+pub fn write_cells(out: &mut impl Write, cells: &[Color], columns: u16, rows: u16) -> io::Result<()> {
+    let rgb = |color: Color| TermColor::Rgb { r: color.r, g: color.g, b: color.b };
+    let at = |x: u16, y: u16| cells[y as usize * columns as usize + x as usize];
+    for row in 0..rows {
+        queue!(out, MoveTo(0, row))?;
+        let mut current = None;
+        for column in 0..columns {
+            let colors = (at(column, row * 2), at(column, row * 2 + 1));
+            if current != Some(colors) {
+                queue!(out, SetForegroundColor(rgb(colors.0)), SetBackgroundColor(rgb(colors.1)))?;
+                current = Some(colors);
+            }
+            queue!(out, Print(HALF_BLOCK))?;
+        }
+    }
+    queue!(out, ResetColor)
+}
+
+/// The terminal as a presentation backend: raw mode and the alternate screen
+/// while it lives, restored when dropped
+pub struct TerminalPresenter {
+    out: io::Stdout,
+}
+
+impl TerminalPresenter {
+    pub fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut out = io::stdout();
+        execute!(out, EnterAlternateScreen, Hide)?;
+        Ok(TerminalPresenter { out })
+    }
+
+    /// Character cells available for the image (the last lines hold the status)
+    fn cells(&self) -> (u16, u16) {
+        let (columns, rows) = terminal::size().unwrap_or((80, 24));
+        (columns.max(1), rows.saturating_sub(STATUS_LINES).max(1))
+    }
+}
+
+impl Presenter for TerminalPresenter {
+    fn size(&self) -> (i32, i32) {
+        let (columns, rows) = self.cells();
+        (columns as i32 * TERMINAL_SUPERSAMPLE, rows as i32 * 2 * TERMINAL_SUPERSAMPLE)
+    }
+
+    fn present(&mut self, framebuffer: &mut Framebuffer, status: &[String]) -> io::Result<()> {
+        let (columns, rows) = self.cells();
+        let cells = downsample(&framebuffer.pixels(), framebuffer.width, framebuffer.height, columns as i32, rows as i32 * 2);
+        write_cells(&mut self.out, &cells, columns, rows)?;
+        for (i, line) in status.iter().take(STATUS_LINES as usize).enumerate() {
+            let line: String = line.chars().take(columns as usize).collect();
+            queue!(self.out, MoveTo(0, rows + i as u16), terminal::Clear(terminal::ClearType::CurrentLine), Print(line))?;
+        }
+        self.out.flush()
+    }
+}
+
+impl Drop for TerminalPresenter {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Interactive loop on the terminal: 1-9 pick a planet, arrows orbit, + and -
+/// zoom, space pauses and q, Esc or Ctrl+C quit
+pub fn run(options: &Options, camera: &Camera, light: &Light, meshes: &SceneMeshes, planets: &[PlanetType], planet: usize) -> io::Result<()> {
+    let mut presenter = TerminalPresenter::new()?;
+    let mut camera = Camera::new(camera.eye, camera.target, camera.up);
+    let mut planet = planet;
+    let (width, height) = presenter.size();
+    let mut renderer = Renderer::new(width, height, options.ssaa, options.display);
+    renderer.post_process = create_post_process(options);
+    let mut comet = Comet::new();
+    let mut belt = AsteroidBelt::new(7);
+    let mut clock = TimeController::default();
+    let frame_time = (options.target_fps > 0).then(|| Duration::from_secs_f32(1.0 / options.target_fps as f32));
+    let mut last_frame = Instant::now();
+
+    loop {
+        // Entrada: se vacía la cola de eventos sin bloquear
+        while event::poll(Duration::ZERO)? {
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Char(digit @ '1'..='9') => {
+                    let index = digit as usize - '1' as usize;
+                    if index < planets.len() {
+                        planet = index;
+                    }
+                }
+                KeyCode::Left => camera.yaw -= ORBIT_STEP,
+                KeyCode::Right => camera.yaw += ORBIT_STEP,
+                KeyCode::Up => camera.pitch += ORBIT_STEP,
+                KeyCode::Down => camera.pitch -= ORBIT_STEP,
+                KeyCode::Char('+') => camera.distance = (camera.distance - ZOOM_STEP).max(0.8),
+                KeyCode::Char('-') => camera.distance += ZOOM_STEP,
+                KeyCode::Char(' ') => clock.toggle_pause(),
+                _ => {}
+            }
+        }
+        camera.set_orbit(camera.target, camera.distance);
+
+        // La terminal cambió de tamaño: framebuffers nuevos, como al redimensionar la ventana
+        let (width, height) = presenter.size();
+        renderer.resize(width, height);
+
+        let real_dt = last_frame.elapsed().as_secs_f32();
+        last_frame = Instant::now();
+        let dt = clock.advance(real_dt);
+        let time = clock.time;
+        let light = &light.at(time);
+        comet.update(time, dt.abs(), light);
+        belt.update(time);
+
+        let frame = FrameSettings {
+            time,
+            dt,
+            debug_view: options.debug_view,
+            render_mode: options.render_mode,
+            shading: options.shading,
+            projection: options.projection,
+            depth_mode: options.depth_mode,
+            depth_overlay: options.depth_overlay,
+            gizmos: options.gizmos,
+            fov_y: FOV_Y,
+            lens_flare: options.lens_flare,
+            msaa: options.msaa,
+            ..FrameSettings::default()
+        };
+        let scene = Scene { meshes, planet: &planets[planet], comet: &comet, belt: &belt, fade_from: None };
+        renderer.render(&camera, light, &scene, &frame);
+
+        let fps = if real_dt > 0.0 { 1.0 / real_dt } else { 0.0 };
+        let status = format!("{} | {:.0} fps | {} | 1-9 planeta, flechas orbitar, +/- zoom, espacio pausa, q salir", planets[planet].name, fps, clock.label());
+        presenter.present(&mut renderer.framebuffer, &[status])?;
+
+        if let Some(frame_time) = frame_time {
+            std::thread::sleep(frame_time.saturating_sub(last_frame.elapsed()));
+        }
+    }
+}
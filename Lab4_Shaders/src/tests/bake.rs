@@ -0,0 +1,36 @@
+// tests/bake.rs
+use crate::bake::{bake, texel_direction};
+use crate::planets::PlanetRegistry;
+
+#[test]
+fn texel_rows_go_from_north_to_south_pole() {
+    let (width, height) = (64, 32);
+    let top = texel_direction(10, 0, width, height);
+    let bottom = texel_direction(10, height - 1, width, height);
+    assert!(top.y > 0.99 && bottom.y < -0.99);
+
+    // La fila del medio cae sobre el ecuador y toda dirección es unitaria
+    let equator = texel_direction(0, height / 2, width, height);
+    assert!(equator.y.abs() < 0.1);
+    for (x, y) in [(0, 0), (31, 7), (63, 31)] {
+        assert!((texel_direction(x, y, width, height).length() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn texture_wraps_around_in_longitude() {
+    // Primera y última columna quedan a medio texel del meridiano 0, a cada lado
+    let (width, height) = (128, 64);
+    let first = texel_direction(0, 20, width, height);
+    let last = texel_direction(width - 1, 20, width, height);
+    assert!((first - last).length() < 0.1);
+    assert!(first.z > 0.0 && last.z < 0.0);
+}
+
+#[test]
+fn baked_texture_is_twice_as_wide_as_tall() {
+    let registry = PlanetRegistry::with_builtins();
+    let planet = registry.get(0).unwrap();
+    let texture = bake(planet.color, planet, 32);
+    assert_eq!((texture.width, texture.height), (32, 16));
+}
@@ -0,0 +1,40 @@
+// tests/camera.rs
+use crate::camera::{framing_distance, Camera, CameraFocus, FOCUS_DURATION};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+fn assert_close(a: Vector3, b: Vector3) {
+    assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn focus_eases_onto_the_body_and_frames_it() {
+    let mut camera = Camera::new(Vector3::new(0.0, 0.4, 2.2), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let (yaw, pitch) = (camera.yaw, camera.pitch);
+    let center = Vector3::new(3.0, 0.5, 0.0);
+    let mut focus = CameraFocus::start(&camera);
+
+    // La curva es simétrica: a mitad de tiempo, mitad de camino
+    focus.update(&mut camera, center, 0.15, FOCUS_DURATION / 2.0, PI / 3.0);
+    assert_close(camera.target, center * 0.5);
+    assert!(!focus.finished());
+
+    focus.update(&mut camera, center, 0.15, FOCUS_DURATION, PI / 3.0);
+    assert!(focus.finished());
+    assert_close(camera.target, center);
+    assert!((camera.distance - framing_distance(0.15, PI / 3.0)).abs() < 1e-5);
+    assert!(((camera.eye - center).length() - camera.distance).abs() < 1e-4);
+    assert_eq!((camera.yaw, camera.pitch), (yaw, pitch));
+}
+
+#[test]
+fn finished_focus_follows_a_moving_body_but_keeps_the_zoom() {
+    let mut camera = Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let mut focus = CameraFocus::start(&camera);
+    focus.update(&mut camera, Vector3::new(1.0, 0.0, 0.0), 0.5, FOCUS_DURATION, PI / 3.0);
+
+    camera.distance = 4.0;
+    focus.update(&mut camera, Vector3::new(2.0, 0.0, 0.0), 0.5, 0.1, PI / 3.0);
+    assert_close(camera.target, Vector3::new(2.0, 0.0, 0.0));
+    assert_eq!(camera.distance, 4.0);
+}
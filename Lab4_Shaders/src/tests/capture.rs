@@ -0,0 +1,51 @@
+// tests/capture.rs
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::planets::PlanetRegistry;
+use crate::tonemap::DisplayTransform;
+use crate::triangle::TriangleStatus;
+use crate::{FrameSettings, Renderer, Scene};
+use raylib::prelude::*;
+
+#[test]
+fn capture_accounts_for_every_triangle_and_fragment() {
+    // Cámara cerca del planeta: hay triángulos recortados y otros fuera de pantalla
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 0.8), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let frame = FrameSettings { time: 0.5, ..FrameSettings::default() };
+
+    let mut renderer = Renderer::new(64, 48, 1, DisplayTransform::default());
+    let stats = renderer.render(&camera, &light, &scene, &frame);
+    let pixels = renderer.framebuffer.pixels();
+    let capture = renderer.capture_frame(&camera, &light, &scene, &frame);
+    // Capturar no cambia la imagen
+    assert_eq!(renderer.framebuffer.pixels(), pixels);
+    assert_eq!(capture.stats, stats);
+    assert_eq!((capture.width, capture.height), (64, 48));
+
+    let triangles = || capture.draws.iter().flat_map(|draw| &draw.triangles);
+    assert_eq!(triangles().count(), stats.triangles);
+    assert_eq!(triangles().filter(|triangle| triangle.status.culled()).count(), stats.triangles_culled);
+    assert_eq!(triangles().filter(|triangle| triangle.status == TriangleStatus::Clipped).count(), stats.triangles_clipped);
+    assert!(stats.triangles_clipped > 0);
+    assert_eq!(triangles().map(|triangle| triangle.fragments).sum::<usize>(), stats.fragments);
+    assert_eq!(triangles().map(|triangle| triangle.written).sum::<usize>(), stats.fragments_written);
+    assert_eq!(capture.draws.iter().filter(|draw| draw.culled).count(), stats.bodies_culled);
+    assert!(triangles().all(|triangle| !triangle.status.culled() || triangle.fragments == 0));
+
+    let json: serde_json::Value = serde_json::from_str(&capture.to_json()).unwrap();
+    assert_eq!(json["draws"][0]["mesh_id"], 0);
+    assert!(json["draws"][0]["triangles"][0]["status"].is_string());
+    assert_eq!(json["stats"]["fragments"], stats.fragments);
+
+    // Después de la captura los frames vuelven a ser normales
+    renderer.render(&camera, &light, &scene, &frame);
+    assert_eq!(renderer.framebuffer.pixels(), pixels);
+}
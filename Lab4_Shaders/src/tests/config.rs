@@ -0,0 +1,131 @@
+// tests/config.rs
+use crate::config::Config;
+use crate::moons::MoonShader;
+use crate::planets::PlanetRegistry;
+use crate::shaders::VertexEffect;
+use std::path::Path;
+
+#[test]
+fn builtin_presets_resolve_against_builtin_shaders() {
+    let config = Config::builtin();
+    let planets = config.planet_types(&PlanetRegistry::with_builtins()).unwrap();
+
+    let names: Vec<&str> = planets.iter().map(|planet| planet.name.as_str()).collect();
+    assert_eq!(names, ["rocky", "gas_giant", "rainbow", "glitter", "heart", "earth", "ice"]);
+    assert!(planets[3].params.rings);
+    assert!(planets[5].params.aurora && !planets[0].params.aurora);
+    assert_eq!(planets[1].params.band_frequency, 4.0);
+    assert!(planets[1].params.asteroids && !planets[3].params.asteroids);
+}
+
+#[test]
+fn preset_overrides_shader_defaults() {
+    let text = r#"
+        [light]
+        position = [0.0, 1.0, 0.0]
+
+        [[planet]]
+        name = "ice_rocky"
+        shader = "rocky"
+        rotation_speed = 2.0
+        effect = "wobble"
+
+        [planet.colors]
+        base = [0.1, 0.2, 0.3]
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+    let planets = config.planet_types(&PlanetRegistry::with_builtins()).unwrap();
+
+    assert_eq!(planets[0].name, "ice_rocky");
+    assert_eq!(planets[0].params.rotation_speed, 2.0);
+    assert_eq!(planets[0].params.noise_scale, 1.0);
+    assert_eq!(planets[0].params.effect, VertexEffect::Wobble);
+    assert_eq!(planets[0].params.color("base").z, 0.3);
+    assert_eq!(config.light_position().y, 1.0);
+    // Shaders without a preset are still selectable after the presets
+    assert_eq!(planets.len(), 7);
+    assert_eq!(planets[1].name, "gas_giant");
+}
+
+#[test]
+fn presets_list_their_moons() {
+    let text = r#"
+        [light]
+        position = [0.0, 1.0, 0.0]
+
+        [[planet]]
+        name = "two_moons"
+        shader = "gas_giant"
+
+        [[planet.moons]]
+        name = "a"
+        shader = "icy"
+        distance = 2.0
+
+        [[planet.moons]]
+        name = "b"
+        shader = "captured"
+        period = -20.0
+
+        [[planet]]
+        name = "moonless"
+        shader = "rocky"
+        moons = []
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+    let planets = config.planet_types(&PlanetRegistry::with_builtins()).unwrap();
+
+    let moons = &planets[0].params.moons;
+    assert_eq!(moons.iter().map(|moon| moon.shader).collect::<Vec<_>>(), [MoonShader::Icy, MoonShader::Captured]);
+    assert_eq!((moons[0].distance, moons[1].period), (2.0, -20.0));
+    assert!(planets[1].params.moons.is_empty());
+    // Sin [[planet.moons]] queda la luna de siempre
+    assert_eq!(planets[2].params.moons.len(), 1);
+
+    let bad = text.replace("\"captured\"", "\"glass\"");
+    let error = Config::parse(&bad, Path::new("test.toml")).unwrap().planet_types(&PlanetRegistry::with_builtins()).map(|_| ()).unwrap_err();
+    assert!(error.contains("moon 'b'"), "{}", error);
+}
+
+#[test]
+fn json_presets_are_supported() {
+    let text = r#"{
+        "light": { "position": [5.0, 5.0, 5.0] },
+        "planet": [{ "name": "bands", "shader": "gas_giant", "band_frequency": 8.0 }]
+    }"#;
+    let config = Config::parse(text, Path::new("planets.json")).unwrap();
+
+    assert_eq!(config.planets[0].band_frequency, Some(8.0));
+}
+
+#[test]
+fn unknown_shader_is_an_error() {
+    let text = r#"
+        [light]
+        position = [5.0, 5.0, 5.0]
+
+        [[planet]]
+        name = "oops"
+        shader = "does_not_exist"
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+
+    assert!(config.planet_types(&PlanetRegistry::with_builtins()).is_err());
+}
+
+#[test]
+fn unknown_vertex_effect_is_an_error() {
+    let text = r#"
+        [light]
+        position = [5.0, 5.0, 5.0]
+
+        [[planet]]
+        name = "oops"
+        shader = "rocky"
+        effect = "spin"
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+
+    let error = config.planet_types(&PlanetRegistry::with_builtins()).err().unwrap();
+    assert!(error.contains("oops") && error.contains("spin"), "{}", error);
+}
@@ -0,0 +1,49 @@
+// tests/crossfade.rs
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::planets::{PlanetCrossfade, PlanetRegistry, CROSSFADE_DURATION};
+use crate::tonemap::DisplayTransform;
+use crate::{FrameSettings, Renderer, Scene};
+use raylib::prelude::*;
+
+#[test]
+fn crossfade_runs_from_the_old_planet_to_the_new_one() {
+    let mut fade = PlanetCrossfade::start(3);
+    assert_eq!((fade.from, fade.remaining()), (3, 1.0));
+    fade.advance(CROSSFADE_DURATION / 2.0);
+    assert!((fade.remaining() - 0.5).abs() < 1e-6);
+    assert!(!fade.finished());
+    fade.advance(-1.0); // el tiempo hacia atrás no lo deshace
+    assert!((fade.remaining() - 0.5).abs() < 1e-6);
+    fade.advance(CROSSFADE_DURATION);
+    assert!(fade.finished());
+    assert_eq!(fade.remaining(), 0.0);
+}
+
+#[test]
+fn fading_planet_blends_both_surfaces() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 2.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    let registry = PlanetRegistry::with_builtins();
+    let (old, new) = (registry.get(2).unwrap().clone(), registry.get(3).unwrap().clone());
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+
+    let mut renderer = Renderer::new(64, 48, 1, DisplayTransform::default());
+    let mut render = |fade_from| {
+        let scene = Scene { meshes: &meshes, planet: &new, comet: &comet, belt: &belt, fade_from };
+        renderer.render(&camera, &light, &scene, &FrameSettings::default());
+        renderer.framebuffer.pixels()
+    };
+    let plain = render(None);
+    let done = render(Some((&old, 0.0)));
+    let halfway = render(Some((&old, 0.5)));
+    let start = render(Some((&old, 1.0)));
+
+    assert_eq!(plain, done);
+    assert_ne!(halfway, plain);
+    assert_ne!(halfway, start);
+}
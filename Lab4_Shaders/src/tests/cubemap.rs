@@ -0,0 +1,58 @@
+// tests/cubemap.rs
+use crate::cubemap::{face_texel, panorama_direction, FACES};
+use crate::matrix::{create_projection_matrix, create_viewport_matrix};
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const SIZE: i32 = 64;
+
+/// Where the face's own 90 degree camera draws the point `origin + direction`
+fn rendered_pixel(face: usize, origin: Vector3, direction: Vector3) -> Option<(i32, i32)> {
+    let camera = FACES[face].camera(origin);
+    let globals = GlobalUniforms {
+        view_matrix: camera.get_view_matrix(),
+        projection_matrix: create_projection_matrix(PI / 2.0, 1.0, 0.1, 100.0),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, SIZE as f32, SIZE as f32),
+        camera_position: origin,
+        ..test_globals()
+    };
+    globals.project(origin + direction * 5.0).map(|screen| (screen.x.floor() as i32, screen.y.floor() as i32))
+}
+
+#[test]
+fn each_axis_lands_in_the_center_of_its_face() {
+    for (index, face) in FACES.iter().enumerate() {
+        assert_eq!(face_texel(face.forward, SIZE), (index, SIZE / 2, SIZE / 2), "{}", face.name);
+    }
+}
+
+#[test]
+fn cube_lookup_matches_what_each_face_camera_renders() {
+    let origin = Vector3::new(1.0, -2.0, 3.0);
+    let directions = [
+        Vector3::new(1.0, 0.3, -0.2),
+        Vector3::new(-0.4, 1.0, 0.7),
+        Vector3::new(0.2, -1.0, -0.6),
+        Vector3::new(0.5, 0.5, 1.0),
+        Vector3::new(-0.9, -0.3, -1.0),
+        Vector3::new(-1.0, 0.8, 0.1),
+    ];
+    for direction in directions {
+        let direction = direction.normalized();
+        let (face, x, y) = face_texel(direction, SIZE);
+        let (rendered_x, rendered_y) = rendered_pixel(face, origin, direction).expect("point behind the face camera");
+        assert!((x - rendered_x).abs() <= 1 && (y - rendered_y).abs() <= 1, "{:?}: lookup ({}, {}), rendered ({}, {})", direction, x, y, rendered_x, rendered_y);
+    }
+}
+
+#[test]
+fn panorama_centers_on_minus_z_with_x_to_the_right() {
+    let (width, height) = (256, 128);
+    let center = panorama_direction(width / 2, height / 2, width, height);
+    assert!((center - Vector3::new(0.0, 0.0, -1.0)).length() < 0.05);
+    let right = panorama_direction(width * 3 / 4, height / 2, width, height);
+    assert!((right - Vector3::new(1.0, 0.0, 0.0)).length() < 0.05);
+    assert!(panorama_direction(10, 0, width, height).y > 0.99);
+}
@@ -0,0 +1,56 @@
+// tests/debug.rs
+use crate::debug::DebugView;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use raylib::prelude::*;
+
+fn fragment() -> Fragment {
+    Fragment::new(0.5, 0.5, Vector3::zero(), 0.5, Vector3::zero())
+}
+
+#[test]
+fn cycling_visits_every_view_and_returns_to_shaded() {
+    let mut view = DebugView::Shaded;
+    let mut seen = Vec::new();
+    for _ in 0..6 {
+        seen.push(view);
+        view = view.next();
+    }
+    assert_eq!(view, DebugView::Shaded);
+    for name in ["shaded", "depth", "normals", "wireframe", "uv", "ids"] {
+        assert!(seen.contains(&name.parse().unwrap()), "{} not reached by next()", name);
+    }
+}
+
+#[test]
+fn normals_map_to_rgb_and_shaded_defers_to_the_shader() {
+    let mut fragment = fragment();
+    fragment.normal = Vector3::new(0.0, 1.0, -1.0);
+    assert_eq!(DebugView::Normals.shade(&fragment, 0), Some(Vector3::new(0.5, 1.0, 0.0)));
+    assert_eq!(DebugView::Shaded.shade(&fragment, 0), None);
+}
+
+#[test]
+fn wireframe_lights_up_only_near_edges() {
+    let mut fragment = fragment();
+    fragment.barycentric = Vector3::new(0.01, 0.49, 0.5);
+    let edge = DebugView::Wireframe.shade(&fragment, 0).unwrap();
+    fragment.barycentric = Vector3::new(0.33, 0.33, 0.34);
+    let interior = DebugView::Wireframe.shade(&fragment, 0).unwrap();
+    assert!(edge.x > interior.x);
+}
+
+#[test]
+fn depth_view_is_brightest_at_the_nearest_fragment() {
+    let mut framebuffer = Framebuffer::new(3, 1);
+    framebuffer.clear();
+    framebuffer.point(0, 0, Vector3::one(), 0.1);
+    framebuffer.point(1, 0, Vector3::one(), 0.9);
+    framebuffer.resolve_depth();
+
+    let near = framebuffer.get_pixel_color(0, 0).unwrap();
+    let far = framebuffer.get_pixel_color(1, 0).unwrap();
+    assert_eq!(near.r, 255);
+    assert!(far.r < near.r && far.r > 0);
+    assert_eq!(framebuffer.get_pixel_color(2, 0), Some(Color::BLACK));
+}
@@ -0,0 +1,38 @@
+// tests/demo.rs
+use crate::camera::Camera;
+use crate::demo::{Demo, DemoFrame};
+use raylib::prelude::*;
+
+#[test]
+fn demo_text_round_trips_exactly() {
+    let camera = Camera::new(Vector3::new(0.1, 0.4, 2.2), Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    let demo = Demo {
+        frames: vec![
+            DemoFrame::new(1.0 / 3.0, 1.0 / 60.0, &camera, 0),
+            DemoFrame::new(0.1 + 0.2, -0.016_666_668, &camera, 4),
+        ],
+    };
+
+    assert_eq!(Demo::parse(&demo.to_text()), Ok(demo));
+}
+
+#[test]
+fn replayed_camera_matches_the_recorded_pose() {
+    let camera = Camera::new(Vector3::new(1.5, 0.7, -2.0), Vector3::new(0.2, 0.0, 0.1), Vector3::new(0.0, 1.0, 0.0));
+    let replayed = DemoFrame::new(0.0, 0.0, &camera, 0).camera();
+
+    assert_eq!(replayed.eye, camera.eye);
+    assert_eq!(replayed.get_view_matrix(), camera.get_view_matrix());
+    assert!((replayed.yaw - camera.yaw).abs() < 1e-6);
+}
+
+#[test]
+fn demo_parse_skips_comments_and_reports_bad_lines() {
+    let text = "# comentario\n\n0.5 0.1 0 0 2 0 0 0 0 1 0 2\n";
+    let demo = Demo::parse(text).unwrap();
+    assert_eq!(demo.frames.len(), 1);
+    assert_eq!(demo.frames[0].planet, 2);
+
+    let error = Demo::parse("0.5 0.1 0 0 2\n").unwrap_err();
+    assert!(error.contains("line 1"), "{}", error);
+}
@@ -0,0 +1,104 @@
+// tests/depth.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::depth::{depth_resolution, DepthMode};
+use crate::light::Light;
+use crate::matrix::{create_projection_matrix, create_reverse_orthographic_matrix, create_reverse_projection_matrix, multiply_matrix_vector4, view_depth};
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::planets::PlanetRegistry;
+use crate::tonemap::DisplayTransform;
+use crate::{create_framebuffers, frame_projection, render_scene, FrameSettings, Renderer, Scene};
+use raylib::prelude::*;
+
+fn ndc_depth(projection: &Matrix, distance: f32) -> f32 {
+    let clip = multiply_matrix_vector4(projection, &Vector4::new(0.3, -0.2, -distance, 1.0));
+    clip.z / clip.w
+}
+
+#[test]
+fn reverse_z_maps_near_to_one_and_far_towards_zero() {
+    let perspective = create_reverse_projection_matrix(1.0, 1.5, 0.1);
+    assert!((ndc_depth(&perspective, 0.1) - 1.0).abs() < 1e-6);
+    // Sin plano lejano: la profundidad baja hacia 0 sin llegar nunca
+    let far = ndc_depth(&perspective, 1e6);
+    assert!(far > 0.0 && far < 1e-6);
+
+    let orthographic = create_reverse_orthographic_matrix(3.0, 2.0, 0.1, 100.0);
+    assert!((ndc_depth(&orthographic, 0.1) - 1.0).abs() < 1e-6);
+    assert!(ndc_depth(&orthographic, 100.0).abs() < 1e-6);
+
+    for projection in [perspective, orthographic] {
+        for distance in [0.5, 3.0, 42.0] {
+            let recovered = view_depth(ndc_depth(&projection, distance), &projection);
+            assert!((recovered - distance).abs() < distance * 1e-3, "{} != {}", recovered, distance);
+        }
+    }
+}
+
+#[test]
+fn reverse_z_keeps_far_surfaces_apart() {
+    let standard = create_projection_matrix(1.0, 1.0, 0.1, 100.0);
+    let reverse = create_reverse_projection_matrix(1.0, 1.0, 0.1);
+
+    // Lejos de la cámara la estándar pierde órdenes de magnitud de resolución
+    assert!(depth_resolution(90.0, &reverse) * 50.0 < depth_resolution(90.0, &standard));
+    assert!(depth_resolution(90.0, &reverse) / 90.0 < 1e-6);
+
+    assert!(DepthMode::ReverseZ.nearer(0.5, 0.2) && DepthMode::Standard.nearer(0.2, 0.5));
+    assert!(DepthMode::ReverseZ.nearer(0.0, DepthMode::ReverseZ.cleared()));
+    assert_eq!("reverse-z".parse::<DepthMode>(), Ok(DepthMode::ReverseZ));
+}
+
+#[test]
+fn both_depth_modes_render_the_same_surfaces() {
+    let camera = Camera::new(Vector3::new(0.0, 1.0, 4.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    let planet = PlanetRegistry::with_builtins().get(1).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+
+    let render = |depth_mode| {
+        let (mut framebuffer, _) = create_framebuffers(96, 64, 1, DisplayTransform::default());
+        let frame = FrameSettings { depth_mode, ..FrameSettings::default() };
+        render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut FrameArena::default());
+        let projection = frame_projection(&camera, &frame, 96.0, 64.0);
+        // Distancia a la cámara de lo que se ve en cada píxel (infinito en el fondo)
+        let distances: Vec<f32> = framebuffer.depth().iter()
+            .map(|&depth| if depth.is_finite() { view_depth(depth, &projection) } else { f32::INFINITY })
+            .collect();
+        (framebuffer.pixels(), distances)
+    };
+    let (standard, standard_distances) = render(DepthMode::Standard);
+    let (reverse, reverse_distances) = render(DepthMode::ReverseZ);
+
+    for (a, b) in standard_distances.iter().zip(&reverse_distances) {
+        assert!(a == b || (a - b).abs() < a * 1e-3, "{} != {}", a, b);
+    }
+    let differing = standard.iter().zip(&reverse).filter(|(a, b)| a != b).count();
+    assert!(differing * 100 < standard.len(), "{} pixels changed", differing);
+}
+
+#[test]
+fn depth_overlay_draws_a_panel_in_the_corner() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(1)));
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+
+    let mut renderer = Renderer::new(320, 200, 1, DisplayTransform::default());
+    renderer.render(&camera, &light, &scene, &FrameSettings::default());
+    let plain = renderer.framebuffer.pixels();
+    let frame = FrameSettings { depth_overlay: true, ..FrameSettings::default() };
+    renderer.render(&camera, &light, &scene, &frame);
+    let with_panel = renderer.framebuffer.pixels();
+
+    // Solo cambia la esquina inferior derecha
+    let changed: Vec<usize> = (0..plain.len()).filter(|&i| plain[i] != with_panel[i]).collect();
+    assert!(!changed.is_empty());
+    assert!(changed.iter().all(|&i| i % 320 >= 100 && i / 320 >= 100));
+}
@@ -0,0 +1,81 @@
+// tests/displacement.rs
+use crate::planets::{PlanetParams, PlanetRegistry, PlanetType};
+use crate::shaders::{Displacement, Material, VertexEffect};
+use raylib::prelude::*;
+
+fn constant_height(_: &Vector3, _: f32, _: &PlanetParams) -> f32 {
+    1.0
+}
+
+// Sube hacia +X: sobre el polo norte, la superficie queda inclinada hacia -X
+fn ramp_height(pos: &Vector3, _: f32, _: &PlanetParams) -> f32 {
+    pos.x
+}
+
+#[test]
+fn flat_relief_moves_along_the_normal_and_keeps_it() {
+    let params = PlanetParams::default();
+    let displacement = Displacement { height: constant_height, params: &params, scale: 0.1, time: 0.0 };
+    let (position, normal) = displacement.apply(Vector3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+    assert!((position - Vector3::new(0.0, 0.6, 0.0)).length() < 1e-6);
+    assert!((normal - Vector3::new(0.0, 1.0, 0.0)).length() < 1e-4);
+}
+
+#[test]
+fn sloped_relief_tilts_the_normal_away_from_the_slope() {
+    let params = PlanetParams::default();
+    let displacement = Displacement { height: ramp_height, params: &params, scale: 1.0, time: 0.0 };
+    let (_, normal) = displacement.apply(Vector3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+    // Pendiente 1: la normal queda a 45° entre +Y y -X
+    let expected = Vector3::new(-1.0, 1.0, 0.0).normalized();
+    assert!((normal - expected).length() < 1e-3, "{:?}", normal);
+}
+
+#[test]
+fn only_planets_with_relief_and_a_scale_are_displaced() {
+    let registry = PlanetRegistry::with_builtins();
+    let mut rocky = registry.get(registry.find("rocky").unwrap()).unwrap().clone();
+    let gas_giant = registry.get(registry.find("gas_giant").unwrap()).unwrap().clone();
+
+    // Sin displacement en los parámetros la esfera queda lisa aunque el shader tenga relieve
+    assert!(Material::Planet { planet: &rocky, model: Matrix::identity(), normal_to_planet: Matrix::identity(), fade_from: None }.displacement(0.0).is_none());
+    rocky.params.displacement = 0.03;
+    assert!(Material::Planet { planet: &rocky, model: Matrix::identity(), normal_to_planet: Matrix::identity(), fade_from: None }.displacement(0.0).is_some());
+    let smooth = PlanetParams { displacement: 0.03, ..gas_giant.params.clone() };
+    let gas_giant = PlanetType { params: smooth, ..gas_giant };
+    assert!(Material::Planet { planet: &gas_giant, model: Matrix::identity(), normal_to_planet: Matrix::identity(), fade_from: None }.displacement(0.0).is_none());
+    assert!(Material::Flat(Vector3::one()).displacement(0.0).is_none());
+}
+
+#[test]
+fn pulse_scales_the_body_and_keeps_sphere_normals_radial() {
+    let direction = Vector3::new(1.0, 2.0, -2.0) / 3.0;
+    // En t tal que sin(3t) = 1 el cuerpo está en su tamaño máximo
+    let time = std::f32::consts::FRAC_PI_2 / 3.0;
+    let (position, normal) = VertexEffect::Pulse.apply(direction * 0.5, direction, time);
+
+    assert!((position.length() - 0.5 * 1.08).abs() < 1e-4);
+    assert!((normal - direction).length() < 1e-3, "{:?}", normal);
+}
+
+#[test]
+fn twist_turns_the_hemispheres_opposite_ways_and_keeps_the_equator() {
+    let time = std::f32::consts::FRAC_PI_2 / 1.5;
+    let north = VertexEffect::Twist.transform_point(Vector3::new(0.3, 0.4, 0.0), time);
+    let south = VertexEffect::Twist.transform_point(Vector3::new(0.3, -0.4, 0.0), time);
+    let equator = VertexEffect::Twist.transform_point(Vector3::new(0.5, 0.0, 0.0), time);
+
+    assert!(north.z * south.z < 0.0, "{:?} {:?}", north, south);
+    assert!((north.length() - 0.5).abs() < 1e-5);
+    assert!((equator - Vector3::new(0.5, 0.0, 0.0)).length() < 1e-6);
+}
+
+#[test]
+fn effects_parse_from_their_names() {
+    for effect in VertexEffect::ALL {
+        assert_eq!(effect.name().parse::<VertexEffect>(), Ok(effect));
+    }
+    assert!("spin".parse::<VertexEffect>().is_err());
+}
@@ -0,0 +1,34 @@
+// tests/events.rs
+use crate::events::{occults, Event, EventDetector};
+use crate::light::Light;
+use crate::moons::Moon;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+#[test]
+fn occultation_needs_the_near_body_in_front_and_overlapping() {
+    let viewer = Vector3::new(10.0, 0.0, 0.0);
+    let far = Vector3::zero();
+
+    assert!(occults(viewer, Vector3::new(3.0, 0.4, 0.0), 0.15, far, 0.5));
+    assert!(!occults(viewer, Vector3::new(3.0, 1.5, 0.0), 0.15, far, 0.5));
+    // Detrás del cuerpo lejano no lo tapa
+    assert!(!occults(viewer, Vector3::new(-3.0, 0.0, 0.0), 0.15, far, 0.5));
+}
+
+#[test]
+fn detector_reports_events_once_when_they_start() {
+    // Sol y cámara del lado +X: a tiempo 0 la luna está en (3, 0, 0), entre ellos y el planeta
+    let light = Light::new(Vector3::new(10.0, 0.0, 0.0));
+    let camera = Vector3::new(6.0, 0.0, 0.0);
+    let moons = [Moon::default()];
+    let mut detector = EventDetector::default();
+
+    assert_eq!(detector.update(0.0, &light, camera, &moons), vec![Event::Eclipse, Event::Transit]);
+    assert_eq!(detector.update(0.01, &light, camera, &moons), vec![]);
+    assert_eq!(detector.active(), &[Event::Eclipse, Event::Transit]);
+
+    // Media órbita después la luna queda detrás del planeta
+    assert_eq!(detector.update(2.0 * PI, &light, camera, &moons), vec![]);
+    assert!(detector.active().is_empty());
+}
@@ -0,0 +1,57 @@
+// tests/flare.rs
+use crate::flare::{ghost_positions, sun_visibility};
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const SIZE: i32 = 100;
+
+fn globals(eye: Vector3, target: Vector3) -> GlobalUniforms {
+    GlobalUniforms {
+        view_matrix: create_view_matrix(eye, target, Vector3::new(0.0, 1.0, 0.0)),
+        projection_matrix: create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, SIZE as f32, SIZE as f32),
+        camera_position: eye,
+        ..test_globals()
+    }
+}
+
+#[test]
+fn sun_is_fully_visible_over_an_empty_sky() {
+    let framebuffer = Framebuffer::new(SIZE, SIZE);
+    let globals = globals(Vector3::new(0.0, 0.0, 10.0), Vector3::zero());
+    assert_eq!(sun_visibility(&framebuffer, &globals, Vector3::zero(), 0.4), 1.0);
+}
+
+#[test]
+fn sun_behind_an_occluder_or_the_camera_is_hidden() {
+    let globals = globals(Vector3::new(0.0, 0.0, 10.0), Vector3::zero());
+
+    // Algo dibujado en toda la pantalla mucho más cerca que el sol lo tapa por completo
+    let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            framebuffer.point(x, y, Vector3::one(), -0.5);
+        }
+    }
+    assert_eq!(sun_visibility(&framebuffer, &globals, Vector3::zero(), 0.4), 0.0);
+
+    // Detrás de la cámara no hay destello
+    let empty = Framebuffer::new(SIZE, SIZE);
+    assert_eq!(sun_visibility(&empty, &globals, Vector3::new(0.0, 0.0, 20.0), 0.4), 0.0);
+}
+
+#[test]
+fn ghosts_line_up_with_the_sun_and_the_screen_center() {
+    let sun = Vector2::new(20.0, 30.0);
+    let center = Vector2::new(SIZE as f32 / 2.0, SIZE as f32 / 2.0);
+    let axis = (center - sun) / (center - sun).length();
+    for ghost in ghost_positions(sun, SIZE, SIZE) {
+        let offset = ghost - sun;
+        // Producto cruz 2D nulo: el fantasma está sobre la recta sol-centro
+        assert!((offset.x * axis.y - offset.y * axis.x).abs() < 1e-3, "{:?}", ghost);
+    }
+}
@@ -0,0 +1,79 @@
+// tests/frustum.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::framebuffer::Framebuffer;
+use crate::frustum::Frustum;
+use crate::light::Light;
+use crate::matrix::{create_orthographic_matrix, create_projection_matrix, create_view_matrix};
+use crate::mesh::{generate_icosphere, generate_nucleus, generate_ring, LodMesh, SceneMeshes};
+use crate::planets::PlanetRegistry;
+use crate::{render_scene, FrameSettings, Scene};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+/// Camera at z = 5 looking at the origin, 60 degree field of view
+fn frustum(projection: Matrix) -> Frustum {
+    let view = create_view_matrix(Vector3::new(0.0, 0.0, 5.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    Frustum::from_matrix(&(view * projection))
+}
+
+#[test]
+fn spheres_in_front_are_kept_and_behind_are_culled() {
+    let frustum = frustum(create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0));
+
+    assert!(frustum.contains_sphere(Vector3::zero(), 0.5));
+    assert!(!frustum.contains_sphere(Vector3::new(0.0, 0.0, 8.0), 0.5)); // detrás de la cámara
+    assert!(!frustum.contains_sphere(Vector3::new(0.0, 0.0, -200.0), 0.5)); // más allá del plano lejano
+    // A 5 unidades, la mitad de la vista mide 5 * tan(30°) ≈ 2.89
+    assert!(!frustum.contains_sphere(Vector3::new(4.0, 0.0, 0.0), 0.5));
+    assert!(!frustum.contains_sphere(Vector3::new(0.0, -4.0, 0.0), 0.5));
+}
+
+#[test]
+fn spheres_crossing_a_plane_are_kept() {
+    let frustum = frustum(create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0));
+
+    // El centro está fuera por la derecha, pero el borde de la esfera entra en la vista
+    assert!(frustum.contains_sphere(Vector3::new(3.2, 0.0, 0.0), 0.5));
+    assert!(!frustum.contains_sphere(Vector3::new(3.2, 0.0, 0.0), 0.1));
+    // Una esfera alrededor de la cámara siempre se dibuja
+    assert!(frustum.contains_sphere(Vector3::new(0.0, 0.0, 5.0), 1.0));
+}
+
+#[test]
+fn orthographic_volume_is_a_box() {
+    let frustum = frustum(create_orthographic_matrix(2.0, 2.0, 0.1, 100.0));
+
+    assert!(frustum.contains_sphere(Vector3::new(1.8, 0.0, -50.0), 0.1));
+    assert!(!frustum.contains_sphere(Vector3::new(2.5, 0.0, 0.0), 0.1));
+    assert!(!frustum.contains_sphere(Vector3::new(0.0, 2.5, -50.0), 0.1));
+}
+
+#[test]
+fn bodies_out_of_view_skip_the_pipeline() {
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes {
+        sphere: LodMesh::single(generate_icosphere(2)),
+        ring: generate_ring(0.8, 1.5, 48),
+        nucleus: generate_nucleus(1, 0.35),
+        asteroid: generate_nucleus(1, 0.45),
+    };
+    let planet = PlanetRegistry::with_builtins().get(1).unwrap().clone(); // gigante gaseoso, con asteroides
+    let belt = AsteroidBelt::new(7);
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &Comet::new(), belt: &belt, fade_from: None };
+    let mut framebuffer = Framebuffer::new(64, 48);
+    let frame = FrameSettings { time: 1.0, dt: 0.1, ..FrameSettings::default() };
+
+    // Mirando hacia el planeta se dibuja algo; de espaldas a todo, ningún vértice se transforma
+    let toward = Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let stats = render_scene(&mut framebuffer, &toward, &light, &scene, &frame, &mut FrameArena::default());
+    assert!(stats.vertices > 0);
+
+    let away = Camera::new(Vector3::new(0.0, -30.0, 0.0), Vector3::new(0.0, -60.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let stats = render_scene(&mut framebuffer, &away, &light, &scene, &frame, &mut FrameArena::default());
+    assert_eq!(stats.vertices, 0);
+    // Planeta, luna, cinturón, cometa y sol
+    assert_eq!(stats.bodies_culled, 5);
+}
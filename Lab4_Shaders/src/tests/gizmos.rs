@@ -0,0 +1,78 @@
+// tests/gizmos.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::gizmos::Gizmos;
+use crate::light::Light;
+use crate::matrix::{create_viewport_matrix, multiply_matrix_vector4};
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::picking::Body;
+use crate::planets::PlanetRegistry;
+use crate::shaders::planet_model_matrix;
+use crate::tonemap::DisplayTransform;
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use crate::{create_framebuffers, frame_projection, render_scene, FrameSettings, Scene};
+use raylib::prelude::*;
+
+const WIDTH: i32 = 240;
+const HEIGHT: i32 = 180;
+
+#[test]
+fn gizmos_toggle_one_body_at_a_time() {
+    let mut gizmos = Gizmos::NONE;
+    gizmos.toggle(Body::Moon(1));
+    assert!(gizmos.shows(Body::Moon(1)) && !gizmos.shows(Body::Moon(0)) && !gizmos.shows(Body::Planet));
+    gizmos.toggle(Body::Moon(1));
+    assert!(!gizmos.any());
+    assert!(Gizmos::ALL.shows(Body::Comet) && Gizmos::ALL.shows(Body::Moon(5)));
+}
+
+#[test]
+fn gizmos_trace_the_moon_orbit_and_the_tilted_axis() {
+    // Vista desde +X: la inclinación del eje (alrededor de X) se ve de costado
+    let camera = Camera::new(Vector3::new(6.0, 0.0, 0.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(1)));
+    let planet = PlanetRegistry::with_builtins().get(1).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+
+    let render = |gizmos| {
+        let (mut framebuffer, _) = create_framebuffers(WIDTH, HEIGHT, 1, DisplayTransform::default());
+        let frame = FrameSettings { gizmos, ..FrameSettings::default() };
+        render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut FrameArena::default());
+        framebuffer.pixels()
+    };
+    let plain = render(Gizmos::NONE);
+    let changed_near = |gizmos, world: Vector3| {
+        let globals = GlobalUniforms {
+            view_matrix: camera.get_view_matrix(),
+            projection_matrix: frame_projection(&camera, &FrameSettings::default(), WIDTH as f32, HEIGHT as f32),
+            viewport_matrix: create_viewport_matrix(0.0, 0.0, WIDTH as f32, HEIGHT as f32),
+            camera_position: camera.eye,
+            light,
+            ..test_globals()
+        };
+        let screen = globals.project(world).unwrap();
+        let pixels = render(gizmos);
+        (-2..=2).any(|dy| (-2..=2).any(|dx| {
+            let index = ((screen.y as i32 + dy) * WIDTH + screen.x as i32 + dx) as usize;
+            pixels[index] != plain[index]
+        }))
+    };
+
+    let mut moon_only = Gizmos::NONE;
+    moon_only.toggle(Body::Moon(0));
+    let moon = &planet.params.moons[0];
+    assert!(changed_near(moon_only, moon.position(moon.period * 0.25)));
+    assert!(changed_near(moon_only, moon.position(moon.period * 0.7)));
+
+    let mut planet_only = Gizmos::NONE;
+    planet_only.toggle(Body::Planet);
+    let axis = multiply_matrix_vector4(&planet_model_matrix(0.0, &planet.params), &Vector4::new(0.0, 1.0, 0.0, 0.0));
+    let tip = Vector3::new(axis.x, axis.y, axis.z).normalized() * 0.8;
+    assert!(changed_near(planet_only, tip));
+    assert!(!changed_near(planet_only, Vector3::new(0.0, 0.8, 0.0)));
+}
@@ -0,0 +1,162 @@
+// tests/golden.rs
+// Regresión por imágenes de referencia: cada tipo de planeta se renderiza con
+// tiempo, cámara y resolución fijos y se compara con el PNG guardado en
+// src/tests/golden/. Si un cambio de shader es intencional, regenerarlas con
+//
+//     GOLDEN_UPDATE=1 cargo test golden
+use crate::arena::FrameArena;
+use crate::camera::Camera;
+use crate::asteroids::AsteroidBelt;
+use crate::comet::Comet;
+use crate::config::Config;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, generate_nucleus, generate_ring, LodMesh, SceneMeshes};
+use crate::planets::{PlanetRegistry, PlanetType};
+use crate::shaders::{RING_INNER_RADIUS, RING_OUTER_RADIUS};
+use crate::tonemap::DisplayTransform;
+use crate::{render_scene, FrameSettings, Scene};
+use raylib::prelude::*;
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+const WIDTH: i32 = 160;
+const HEIGHT: i32 = 120;
+const TIME: f32 = 2.0;
+
+// Tolerancia "perceptual": diferencia de luma por píxel (0-255). Se permiten
+// pequeños cambios de redondeo, pero no que cambie el aspecto
+const PIXEL_THRESHOLD: f32 = 16.0;
+const MAX_DIFFERENT_PIXELS: f32 = 0.005; // fracción de la imagen
+const MAX_MEAN_DIFFERENCE: f32 = 1.0;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/golden").join(format!("{}.png", name))
+}
+
+/// Planet type `planet_index` of the built-in presets
+fn builtin_planet(planet_index: usize) -> PlanetType {
+    Config::builtin().planet_types(&PlanetRegistry::with_builtins()).unwrap().swap_remove(planet_index)
+}
+
+fn render(planet: &PlanetType) -> Vec<Color> {
+    let config = Config::builtin();
+    let camera = Camera::new(Vector3::new(0.0, 0.4, 2.2), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(config.light_position());
+    let meshes = SceneMeshes {
+        sphere: LodMesh::single(generate_icosphere(3)),
+        ring: generate_ring(RING_INNER_RADIUS, RING_OUTER_RADIUS, 96),
+        nucleus: generate_nucleus(2, 0.35),
+        asteroid: generate_nucleus(1, 0.45),
+    };
+
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.set_background_color(Color::new(68, 29, 102, 255));
+    framebuffer.display = DisplayTransform::default();
+    let frame = FrameSettings { time: TIME, dt: 1.0 / 30.0, ..FrameSettings::default() };
+    let mut belt = AsteroidBelt::new(7);
+    belt.update(TIME);
+    let scene = Scene { meshes: &meshes, planet, comet: &Comet::new(), belt: &belt, fade_from: None };
+    render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut FrameArena::default());
+
+    framebuffer.pixels()
+}
+
+fn write_png(path: &PathBuf, pixels: &[Color]) {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path).unwrap()), WIDTH as u32, HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = pixels.iter().flat_map(|color| [color.r, color.g, color.b, color.a]).collect();
+    encoder.write_header().unwrap().write_image_data(&data).unwrap();
+}
+
+fn read_png(path: &PathBuf) -> Option<Vec<Color>> {
+    let decoder = png::Decoder::new(File::open(path).ok()?);
+    let mut reader = decoder.read_info().ok()?;
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).ok()?;
+    assert_eq!((info.width, info.height), (WIDTH as u32, HEIGHT as u32), "{} has the wrong size", path.display());
+    assert_eq!(info.color_type, png::ColorType::Rgba, "{} is not RGBA", path.display());
+    Some(data[..info.buffer_size()].chunks_exact(4).map(|p| Color::new(p[0], p[1], p[2], p[3])).collect())
+}
+
+fn luma_difference(a: Color, b: Color) -> f32 {
+    0.299 * (a.r as f32 - b.r as f32).abs() + 0.587 * (a.g as f32 - b.g as f32).abs() + 0.114 * (a.b as f32 - b.b as f32).abs()
+}
+
+fn check_golden(planet_index: usize) {
+    let planet = builtin_planet(planet_index);
+    check_image(&planet.name, &planet);
+}
+
+/// Compares `planet` rendered with the reference image `name`
+fn check_image(name: &str, planet: &PlanetType) {
+    let actual = render(planet);
+    let path = golden_path(name);
+
+    if env::var_os("GOLDEN_UPDATE").is_some() {
+        write_png(&path, &actual);
+        return;
+    }
+    let expected = read_png(&path)
+        .unwrap_or_else(|| panic!("missing reference {}; run with GOLDEN_UPDATE=1 to create it", path.display()));
+
+    let differences: Vec<f32> = expected.iter().zip(&actual).map(|(a, b)| luma_difference(*a, *b)).collect();
+    let different = differences.iter().filter(|&&difference| difference > PIXEL_THRESHOLD).count();
+    let mean = differences.iter().sum::<f32>() / differences.len() as f32;
+
+    if different as f32 > MAX_DIFFERENT_PIXELS * differences.len() as f32 || mean > MAX_MEAN_DIFFERENCE {
+        // Dejar la imagen obtenida al lado del target para compararlas a mano
+        let actual_path = env::temp_dir().join(format!("{}.actual.png", name));
+        write_png(&actual_path, &actual);
+        panic!(
+            "{} differs from {}: {} pixels over the threshold, mean difference {:.2} (rendered image: {})",
+            name, path.display(), different, mean, actual_path.display()
+        );
+    }
+}
+
+#[test]
+fn golden_rocky() {
+    check_golden(0);
+}
+
+#[test]
+fn golden_gas_giant() {
+    check_golden(1);
+}
+
+#[test]
+fn golden_rainbow() {
+    check_golden(2);
+}
+
+#[test]
+fn golden_glitter() {
+    check_golden(3);
+}
+
+#[test]
+fn golden_heart() {
+    check_golden(4);
+}
+
+#[test]
+fn golden_earth() {
+    check_golden(5);
+}
+
+#[test]
+fn golden_ice() {
+    check_golden(6);
+}
+
+#[test]
+fn golden_rocky_triplanar() {
+    // El relieve del rocoso con la textura de roca proyectada en tres planos
+    let mut planet = builtin_planet(0);
+    planet.params.triplanar = true;
+    check_image("rocky_triplanar", &planet);
+}
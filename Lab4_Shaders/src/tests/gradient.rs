@@ -0,0 +1,79 @@
+// tests/gradient.rs
+use crate::config::Config;
+use crate::gradient::Gradient;
+use crate::planets::PlanetRegistry;
+use crate::tonemap::ColorSpace;
+use raylib::prelude::*;
+use std::path::Path;
+
+const RED: Vector3 = Vector3::new(1.0, 0.0, 0.0);
+const BLUE: Vector3 = Vector3::new(0.0, 0.0, 1.0);
+const WHITE: Vector3 = Vector3::new(1.0, 1.0, 1.0);
+
+#[test]
+fn gradient_interpolates_between_stops_and_stays_flat_outside() {
+    // Las paradas se ordenan aunque lleguen desordenadas
+    let gradient = Gradient::new(vec![(1.0, BLUE), (0.0, RED)]);
+
+    assert_eq!(gradient.sample(-3.0), RED);
+    assert_eq!(gradient.sample(0.25), Vector3::new(0.75, 0.0, 0.25));
+    assert_eq!(gradient.sample(1.0), BLUE);
+    assert_eq!(gradient.sample(7.0), BLUE);
+    assert_eq!(Gradient::default().sample(0.5), Vector3::zero());
+}
+
+#[test]
+fn repeated_stops_make_a_hard_edge() {
+    let gradient = Gradient::new(vec![(0.0, RED), (0.5, RED), (0.5, WHITE), (1.0, BLUE)]);
+
+    assert_eq!(gradient.sample(0.49), RED);
+    assert_eq!(gradient.sample(0.5), WHITE); // en el borde gana la parada de la derecha
+    assert_eq!(gradient.sample(0.75), Vector3::new(0.5, 0.5, 1.0));
+}
+
+#[test]
+fn preset_gradients_resolve_named_colors() {
+    let text = r#"
+        [light]
+        position = [0.0, 1.0, 0.0]
+
+        [[planet]]
+        name = "frozen_rocky"
+        shader = "rocky"
+
+        [planet.colors]
+        ice = [0.9, 0.95, 1.0]
+
+        [planet.gradients]
+        terrain = [{ at = 0.0, color = [0.1, 0.2, 0.4] }, { at = 1.0, color = "ice" }]
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+    let planets = config.planet_types(&PlanetRegistry::with_builtins()).unwrap();
+    let params = &planets[0].params;
+
+    assert_eq!(params.gradient("terrain", 0.0), Vector3::new(0.1, 0.2, 0.4));
+    assert_eq!(params.gradient("terrain", 1.0), Vector3::new(0.9, 0.95, 1.0));
+    assert_eq!(params.gradient("missing", 0.5), Vector3::new(1.0, 0.0, 1.0));
+
+    // La paleta se decodifica a lineal junto con los colores sueltos
+    let linear = planets[0].in_color_space(ColorSpace::Linear);
+    assert_eq!(linear.params.gradient("terrain", 1.0), linear.params.color("ice"));
+}
+
+#[test]
+fn gradient_with_unknown_color_is_an_error() {
+    let text = r#"
+        [light]
+        position = [0.0, 1.0, 0.0]
+
+        [[planet]]
+        name = "rocky"
+        shader = "rocky"
+
+        [planet.gradients]
+        terrain = [{ at = 0.0, color = "lava" }]
+    "#;
+    let config = Config::parse(text, Path::new("test.toml")).unwrap();
+    let Err(message) = config.planet_types(&PlanetRegistry::with_builtins()) else { panic!("unknown color accepted") };
+    assert!(message.contains("unknown color 'lava'"), "{}", message);
+}
@@ -0,0 +1,60 @@
+// tests/light.rs
+use crate::config::Config;
+use crate::light::{color_temperature, Light, LightAnimation, DAYLIGHT_KELVIN};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::path::Path;
+
+#[test]
+fn color_temperature_goes_from_red_to_blue() {
+    let candle = color_temperature(1900.0);
+    let daylight = color_temperature(DAYLIGHT_KELVIN);
+    let blue_star = color_temperature(20000.0);
+
+    assert!(candle.x == 1.0 && candle.z < 0.2, "{:?}", candle);
+    assert!(daylight.x > 0.95 && daylight.y > 0.95 && daylight.z > 0.95, "{:?}", daylight);
+    assert!(blue_star.z == 1.0 && blue_star.x < 0.8, "{:?}", blue_star);
+    for kelvin in [500.0, 1000.0, 4000.0, 6600.0, 12000.0, 50000.0] {
+        let color = color_temperature(kelvin);
+        assert!([color.x, color.y, color.z].iter().all(|channel| (0.0..=1.0).contains(channel)), "{}: {:?}", kelvin, color);
+    }
+}
+
+#[test]
+fn animated_light_orbits_and_pulses() {
+    let animation = LightAnimation { orbit_speed: PI / 2.0, pulse: 0.5, pulse_speed: 0.25, flicker: 0.0 };
+    let light = Light::new(Vector3::new(5.0, 2.0, 0.0)).with_animation(animation);
+
+    // Un segundo: un cuarto de vuelta alrededor de Y, a la misma altura y distancia
+    let later = light.at(1.0);
+    assert!((later.position - Vector3::new(0.0, 2.0, 5.0)).length() < 1e-4, "{:?}", later.position);
+    assert!((later.intensity - 1.5).abs() < 1e-5);
+    assert_eq!(later.animation, LightAnimation::default());
+    assert!((light.at(3.0).intensity - 0.5).abs() < 1e-5);
+
+    // Sin animación la luz no cambia
+    let still = Light::new(Vector3::new(5.0, 2.0, 0.0)).at(7.0);
+    assert_eq!((still.position, still.intensity), (Vector3::new(5.0, 2.0, 0.0), 1.0));
+}
+
+#[test]
+fn light_preset_sets_color_and_animation() {
+    let text = r#"
+        [light]
+        position = [5.0, 0.0, 0.0]
+        temperature = 3000.0
+        intensity = 1.2
+        orbit_speed = 0.1
+        flicker = 0.05
+    "#;
+    let light = Config::parse(text, Path::new("test.toml")).unwrap().light();
+    assert_eq!(light.color, color_temperature(3000.0));
+    assert_eq!(light.intensity, 1.2);
+    assert_eq!(light.animation.orbit_speed, 0.1);
+    assert_eq!(light.animation.pulse, 0.0);
+    assert_eq!(light.animation.flicker, 0.05);
+
+    // La luz que falta en el preset es blanca y quieta
+    let plain = Config::builtin().light();
+    assert_eq!((plain.color, plain.intensity), (Vector3::one(), 1.0));
+}
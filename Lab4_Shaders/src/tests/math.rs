@@ -0,0 +1,69 @@
+// tests/math.rs
+// Los tipos de Rust puro del build web tienen que dar lo mismo que los de raylib
+use crate::math::portable;
+use crate::matrix::{create_model_matrix, create_projection_matrix, create_view_matrix};
+use raylib::prelude::*;
+
+fn portable_matrix(m: Matrix) -> portable::Matrix {
+    portable::Matrix {
+        m0: m.m0, m1: m.m1, m2: m.m2, m3: m.m3,
+        m4: m.m4, m5: m.m5, m6: m.m6, m7: m.m7,
+        m8: m.m8, m9: m.m9, m10: m.m10, m11: m.m11,
+        m12: m.m12, m13: m.m13, m14: m.m14, m15: m.m15,
+    }
+}
+
+fn fields(m: portable::Matrix) -> [f32; 16] {
+    [m.m0, m.m1, m.m2, m.m3, m.m4, m.m5, m.m6, m.m7, m.m8, m.m9, m.m10, m.m11, m.m12, m.m13, m.m14, m.m15]
+}
+
+fn assert_matrix_close(a: portable::Matrix, b: portable::Matrix) {
+    for (x, y) in fields(a).into_iter().zip(fields(b)) {
+        assert!((x - y).abs() < 1e-4 * (1.0 + y.abs()), "{:?} != {:?}", a, b);
+    }
+}
+
+fn model_view() -> Matrix {
+    create_model_matrix(Vector3::new(1.0, -2.0, 0.5), 1.5, Vector3::new(0.3, -0.7, 1.1))
+        * create_view_matrix(Vector3::new(2.0, 1.0, 5.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0))
+}
+
+#[test]
+fn portable_matrix_product_matches_raylib() {
+    let projection = create_projection_matrix(1.0, 1.5, 0.1, 50.0);
+    let expected = portable_matrix(model_view() * projection);
+    assert_matrix_close(portable_matrix(model_view()) * portable_matrix(projection), expected);
+}
+
+#[test]
+fn portable_inverse_undoes_the_matrix() {
+    let matrix = portable_matrix(model_view());
+    assert_matrix_close(matrix * matrix.inverted(), portable::Matrix::identity());
+    assert_matrix_close(matrix.inverted(), portable_matrix(model_view().inverted()));
+}
+
+#[test]
+fn portable_vectors_match_raylib() {
+    let (a, b) = (Vector3::new(1.0, -2.0, 3.0), Vector3::new(-0.5, 4.0, 2.0));
+    let (pa, pb) = (portable::Vector3::new(a.x, a.y, a.z), portable::Vector3::new(b.x, b.y, b.z));
+    let same = |p: portable::Vector3, v: Vector3| (p.x - v.x).abs() < 1e-6 && (p.y - v.y).abs() < 1e-6 && (p.z - v.z).abs() < 1e-6;
+
+    assert!(same(pa.cross(pb), a.cross(b)));
+    assert!(same(pa.normalized(), a.normalized()));
+    assert!(same(pa.lerp(pb, 0.25), a.lerp(b, 0.25)));
+    assert_eq!(pa.dot(pb), a.dot(b));
+    assert_eq!(portable::Vector3::zero().normalized(), portable::Vector3::zero());
+}
+
+#[test]
+fn portable_image_ignores_pixels_outside() {
+    let mut image = portable::Image::gen_image_color(4, 3, portable::Color::BLACK);
+    image.draw_pixel(2, 1, portable::Color::WHITE);
+    image.draw_pixel(4, 0, portable::Color::WHITE);
+    image.draw_pixel(-1, 2, portable::Color::WHITE);
+
+    assert_eq!((image.width(), image.height()), (4, 3));
+    assert_eq!(image.get_color(2, 1), portable::Color::WHITE);
+    assert_eq!(image.get_color(3, 0), portable::Color::BLACK);
+    assert_eq!(image.get_color(0, 2), portable::Color::BLACK);
+}
@@ -0,0 +1,145 @@
+// tests/matrix.rs
+use crate::camera::{Camera, Projection};
+use crate::matrix::{create_model_matrix, create_orthographic_matrix, create_projection_matrix, create_view_matrix, multiply_matrix_vector4, multiply_matrix_vectors4, view_depth, world_to_model_normal_matrix, SimdMatrix};
+use crate::planets::PlanetParams;
+use crate::shaders::planet_model_matrix;
+use raylib::prelude::*;
+
+fn assert_close(a: Vector4, b: Vector4) {
+    let tolerance = 1e-5 * (1.0 + b.x.abs().max(b.y.abs()).max(b.z.abs()).max(b.w.abs()));
+    assert!(
+        (a.x - b.x).abs() < tolerance && (a.y - b.y).abs() < tolerance && (a.z - b.z).abs() < tolerance && (a.w - b.w).abs() < tolerance,
+        "{:?} != {:?}", a, b
+    );
+}
+
+#[test]
+fn simd_transform_matches_scalar() {
+    let matrix = create_model_matrix(Vector3::new(1.0, -2.0, 0.5), 1.5, Vector3::new(0.3, -0.7, 1.1))
+        * create_view_matrix(Vector3::new(2.0, 1.0, 5.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0))
+        * create_projection_matrix(1.0, 1.5, 0.1, 50.0);
+    let vectors: Vec<Vector4> = (0..37)
+        .map(|i| Vector4::new(i as f32 * 0.3 - 5.0, (i as f32).sin(), (i as f32).cos() * 2.0, if i % 5 == 0 { 0.0 } else { 1.0 }))
+        .collect();
+
+    let simd = SimdMatrix::new(&matrix);
+    let mut batch = Vec::new();
+    multiply_matrix_vectors4(&matrix, &vectors, &mut batch);
+
+    assert_eq!(batch.len(), vectors.len());
+    for (vector, batched) in vectors.iter().zip(&batch) {
+        let scalar = multiply_matrix_vector4(&matrix, vector);
+        assert_close(simd.transform(*vector), scalar);
+        assert_close(*batched, scalar);
+    }
+}
+
+#[test]
+fn combined_matrix_matches_chained_products() {
+    // vertex_shader multiplica modelo, vista y proyección una sola vez por draw
+    let model = create_model_matrix(Vector3::new(0.5, 0.0, 0.0), 0.3, Vector3::zero());
+    let view = create_view_matrix(Vector3::new(0.0, 1.0, 4.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let projection = create_projection_matrix(1.0, 1.0, 0.1, 100.0);
+    let point = Vector4::new(0.1, 0.2, 0.3, 1.0);
+
+    let chained = multiply_matrix_vector4(&projection, &multiply_matrix_vector4(&view, &multiply_matrix_vector4(&model, &point)));
+    assert_close(SimdMatrix::new(&(model * view * projection)).transform(point), chained);
+}
+
+#[test]
+fn orthographic_maps_view_box_to_ndc_cube() {
+    let matrix = create_orthographic_matrix(4.0, 3.0, 0.5, 10.0);
+
+    assert_close(multiply_matrix_vector4(&matrix, &Vector4::new(4.0, 3.0, -0.5, 1.0)), Vector4::new(1.0, 1.0, -1.0, 1.0));
+    assert_close(multiply_matrix_vector4(&matrix, &Vector4::new(-4.0, -3.0, -10.0, 1.0)), Vector4::new(-1.0, -1.0, 1.0, 1.0));
+    // Sin perspectiva: el tamaño no depende de la distancia
+    let near = multiply_matrix_vector4(&matrix, &Vector4::new(2.0, 0.0, -1.0, 1.0));
+    let far = multiply_matrix_vector4(&matrix, &Vector4::new(2.0, 0.0, -9.0, 1.0));
+    assert_eq!(near.x, far.x);
+}
+
+#[test]
+fn orthographic_keeps_target_framing() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 8.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let fov_y = std::f32::consts::PI / 3.0;
+
+    // A la distancia del objetivo ambas proyecciones ven un cuerpo del mismo tamaño
+    let perspective = Projection::Perspective.screen_radius(&camera, Vector3::zero(), 0.5, fov_y, 600.0);
+    let orthographic = Projection::Orthographic.screen_radius(&camera, Vector3::zero(), 0.5, fov_y, 600.0);
+    assert!((perspective - orthographic).abs() < 0.5, "{} vs {}", perspective, orthographic);
+
+    // ...pero en ortográfica alejarse del objetivo no lo achica
+    let behind = Projection::Orthographic.screen_radius(&camera, Vector3::new(0.0, 0.0, -5.0), 0.5, fov_y, 600.0);
+    assert_eq!(behind, orthographic);
+}
+
+#[test]
+fn comet_nucleus_spins_in_place() {
+    use crate::comet::{comet_model_matrix, comet_position};
+
+    // El centro de la malla del núcleo tiene que caer en su posición orbital
+    let time = 3.7;
+    let center = multiply_matrix_vector4(&comet_model_matrix(time), &Vector4::new(0.0, 0.0, 0.0, 1.0));
+    assert!((Vector3::new(center.x, center.y, center.z) - comet_position(time)).length() < 1e-4);
+}
+
+#[test]
+fn asteroids_spin_in_place() {
+    use crate::asteroids::AsteroidBelt;
+
+    // Los asteroides quedan cerca del plano del cinturón aunque giren sobre sí mismos
+    let mut belt = AsteroidBelt::new(7);
+    belt.update(3.7);
+    for instance in &belt.instances {
+        let center = multiply_matrix_vector4(instance, &Vector4::new(0.0, 0.0, 0.0, 1.0));
+        assert!(center.y.abs() <= 0.15 + 1e-4, "asteroid off the belt plane: {}", center.y);
+    }
+}
+
+#[test]
+fn planet_axis_keeps_its_tilt_while_precessing() {
+    let params = PlanetParams { axial_tilt: 0.47, precession_speed: 0.3, ..PlanetParams::default() };
+    let pole = Vector4::new(0.0, 1.0, 0.0, 0.0);
+
+    let mut previous: Option<Vector4> = None;
+    for time in [0.0, 1.0, 5.0] {
+        let axis = multiply_matrix_vector4(&planet_model_matrix(time, &params), &pole);
+        // El eje siempre forma el mismo ángulo con la vertical, pero su dirección gira
+        assert!((axis.y - 0.47_f32.cos()).abs() < 1e-5, "{:?}", axis);
+        assert!(((axis.x * axis.x + axis.z * axis.z).sqrt() - 0.47_f32.sin()).abs() < 1e-5);
+        if let Some(previous) = previous {
+            assert!((axis.x - previous.x).abs() + (axis.z - previous.z).abs() > 1e-3);
+        }
+        previous = Some(axis);
+    }
+
+    // Sin inclinación ni precesión el planeta queda como antes
+    assert_close(multiply_matrix_vector4(&planet_model_matrix(3.0, &PlanetParams::default()), &pole), pole);
+}
+
+#[test]
+fn view_depth_undoes_both_projections() {
+    let perspective = create_projection_matrix(1.0, 1.5, 0.1, 100.0);
+    let orthographic = create_orthographic_matrix(3.0, 2.0, 0.1, 100.0);
+    for projection in [perspective, orthographic] {
+        for distance in [0.5, 3.0, 42.0] {
+            let clip = multiply_matrix_vector4(&projection, &Vector4::new(0.2, -0.1, -distance, 1.0));
+            let recovered = view_depth(clip.z / clip.w, &projection);
+            assert!((recovered - distance).abs() < distance * 1e-3, "{} != {}", recovered, distance);
+        }
+    }
+}
+
+#[test]
+fn normal_matrix_brings_world_normals_back_without_translation() {
+    let model = create_model_matrix(Vector3::new(3.0, -1.0, 2.0), 0.6, Vector3::new(0.4, -1.2, 0.9));
+    let to_model = world_to_model_normal_matrix(&model);
+    let normal = Vector3::new(0.2, -0.5, 0.8).normalized();
+
+    // Ida con el modelo (w = 0: sin traslación) y vuelta con la matriz de normales
+    let world = multiply_matrix_vector4(&model, &Vector4::new(normal.x, normal.y, normal.z, 0.0));
+    let back = multiply_matrix_vector4(&to_model, &world);
+    assert!(back.w.abs() < 1e-6);
+    let back = Vector3::new(back.x, back.y, back.z).normalized();
+    assert!((back - normal).length() < 1e-5, "{:?} != {:?}", back, normal);
+}
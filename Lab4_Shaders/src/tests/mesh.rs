@@ -0,0 +1,118 @@
+// tests/mesh.rs
+use crate::mesh::{generate_icosphere, generate_ring, generate_uv_sphere, projected_radius, LodMesh, MeshSpec, SPHERE_RADIUS};
+use raylib::prelude::Vector3;
+use crate::vertex::Vertex;
+
+/// Every vertex lies on the sphere, normals are unit length and point outward,
+/// and every triangle is wound counter-clockwise seen from outside. u may go
+/// slightly past 1 on triangles that wrap around the seam.
+fn assert_valid_sphere(vertices: &[Vertex]) {
+    assert_eq!(vertices.len() % 3, 0);
+
+    for vertex in vertices {
+        assert!((vertex.position.length() - SPHERE_RADIUS).abs() < 1e-4);
+        assert!((vertex.normal.length() - 1.0).abs() < 1e-4);
+        assert!(vertex.normal.dot(vertex.position) > 0.0);
+        assert!(vertex.tex_coords.x >= 0.0 && vertex.tex_coords.x <= 1.25, "u = {}", vertex.tex_coords.x);
+        assert!(vertex.tex_coords.y >= 0.0 && vertex.tex_coords.y <= 1.0 + 1e-6, "v = {}", vertex.tex_coords.y);
+    }
+
+    for tri in vertices.chunks_exact(3) {
+        let edge1 = tri[1].position - tri[0].position;
+        let edge2 = tri[2].position - tri[0].position;
+        let face_normal = edge1.cross(edge2);
+        assert!(face_normal.length() > 0.0, "degenerate triangle");
+        assert!(face_normal.dot(tri[0].position) > 0.0, "triangle wound inward");
+    }
+}
+
+/// Largest u distance between the vertices of one triangle (large values mean a seam wrap)
+fn max_u_span(vertices: &[Vertex]) -> f32 {
+    vertices
+        .chunks_exact(3)
+        .map(|tri| {
+            let us = [tri[0].tex_coords.x, tri[1].tex_coords.x, tri[2].tex_coords.x];
+            us.iter().cloned().fold(f32::MIN, f32::max) - us.iter().cloned().fold(f32::MAX, f32::min)
+        })
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn uv_sphere_triangle_count() {
+    // Los polos usan un solo triángulo por quad
+    let vertices = generate_uv_sphere(16, 8);
+    assert_eq!(vertices.len() / 3, 16 * 2 + 16 * 6 * 2);
+}
+
+#[test]
+fn uv_sphere_is_a_valid_sphere() {
+    let vertices = generate_uv_sphere(24, 12);
+    assert_valid_sphere(&vertices);
+    assert!(max_u_span(&vertices) < 0.1);
+}
+
+#[test]
+fn icosphere_triangle_count_grows_by_four() {
+    for subdivisions in 0..4 {
+        assert_eq!(generate_icosphere(subdivisions).len() / 3, 20 * 4usize.pow(subdivisions));
+    }
+}
+
+#[test]
+fn icosphere_is_a_valid_sphere() {
+    let vertices = generate_icosphere(3);
+    assert_valid_sphere(&vertices);
+    assert!(max_u_span(&vertices) < 0.5);
+}
+
+#[test]
+fn ring_is_a_flat_annulus_facing_up() {
+    let vertices = generate_ring(0.8, 1.5, 32);
+    assert_eq!(vertices.len() / 3, 32 * 2);
+
+    for vertex in &vertices {
+        let radius = vertex.position.length();
+        assert!(vertex.position.y == 0.0);
+        assert!((radius - 0.8).abs() < 1e-4 || (radius - 1.5).abs() < 1e-4);
+        // v marca el borde: 0 adentro, 1 afuera
+        assert_eq!(vertex.tex_coords.y, if radius < 1.0 { 0.0 } else { 1.0 });
+    }
+
+    for tri in vertices.chunks_exact(3) {
+        let face_normal = (tri[1].position - tri[0].position).cross(tri[2].position - tri[0].position);
+        assert!(face_normal.y > 0.0, "triangle wound downward");
+    }
+}
+
+#[test]
+fn mesh_spec_parsing() {
+    assert_eq!("obj".parse::<MeshSpec>(), Ok(MeshSpec::Obj));
+    assert_eq!("uv:32x16".parse::<MeshSpec>(), Ok(MeshSpec::UvSphere { slices: 32, stacks: 16 }));
+    assert_eq!("ico:3".parse::<MeshSpec>(), Ok(MeshSpec::Icosphere { subdivisions: 3 }));
+    assert!("uv:2x16".parse::<MeshSpec>().is_err());
+    assert!("ico:9".parse::<MeshSpec>().is_err());
+    assert!("cube".parse::<MeshSpec>().is_err());
+}
+
+#[test]
+fn lod_picks_finer_levels_for_larger_bodies() {
+    let lod = LodMesh::icospheres();
+    assert_eq!(lod.level_for(0.0), 0);
+    assert_eq!(lod.level_for(11.9), 0);
+    assert_eq!(lod.level_for(12.0), 1);
+    assert_eq!(lod.level_for(500.0), 2);
+    assert_eq!(lod.select(500.0).len(), generate_icosphere(3).len());
+    assert_eq!(lod.select(f32::INFINITY).len(), generate_icosphere(3).len());
+}
+
+#[test]
+fn projected_radius_shrinks_with_distance() {
+    let eye = Vector3::new(0.0, 0.0, 8.0);
+    let fov = std::f32::consts::PI / 3.0;
+    let near = projected_radius(Vector3::new(0.0, 0.0, 4.0), 0.5, eye, fov, 900.0);
+    let far = projected_radius(Vector3::new(0.0, 0.0, 0.0), 0.5, eye, fov, 900.0);
+
+    assert!((near - 2.0 * far).abs() < 1e-3);
+    assert!((far - 0.5 / (8.0 * (fov / 2.0).tan()) * 450.0).abs() < 1e-3);
+    assert_eq!(projected_radius(eye, 0.5, eye, fov, 900.0), f32::INFINITY);
+}
@@ -0,0 +1,63 @@
+// tests/mod.rs
+mod rasterizer;
+mod config;
+mod mesh;
+mod shadow;
+mod postprocess;
+mod tonemap;
+mod debug;
+mod tiles;
+mod matrix;
+mod golden;
+mod noise;
+mod particles;
+mod time;
+mod demo;
+mod picking;
+mod camera;
+mod events;
+mod bake;
+mod cubemap;
+mod motion;
+mod flare;
+mod displacement;
+mod frustum;
+mod resize;
+mod terminal;
+mod math;
+mod renderer;
+mod planet_shaders;
+mod gradient;
+mod shading;
+mod depth;
+mod moon;
+mod gizmos;
+mod capture;
+mod crossfade;
+mod light;
+mod split;
+mod recorder;
+
+use crate::light::Light;
+use crate::uniforms::GlobalUniforms;
+use raylib::prelude::*;
+
+/// Uniforms for tests that shade or project by hand: identity matrices, time 0, a
+/// light at the origin and the defaults for the rest. Each test overrides only
+/// what it uses with `..test_globals()`.
+fn test_globals() -> GlobalUniforms {
+    GlobalUniforms {
+        view_matrix: Matrix::identity(),
+        projection_matrix: Matrix::identity(),
+        viewport_matrix: Matrix::identity(),
+        time: 0.0,
+        previous_time: 0.0,
+        camera_position: Vector3::zero(),
+        light: Light::new(Vector3::zero()),
+        debug_view: Default::default(),
+        render_mode: Default::default(),
+        shading: Default::default(),
+        previous_view_projection: None,
+        color_space: Default::default(),
+    }
+}
@@ -0,0 +1,88 @@
+// tests/moon.rs
+use crate::matrix::multiply_matrix_vector4;
+use crate::moons::{Moon, MoonShader};
+use crate::shaders::{captured_moon_color, icy_moon_color, moon_surface_color, MOON_COLOR};
+use raylib::prelude::*;
+
+fn brightness(color: Vector3) -> f32 {
+    (color.x + color.y + color.z) / 3.0
+}
+
+/// Unit directions spread over the sphere (espiral de Fibonacci)
+fn sphere_directions(count: usize) -> Vec<Vector3> {
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let angle = i as f32 * 2.399963;
+            let ring = (1.0 - y * y).sqrt();
+            Vector3::new(ring * angle.cos(), y, ring * angle.sin())
+        })
+        .collect()
+}
+
+#[test]
+fn moon_keeps_the_same_side_towards_the_planet() {
+    let moon = Moon::default();
+    for time in [0.0, 1.3, 4.0, 9.7, 20.0] {
+        let model = moon.model_matrix(time);
+        let center = multiply_matrix_vector4(&model, &Vector4::new(0.0, 0.0, 0.0, 1.0));
+        assert!((Vector3::new(center.x, center.y, center.z) - moon.position(time)).length() < 1e-4);
+        let near_side = multiply_matrix_vector4(&model, &Vector4::new(1.0, 0.0, 0.0, 0.0));
+        let near_side = Vector3::new(near_side.x, near_side.y, near_side.z).normalized();
+        let to_planet = (-moon.position(time)).normalized();
+        // La órbita está algo inclinada: el giro solo sigue la dirección horizontal
+        assert!(near_side.dot(to_planet) > 0.95, "time {}: {:?} vs {:?}", time, near_side, to_planet);
+    }
+}
+
+#[test]
+fn moon_surface_has_craters_and_darker_maria_on_the_near_side() {
+    let directions = sphere_directions(4000);
+    let colors: Vec<Vector3> = directions.iter().map(moon_surface_color).collect();
+
+    // Bordes de cráteres más claros que las tierras altas y fondos o mares más oscuros
+    assert!(colors.iter().any(|&color| brightness(color) > brightness(MOON_COLOR) * 1.1));
+    assert!(colors.iter().any(|&color| brightness(color) < brightness(MOON_COLOR) * 0.6));
+
+    let side_average = |near: bool| {
+        let side: Vec<f32> = directions.iter().zip(&colors)
+            .filter(|(direction, _)| (direction.x > 0.3) == near && direction.x.abs() > 0.3)
+            .map(|(_, &color)| brightness(color))
+            .collect();
+        side.iter().sum::<f32>() / side.len() as f32
+    };
+    assert!(side_average(true) < side_average(false), "{} vs {}", side_average(true), side_average(false));
+}
+
+#[test]
+fn moons_follow_their_own_orbital_elements() {
+    let moon = Moon { distance: 4.0, period: 10.0, eccentricity: 0.3, inclination: 0.5, node: 1.0, phase: 0.7, ..Moon::default() };
+    let retrograde = Moon { period: -10.0, ..moon.clone() };
+
+    let mut highest: f32 = 0.0;
+    for step in 0..200 {
+        let time = step as f32 * 0.05;
+        let position = moon.position(time);
+        // El planeta está en un foco: la distancia va del periapsis al apoapsis
+        let distance = position.length();
+        assert!(distance > 4.0 * 0.7 - 1e-3 && distance < 4.0 * 1.3 + 1e-3, "{}", distance);
+        assert!((moon.position(time + 10.0) - position).length() < 1e-3);
+        highest = highest.max(position.y.abs());
+
+        // Una órbita retrógrada pasa por los mismos puntos en sentido contrario
+        assert!((retrograde.position(-time) - position).length() < 1e-3);
+    }
+    assert!(highest > 0.4 * 4.0 * 0.5_f32.sin() && highest < 4.0 * 1.3 * 0.5_f32.sin() + 1e-3);
+}
+
+#[test]
+fn moon_shaders_give_different_surfaces() {
+    let directions = sphere_directions(2000);
+    let average = |color: fn(&Vector3) -> Vector3| directions.iter().map(|direction| brightness(color(direction))).sum::<f32>() / directions.len() as f32;
+
+    // El hielo es lo más claro y el asteroide capturado lo más oscuro
+    let (rocky, icy, captured) = (average(moon_surface_color), average(icy_moon_color), average(captured_moon_color));
+    assert!(icy > rocky && rocky > captured, "{} {} {}", icy, rocky, captured);
+    assert!(MoonShader::Captured.irregular() && !MoonShader::Icy.irregular());
+    assert_eq!("icy".parse::<MoonShader>(), Ok(MoonShader::Icy));
+}
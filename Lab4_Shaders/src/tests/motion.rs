@@ -0,0 +1,73 @@
+// tests/motion.rs
+use crate::light::Light;
+use crate::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix, multiply_matrix_vector4};
+use crate::planets::PlanetParams;
+use crate::shaders::{previous_planet_model_matrix, rotate_planet_position, vertex_shader, VertexEffect, VertexTransform};
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use crate::vertex::Vertex;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+fn globals(eye: Vector3, previous_eye: Option<Vector3>) -> GlobalUniforms {
+    let projection_matrix = create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0);
+    let view = |eye: Vector3| create_view_matrix(eye, eye - Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 1.0, 0.0));
+    GlobalUniforms {
+        view_matrix: view(eye),
+        projection_matrix,
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, 100.0, 100.0),
+        camera_position: eye,
+        light: Light::new(Vector3::new(5.0, 0.0, 0.0)),
+        previous_view_projection: previous_eye.map(|eye| view(eye) * projection_matrix),
+        ..test_globals()
+    }
+}
+
+#[test]
+fn vertices_remember_where_the_camera_saw_them_last_frame() {
+    let vertex = Vertex::new(Vector3::zero(), Vector3::new(0.0, 0.0, 1.0), Vector2::zero());
+
+    // Sin matriz anterior no hay movimiento
+    let still = vertex_shader(&vertex, &VertexTransform::new(&globals(Vector3::new(0.0, 0.0, 5.0), None), Matrix::identity(), Matrix::identity()));
+    assert_eq!(still.previous_position, still.transformed_position);
+
+    // La cámara se desplazó a la derecha: el punto se movió a la izquierda en pantalla
+    let moved = globals(Vector3::new(0.2, 0.0, 5.0), Some(Vector3::new(0.0, 0.0, 5.0)));
+    let shaded = vertex_shader(&vertex, &VertexTransform::new(&moved, Matrix::identity(), Matrix::identity()));
+    assert!(shaded.transformed_position.x < shaded.previous_position.x - 1.0);
+    assert!((shaded.transformed_position.y - shaded.previous_position.y).abs() < 1e-3);
+}
+
+#[test]
+fn previous_planet_matrix_follows_the_surface_spin() {
+    let params = PlanetParams { rotation_speed: 0.8, axial_tilt: 0.0, precession_speed: 0.0, ..PlanetParams::default() };
+    let (time, dt) = (2.0, 0.1);
+    let previous = previous_planet_model_matrix(time, dt, &params);
+
+    // El detalle que hoy está en p estaba, un paso antes, donde la matriz lleva a p
+    for p in [Vector3::new(0.5, 0.0, 0.0), Vector3::new(0.1, 0.3, -0.35)] {
+        let moved = multiply_matrix_vector4(&previous, &Vector4::new(p.x, p.y, p.z, 1.0));
+        let moved = Vector3::new(moved.x, moved.y, moved.z);
+        let then = rotate_planet_position(&moved, time - dt, params.rotation_speed);
+        let now = rotate_planet_position(&p, time, params.rotation_speed);
+        assert!((then - now).length() < 1e-5, "{:?} != {:?}", then, now);
+    }
+}
+
+#[test]
+fn animated_vertex_effects_have_motion_vectors() {
+    // Cámara y cuerpo quietos: solo el latido mueve el vértice entre un frame y otro
+    let eye = Vector3::new(0.0, 0.0, 5.0);
+    let vertex = Vertex::new(Vector3::new(0.5, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector2::zero());
+    let at = |time: f32, previous_time: f32, motion: bool| {
+        let eye_before = motion.then_some(eye);
+        let globals = GlobalUniforms { time, previous_time, ..globals(eye, eye_before) };
+        vertex_shader(&vertex, &VertexTransform::new(&globals, Matrix::identity(), Matrix::identity()).with_effect(VertexEffect::Pulse))
+    };
+
+    let shaded = at(0.5, 0.0, true);
+    assert!((shaded.transformed_position.x - shaded.previous_position.x).abs() > 0.1);
+    // La posición anterior es la que el efecto daba en el frame anterior
+    let before = at(0.0, 0.0, false);
+    assert!((shaded.previous_position - before.transformed_position).length() < 1e-3);
+}
@@ -0,0 +1,37 @@
+// tests/noise.rs
+use crate::noise::{value_noise, worley};
+use raylib::prelude::*;
+
+#[test]
+fn worley_distances_are_ordered_and_bounded() {
+    for step in 0..500 {
+        let t = step as f32 * 0.173;
+        let pos = Vector3::new(t.sin() * 9.0, t * 0.7 - 20.0, (t * 1.3).cos() * 5.0);
+        let (nearest, second) = worley(&pos);
+        assert!(nearest <= second, "at {:?}", pos);
+        // La celda propia siempre tiene un punto: nunca más lejos que la diagonal de una celda
+        assert!(nearest <= 3.0f32.sqrt(), "at {:?}", pos);
+    }
+}
+
+#[test]
+fn worley_is_continuous() {
+    // Las distancias se mueven como mucho lo que se movió el punto (son 1-Lipschitz)
+    let mut previous = worley(&Vector3::new(-3.0, 0.25, 0.5));
+    for step in 1..=600 {
+        let pos = Vector3::new(-3.0 + step as f32 * 0.01, 0.25, 0.5);
+        let current = worley(&pos);
+        assert!((current.0 - previous.0).abs() <= 0.0101, "F1 jumps at {:?}", pos);
+        assert!((current.1 - previous.1).abs() <= 0.0101, "F2 jumps at {:?}", pos);
+        previous = current;
+    }
+}
+
+#[test]
+fn value_noise_is_continuous_across_cells() {
+    for boundary in [-2.0f32, 0.0, 1.0, 5.0] {
+        let below = value_noise(&Vector3::new(boundary - 1e-4, 0.3, 0.7));
+        let above = value_noise(&Vector3::new(boundary + 1e-4, 0.3, 0.7));
+        assert!((below - above).abs() < 1e-2, "jump at x = {}", boundary);
+    }
+}
@@ -0,0 +1,55 @@
+// tests/particles.rs
+use crate::comet::{comet_position, Comet};
+use crate::light::Light;
+use crate::particles::{Particle, ParticleSystem};
+use raylib::prelude::*;
+
+fn particle(lifetime: f32) -> Particle {
+    Particle { position: Vector3::zero(), velocity: Vector3::new(1.0, 0.0, 0.0), age: 0.0, lifetime, size: 0.1 }
+}
+
+#[test]
+fn particles_move_age_and_expire() {
+    let mut system = ParticleSystem::new(8, 1);
+    system.spawn(particle(1.0));
+    system.spawn(particle(0.25));
+
+    system.update(0.5, |_| Vector3::new(0.0, 2.0, 0.0));
+    assert_eq!(system.particles.len(), 1, "the short-lived particle should be gone");
+    let survivor = &system.particles[0];
+    assert_eq!(survivor.position, Vector3::new(0.5, 0.5, 0.0));
+    assert_eq!(survivor.life_fraction(), 0.5);
+}
+
+#[test]
+fn emission_carries_fractions_and_respects_capacity() {
+    let mut system = ParticleSystem::new(3, 1);
+    // 10 partículas/s a 30 fps: 1 cada 3 frames
+    let emitted: usize = (0..30).map(|_| system.emit_count(10.0, 1.0 / 30.0)).sum();
+    assert!((9..=10).contains(&emitted), "emitted {}", emitted);
+
+    for _ in 0..5 {
+        system.spawn(particle(1.0));
+    }
+    assert_eq!(system.particles.len(), 3);
+}
+
+#[test]
+fn comet_tail_points_away_from_the_light() {
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let mut comet = Comet::new();
+    let mut time = 10.0;
+    for _ in 0..60 {
+        time += 1.0 / 30.0;
+        comet.update(time, 1.0 / 30.0, &light);
+    }
+
+    let nucleus = comet_position(time);
+    let away = (nucleus - light.position).normalized();
+    let count = comet.tail.particles.len();
+    assert!(count > 100, "only {} particles", count);
+    let center = comet.tail.particles.iter().fold(Vector3::zero(), |sum, particle| sum + particle.position) / count as f32;
+    let alignment = (center - nucleus).normalized().dot(away);
+    // La cola se curva un poco hacia atrás en la órbita porque el núcleo sigue avanzando
+    assert!(alignment > 0.8, "tail is not behind the comet ({})", alignment);
+}
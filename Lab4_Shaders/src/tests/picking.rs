@@ -0,0 +1,73 @@
+// tests/picking.rs
+use crate::camera::{Camera, Projection};
+use crate::depth::DepthMode;
+use crate::light::Light;
+use crate::matrix::create_viewport_matrix;
+use crate::moons::Moon;
+use crate::picking::{pick, Body, Ray};
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const WIDTH: f32 = 320.0;
+const HEIGHT: f32 = 240.0;
+
+fn globals(camera: &Camera, projection: Projection, light: Light) -> GlobalUniforms {
+    GlobalUniforms {
+        view_matrix: camera.get_view_matrix(),
+        projection_matrix: projection.matrix(camera, PI / 3.0, WIDTH / HEIGHT, 0.1, 100.0, DepthMode::Standard),
+        viewport_matrix: create_viewport_matrix(0.0, 0.0, WIDTH, HEIGHT),
+        camera_position: camera.eye,
+        light,
+        ..test_globals()
+    }
+}
+
+/// Ray through the pixel where `world` lands on screen
+fn ray_through(globals: &GlobalUniforms, world: Vector3) -> Ray {
+    let screen = globals.project(world).expect("point must be in front of the camera");
+    Ray::through_pixel(screen.x, screen.y, WIDTH, HEIGHT, &globals.view_matrix, &globals.projection_matrix)
+}
+
+#[test]
+fn pixel_ray_passes_through_the_projected_point() {
+    let camera = Camera::new(Vector3::new(1.0, 0.8, 3.0), Vector3::new(0.2, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 3.0, 5.0));
+    let point = Vector3::new(0.7, -0.3, 0.4);
+
+    for projection in [Projection::Perspective, Projection::Orthographic] {
+        let ray = ray_through(&globals(&camera, projection, light), point);
+        // Distancia del punto a la recta del rayo
+        let offset = point - ray.origin;
+        let along = offset.dot(ray.direction);
+        let distance = (offset - ray.direction * along).length();
+        assert!(along > 0.0, "{:?}: point behind the ray origin", projection);
+        assert!(distance < 1e-3, "{:?}: ray misses the point by {}", projection, distance);
+    }
+}
+
+#[test]
+fn pick_returns_the_nearest_body_under_the_cursor() {
+    let camera = Camera::new(Vector3::new(0.0, 0.4, 6.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    // Sol justo detrás del planeta: el planeta lo tapa
+    let light = Light::new(Vector3::new(0.0, 0.0, -8.0));
+    let globals = globals(&camera, Projection::Perspective, light);
+    let time = 3.0;
+    let moons = [Moon::default()];
+
+    assert_eq!(pick(&ray_through(&globals, Vector3::zero()), time, &light, &moons), Some(Body::Planet));
+    assert_eq!(pick(&ray_through(&globals, moons[0].position(time)), time, &light, &moons), Some(Body::Moon(0)));
+
+    let empty = Ray::through_pixel(2.0, 2.0, WIDTH, HEIGHT, &globals.view_matrix, &globals.projection_matrix);
+    assert_eq!(pick(&empty, time, &light, &moons), None);
+}
+
+#[test]
+fn ray_starting_inside_a_sphere_hits_at_zero() {
+    let ray = Ray { origin: Vector3::zero(), direction: Vector3::new(0.0, 0.0, -1.0) };
+    assert_eq!(ray.intersect_sphere(Vector3::zero(), 1.0), Some(0.0));
+    assert_eq!(ray.intersect_sphere(Vector3::new(0.0, 0.0, 3.0), 1.0), None);
+    let hit = ray.intersect_sphere(Vector3::new(0.0, 0.0, -3.0), 1.0).unwrap();
+    assert!((hit - 2.0).abs() < 1e-6);
+}
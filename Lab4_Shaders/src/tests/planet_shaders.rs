@@ -0,0 +1,148 @@
+// tests/planet_shaders.rs
+// Invariantes de cada función de color de planeta y de los ruidos en los que se
+// apoyan, revisadas sobre muchos puntos de la esfera en vez de una imagen fija
+use crate::config::Config;
+use crate::noise::{fbm, fractal_noise, noise, value_noise};
+use crate::planets::{PlanetRegistry, PlanetType};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const TIMES: [f32; 4] = [0.0, 0.37, 2.0, 41.5];
+
+fn planets() -> Vec<PlanetType> {
+    Config::builtin().planet_types(&PlanetRegistry::with_builtins()).unwrap()
+}
+
+/// Points of the unit sphere on a latitude-longitude grid, poles included
+fn sphere_points() -> Vec<Vector3> {
+    let mut points = vec![Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)];
+    for i in 1..24 {
+        let latitude = PI * i as f32 / 24.0 - PI / 2.0;
+        for j in 0..48 {
+            let longitude = 2.0 * PI * j as f32 / 48.0;
+            points.push(Vector3::new(latitude.cos() * longitude.cos(), latitude.sin(), latitude.cos() * longitude.sin()));
+        }
+    }
+    points
+}
+
+/// Points on the silhouette seen from +Z (z = 0), where the normal is perpendicular
+/// to the view, plus the exact axes, where atan2 and normalizing are most fragile
+fn silhouette_points() -> Vec<Vector3> {
+    let mut points: Vec<Vector3> = (0..360)
+        .map(|degree| (degree as f32).to_radians())
+        .map(|angle| Vector3::new(angle.cos(), angle.sin(), 0.0))
+        .collect();
+    points.extend([Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0), Vector3::zero()]);
+    points
+}
+
+fn is_finite(color: Vector3) -> bool {
+    color.x.is_finite() && color.y.is_finite() && color.z.is_finite()
+}
+
+#[test]
+fn planet_colors_are_finite_on_the_silhouette() {
+    for planet in planets() {
+        for time in TIMES {
+            for pos in silhouette_points() {
+                let color = (planet.color)(&pos, time, &planet.params);
+                assert!(is_finite(color), "{} gives {:?} at {:?}, t = {}", planet.name, color, pos, time);
+                if let Some(emission) = planet.emission {
+                    let glow = emission(&pos, time, &planet.params);
+                    assert!(is_finite(glow), "{} emission gives {:?} at {:?}", planet.name, glow, pos);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn planet_colors_clamp_into_the_unit_range() {
+    // Los colores pueden pasar de 1 (el framebuffer los lleva por el tone mapping),
+    // pero un componente negativo oscurece el planeta sin que se note
+    for planet in planets() {
+        for time in TIMES {
+            for pos in sphere_points() {
+                let color = (planet.color)(&pos, time, &planet.params);
+                assert!(color.x >= 0.0 && color.y >= 0.0 && color.z >= 0.0, "{} gives {:?} at {:?}, t = {}", planet.name, color, pos, time);
+                let clamped = Vector3::new(color.x.min(1.0), color.y.min(1.0), color.z.min(1.0));
+                assert!((0.0..=1.0).contains(&clamped.x) && (0.0..=1.0).contains(&clamped.y) && (0.0..=1.0).contains(&clamped.z));
+            }
+        }
+    }
+}
+
+#[test]
+fn planet_gloss_and_height_stay_in_range() {
+    for planet in planets() {
+        for time in TIMES {
+            for pos in sphere_points() {
+                if let Some(gloss) = planet.gloss {
+                    let value = gloss(&pos, time, &planet.params);
+                    assert!((0.0..=1.0).contains(&value), "{} gloss {} at {:?}", planet.name, value, pos);
+                }
+                if let Some(height) = planet.height {
+                    let value = height(&pos, time, &planet.params);
+                    assert!((-1.0..=1.0).contains(&value), "{} height {} at {:?}", planet.name, value, pos);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn planet_colors_are_deterministic() {
+    // Dos llamadas con las mismas entradas dan exactamente el mismo color (las
+    // referencias de golden.rs y el bake dependen de eso)
+    let (first, second) = (planets(), planets());
+    for (a, b) in first.iter().zip(&second) {
+        for pos in sphere_points().into_iter().step_by(7) {
+            let (x, y) = ((a.color)(&pos, 2.0, &a.params), (b.color)(&pos, 2.0, &b.params));
+            assert_eq!((x.x.to_bits(), x.y.to_bits(), x.z.to_bits()), (y.x.to_bits(), y.y.to_bits(), y.z.to_bits()), "{} at {:?}", a.name, pos);
+        }
+    }
+}
+
+#[test]
+fn noise_is_never_negative() {
+    let mut sum = 0.0;
+    let mut count = 0;
+    let (mut low, mut high) = (false, false);
+    for x in -20..20 {
+        for y in -20..20 {
+            for z in [-3, 0, 7] {
+                let value = noise(&Vector3::new(x as f32, y as f32, z as f32));
+                assert!((0.0..1.0).contains(&value), "noise = {} at ({}, {}, {})", value, x, y, z);
+                low |= value < 0.25;
+                high |= value > 0.75;
+                sum += value;
+                count += 1;
+            }
+        }
+    }
+    // Valores repartidos por todo el rango, con la media cerca del centro
+    let mean = sum / count as f32;
+    assert!(low && high, "noise only covers part of [0, 1)");
+    assert!((mean - 0.5).abs() < 0.05, "noise mean is {}", mean);
+}
+
+#[test]
+fn signed_noises_stay_in_their_documented_ranges() {
+    let (mut fbm_sum, mut count) = (0.0, 0);
+    for step in 0..2000 {
+        let t = step as f32 * 0.0917;
+        let pos = Vector3::new(t.sin() * 13.0, t * 0.31 - 30.0, (t * 1.7).cos() * 9.0);
+        let value = value_noise(&pos);
+        assert!((-1.0..=1.0).contains(&value), "value_noise = {} at {:?}", value, pos);
+        let octaves = fbm(&pos, 5);
+        assert!(octaves.abs() < 1.0, "fbm = {} at {:?}", octaves, pos);
+        let fractal = fractal_noise(&pos, 4);
+        assert!(fractal.abs() < 2.0, "fractal_noise = {} at {:?}", fractal, pos);
+        fbm_sum += octaves;
+        count += 1;
+    }
+    // Centrado en 0: quien lo usa como color lo lleva a [0, 1] con * 0.5 + 0.5
+    let mean = fbm_sum / count as f32;
+    assert!(mean.abs() < 0.1, "fbm mean is {}", mean);
+}
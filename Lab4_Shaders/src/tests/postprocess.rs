@@ -0,0 +1,214 @@
+// tests/postprocess.rs
+use crate::framebuffer::Framebuffer;
+use crate::matrix::create_projection_matrix;
+use crate::tonemap::{DisplayTransform, ToneMapping};
+use crate::postprocess::{Bloom, DepthOfField, Fxaa, MotionBlur, PostEffect, PostProcessPipeline};
+use crate::tiles::Tile;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const DARK: Color = Color::new(0, 0, 0, 255);
+const LIGHT: Color = Color::new(255, 255, 255, 255);
+
+/// Framebuffer filled from a predicate: true pixels are white, the rest black
+fn image(width: i32, height: i32, white: impl Fn(i32, i32) -> bool) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    let pixels: Vec<Color> = (0..width * height)
+        .map(|index| if white(index % width, index / width) { LIGHT } else { DARK })
+        .collect();
+    framebuffer.set_pixels(&pixels);
+    framebuffer
+}
+
+fn projection() -> Matrix {
+    create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0)
+}
+
+/// NDC depth that `projection()` gives a surface `distance` in front of the camera
+fn ndc_depth(distance: f32) -> f32 {
+    let p = projection();
+    (p.m10 * -distance + p.m14) / (p.m11 * -distance + p.m15)
+}
+
+trait ApplyTo {
+    fn apply_to(self, framebuffer: &mut Framebuffer);
+}
+
+impl<E: PostEffect + 'static> ApplyTo for E {
+    fn apply_to(self, framebuffer: &mut Framebuffer) {
+        let mut pipeline = PostProcessPipeline::new();
+        pipeline.push(self, true);
+        pipeline.apply(framebuffer, &projection());
+    }
+}
+
+#[test]
+fn fxaa_leaves_flat_regions_untouched() {
+    let mut framebuffer = image(8, 8, |x, _| x < 4);
+    let before = framebuffer.pixels();
+    Fxaa.apply_to(&mut framebuffer);
+    let after = framebuffer.pixels();
+
+    // Lejos del borde (columnas 0-1 y 6-7) nada cambia
+    for y in 0..8 {
+        for x in [0, 1, 6, 7] {
+            assert_eq!(after[(y * 8 + x) as usize], before[(y * 8 + x) as usize]);
+        }
+    }
+}
+
+#[test]
+fn fxaa_softens_a_staircase_edge() {
+    // Escalón diagonal: cada fila el borde avanza 2 pixeles
+    let mut framebuffer = image(16, 8, |x, y| x < 2 * y + 2);
+    let before = framebuffer.pixels();
+    Fxaa.apply_to(&mut framebuffer);
+    let after = framebuffer.pixels();
+
+    let changed = before.iter().zip(&after).filter(|(a, b)| a != b).count();
+    assert!(changed > 0);
+    // Los pixeles modificados quedan entre los dos colores originales
+    assert!(after.iter().any(|color| color.r > 0 && color.r < 255));
+}
+
+#[test]
+fn effects_only_touch_the_scissored_region() {
+    // El mismo escalón en las dos mitades; solo la izquierda se procesa
+    let mut framebuffer = image(32, 8, |x, y| x % 16 < 2 * y + 2);
+    let before = framebuffer.pixels();
+    framebuffer.set_scissor(Some(Tile { x0: 0, y0: 0, x1: 16, y1: 8 }));
+    Fxaa.apply_to(&mut framebuffer);
+    framebuffer.set_scissor(None);
+    let after = framebuffer.pixels();
+
+    let changed: Vec<usize> = (0..before.len()).filter(|&i| before[i] != after[i]).collect();
+    assert!(!changed.is_empty());
+    assert!(changed.iter().all(|&i| i % 32 < 16));
+}
+
+/// Framebuffer whose pixels `lit` are a surface emitting `light` (linear HDR), resolved
+fn lit_image(width: i32, height: i32, light: f32, lit: impl Fn(i32, i32) -> bool) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.clear();
+    for y in 0..height {
+        for x in 0..width {
+            if lit(x, y) {
+                framebuffer.point(x, y, Vector3::new(light, light, light), 0.5);
+            }
+        }
+    }
+    framebuffer.resolve();
+    framebuffer
+}
+
+#[test]
+fn bloom_spreads_light_around_bright_pixels_only() {
+    let mut framebuffer = lit_image(32, 32, 4.0, |x, y| (14..18).contains(&x) && (14..18).contains(&y));
+    let background = framebuffer.pixels()[0];
+    Bloom::default().apply_to(&mut framebuffer);
+    let after = framebuffer.pixels();
+
+    // Cerca del cuadro brillante aparece brillo; en las esquinas lejanas sigue el fondo
+    assert!(after[(16 * 32 + 20) as usize].r > background.r);
+    assert_eq!(after[0], background);
+    assert_eq!(after[(31 * 32 + 31) as usize], background);
+}
+
+#[test]
+fn bloom_ignores_colors_below_threshold() {
+    let mut framebuffer = lit_image(8, 8, 0.6, |_, _| true);
+    let before = framebuffer.pixels();
+    Bloom::default().apply_to(&mut framebuffer);
+    assert_eq!(framebuffer.pixels(), before);
+}
+
+#[test]
+fn bloom_reads_the_light_before_it_is_clipped() {
+    // Con la curva Clamp 1.5 y 6 se ven igual de blancos, pero el más intenso brilla más
+    let glow_at = |light: f32| {
+        let mut framebuffer = lit_image(32, 32, light, |x, y| (14..18).contains(&x) && (14..18).contains(&y));
+        framebuffer.display = DisplayTransform { tone_mapping: ToneMapping::Clamp, ..DisplayTransform::default() };
+        framebuffer.resolve();
+        let center = framebuffer.pixels()[(16 * 32 + 16) as usize];
+        Bloom::default().apply_to(&mut framebuffer);
+        (center, framebuffer.pixels()[(16 * 32 + 21) as usize].r)
+    };
+    let (dim_center, dim_glow) = glow_at(1.5);
+    let (bright_center, bright_glow) = glow_at(6.0);
+    assert_eq!(dim_center, bright_center);
+    assert!(bright_glow > dim_glow, "{} vs {}", bright_glow, dim_glow);
+}
+
+#[test]
+fn pipeline_toggles_effects_by_name() {
+    let mut pipeline = PostProcessPipeline::new();
+    pipeline.push(Bloom::default(), false);
+    pipeline.push(Fxaa, true);
+
+    assert_eq!(pipeline.toggle("bloom"), Some(true));
+    assert_eq!(pipeline.toggle("fxaa"), Some(false));
+    assert_eq!(pipeline.toggle("vignette"), None);
+}
+
+#[test]
+fn depth_of_field_circle_grows_away_from_the_focus() {
+    let dof = DepthOfField { focus_distance: 5.0, focus_range: 1.0, transition: 2.0, max_radius: 6.0 };
+    assert_eq!(dof.circle_of_confusion(5.0), 0.0);
+    assert_eq!(dof.circle_of_confusion(5.9), 0.0);
+    assert!((dof.circle_of_confusion(7.0) - 3.0).abs() < 1e-4);
+    assert_eq!(dof.circle_of_confusion(20.0), 6.0);
+    assert_eq!(dof.circle_of_confusion(f32::INFINITY), 6.0);
+}
+
+#[test]
+fn depth_of_field_blurs_only_what_is_out_of_focus() {
+    // Tablero de ajedrez: mitad izquierda a la distancia de foco, mitad derecha lejos
+    let (width, height) = (32, 16);
+    let mut framebuffer = image(width, height, |x, y| (x + y) % 2 == 0);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = if x < width / 2 { 5.0 } else { 40.0 };
+            framebuffer.point(x, y, Vector3::zero(), ndc_depth(distance));
+        }
+    }
+    let before = framebuffer.pixels();
+    DepthOfField { focus_distance: 5.0, ..DepthOfField::default() }.apply_to(&mut framebuffer);
+    let after = framebuffer.pixels();
+
+    for y in 0..height {
+        for x in 0..width / 2 - 1 {
+            assert_eq!(after[(y * width + x) as usize], before[(y * width + x) as usize]);
+        }
+    }
+    // Lejos, el tablero se promedia hacia gris
+    let far = after[(8 * width + 24) as usize];
+    assert!(far.r > 40 && far.r < 215, "far pixel not blurred: {:?}", far);
+}
+
+#[test]
+fn motion_blur_streaks_along_the_velocity_only() {
+    // Una columna blanca que se mueve 8 pixeles por frame hacia la derecha
+    let (width, height) = (24, 8);
+    let mut framebuffer = image(width, height, |x, _| x == 12);
+    for y in 0..height {
+        framebuffer.set_velocity(12, y, Vector2::new(8.0, 0.0));
+    }
+    MotionBlur { shutter: 1.0, ..MotionBlur::default() }.apply_to(&mut framebuffer);
+    let after = framebuffer.pixels();
+
+    // El pixel en movimiento se mezcla con sus vecinos horizontales; los quietos no cambian
+    let moving = after[(4 * width + 12) as usize];
+    assert!(moving.r > 0 && moving.r < 255, "moving pixel not blurred: {:?}", moving);
+    assert_eq!(after[(4 * width + 2) as usize], DARK);
+    assert_eq!(after[(4 * width + 13) as usize], DARK);
+}
+
+#[test]
+fn pipeline_reports_enabled_effects() {
+    let mut pipeline = PostProcessPipeline::new();
+    pipeline.push(MotionBlur::default(), false);
+    assert!(!pipeline.is_enabled("motion_blur"));
+    pipeline.toggle("motion_blur");
+    assert!(pipeline.is_enabled("motion_blur"));
+    assert!(!pipeline.is_enabled("bloom"));
+}
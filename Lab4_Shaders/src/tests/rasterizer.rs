@@ -0,0 +1,536 @@
+// tests/rasterizer.rs
+// Small analytic cases for triangle() + Framebuffer::point, checked pixel by pixel.
+use crate::depth::DepthMode;
+use crate::fragment::{Fragment, FULL_COVERAGE};
+use crate::framebuffer::{DepthTest, Framebuffer};
+use crate::stats::RenderStats;
+use crate::tonemap::DisplayTransform;
+use crate::tiles::Tile;
+use crate::triangle::{self, assemble_triangles, classify_triangle, line, triangle, triangle_msaa, RenderMode, TriangleStatus};
+use crate::vertex::Vertex;
+use raylib::prelude::*;
+
+const BACKGROUND: Color = Color::new(0, 0, 0, 255);
+
+/// Builds a vertex that is already in screen space (what the vertex shader would output)
+fn screen_vertex(x: f32, y: f32, depth: f32) -> Vertex {
+    let mut vertex = Vertex::new(Vector3::new(x, y, depth), Vector3::new(0.0, 0.0, 1.0), Vector2::zero());
+    vertex.set_transformed(Vector3::new(x, y, depth), Vector3::new(0.0, 0.0, 1.0));
+    vertex
+}
+
+// Sin recorte: el framebuffer descarta lo que queda fuera
+const WHOLE_SCREEN: Tile = Tile { x0: i32::MIN, y0: i32::MIN, x1: i32::MAX, y1: i32::MAX };
+
+fn rasterize(a: &Vertex, b: &Vertex, c: &Vertex) -> Vec<Fragment> {
+    let mut fragments = Vec::new();
+    triangle(a, b, c, WHOLE_SCREEN, &mut |fragment| fragments.push(fragment));
+    fragments
+}
+
+fn draw(framebuffer: &mut Framebuffer, tri: [(f32, f32); 3], depth: f32, color: Vector3) {
+    let [a, b, c] = tri.map(|(x, y)| screen_vertex(x, y, depth));
+    for fragment in rasterize(&a, &b, &c) {
+        framebuffer.point(fragment.position.x as i32, fragment.position.y as i32, color, fragment.depth);
+    }
+    framebuffer.resolve();
+}
+
+fn new_framebuffer(width: i32, height: i32) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.set_background_color(BACKGROUND);
+    // Sin curva de tono, gamma ni dithering: los colores escritos se comparan byte a byte
+    framebuffer.display = DisplayTransform::linear();
+    framebuffer.clear();
+    framebuffer
+}
+
+/// '#' for every pixel that differs from the background, '.' otherwise
+fn coverage_mask(framebuffer: &mut Framebuffer) -> Vec<String> {
+    (0..framebuffer.height)
+        .map(|y| {
+            (0..framebuffer.width)
+                .map(|x| if framebuffer.get_pixel_color(x, y) == Some(BACKGROUND) { '.' } else { '#' })
+                .collect()
+        })
+        .collect()
+}
+
+fn covered_pixels(framebuffer: &mut Framebuffer) -> usize {
+    coverage_mask(framebuffer).iter().map(|row| row.matches('#').count()).sum()
+}
+
+#[test]
+fn right_triangle_covers_pixel_centers_inside_hypotenuse() {
+    let mut framebuffer = new_framebuffer(6, 6);
+    draw(&mut framebuffer, [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)], 0.5, Vector3::new(1.0, 1.0, 1.0));
+
+    // Pixel (x, y) is covered when its center satisfies x + y + 1 < 4: the centers
+    // exactly on the hypotenuse belong to the neighbour (it is a bottom-right edge)
+    assert_eq!(coverage_mask(&mut framebuffer), vec![
+        "###...",
+        "##....",
+        "#.....",
+        "......",
+        "......",
+        "......",
+    ]);
+}
+
+#[test]
+fn fragment_count_matches_covered_pixels() {
+    let a = screen_vertex(0.0, 0.0, 0.5);
+    let b = screen_vertex(4.0, 0.0, 0.5);
+    let c = screen_vertex(0.0, 4.0, 0.5);
+
+    assert_eq!(rasterize(&a, &b, &c).len(), 6);
+    // Winding order must not change coverage
+    assert_eq!(rasterize(&a, &c, &b).len(), 6);
+}
+
+#[test]
+fn degenerate_triangle_produces_no_fragments() {
+    let a = screen_vertex(0.0, 0.0, 0.5);
+    let b = screen_vertex(2.0, 2.0, 0.5);
+    let c = screen_vertex(4.0, 4.0, 0.5);
+
+    assert!(rasterize(&a, &b, &c).is_empty());
+}
+
+/// How many fragments each pixel of a `width` x `height` grid receives from `triangles`
+fn fragments_per_pixel(width: i32, height: i32, triangles: &[[(f32, f32); 3]]) -> Vec<Vec<u32>> {
+    let mut counts = vec![vec![0; width as usize]; height as usize];
+    for tri in triangles {
+        let [a, b, c] = tri.map(|(x, y)| screen_vertex(x, y, 0.5));
+        for fragment in rasterize(&a, &b, &c) {
+            counts[fragment.position.y as usize][fragment.position.x as usize] += 1;
+        }
+    }
+    counts
+}
+
+#[test]
+fn shared_diagonal_through_pixel_centers_is_drawn_once() {
+    // La diagonal de (0.5, 0.5) a (4.5, 4.5) pasa justo por cinco centros de píxel
+    let square = [
+        [(0.5, 0.5), (4.5, 0.5), (4.5, 4.5)],
+        [(0.5, 0.5), (4.5, 4.5), (0.5, 4.5)],
+    ];
+    let counts = fragments_per_pixel(6, 6, &square);
+
+    // Bordes izquierdo y superior incluidos, derecho e inferior no: 4x4 píxeles, una vez cada uno
+    for (y, row) in counts.iter().enumerate() {
+        for (x, &count) in row.iter().enumerate() {
+            let expected = u32::from(x < 4 && y < 4);
+            assert_eq!(count, expected, "pixel ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn triangle_fan_covers_every_pixel_exactly_once() {
+    // Abanico alrededor de un centro de píxel con vértices en posiciones fraccionarias:
+    // cada par de triángulos vecinos comparte un borde y todos comparten el centro
+    let center = (8.5, 8.5);
+    let rim: Vec<(f32, f32)> = (0..7)
+        .map(|i| {
+            let angle = i as f32 / 7.0 * std::f32::consts::TAU + 0.3;
+            (center.0 + 7.3 * angle.cos(), center.1 + 6.1 * angle.sin())
+        })
+        .collect();
+    let fan: Vec<[(f32, f32); 3]> = (0..rim.len()).map(|i| [center, rim[i], rim[(i + 1) % rim.len()]]).collect();
+    let counts = fragments_per_pixel(17, 17, &fan);
+
+    assert!(counts.iter().flatten().all(|&count| count <= 1), "a pixel was drawn twice");
+    // Sin grietas: el centro del abanico y cada píxel entre dos píxeles cubiertos de la fila también
+    assert_eq!(counts[8][8], 1);
+    for row in &counts {
+        let covered: Vec<usize> = (0..row.len()).filter(|&x| row[x] == 1).collect();
+        if let (Some(&first), Some(&last)) = (covered.first(), covered.last()) {
+            assert_eq!(covered.len(), last - first + 1, "gap in row {:?}", row);
+        }
+    }
+}
+
+#[test]
+fn adjacent_rectangles_share_the_edge_without_overlap() {
+    // Borde vertical en x = 2.5, justo sobre los centros de la columna 2
+    let mut triangles = Vec::new();
+    for (x0, x1) in [(0.5, 2.5), (2.5, 5.5)] {
+        triangles.push([(x0, 0.5), (x1, 0.5), (x1, 3.5)]);
+        triangles.push([(x0, 0.5), (x1, 3.5), (x0, 3.5)]);
+    }
+    let counts = fragments_per_pixel(6, 4, &triangles);
+
+    assert_eq!(counts[0], vec![1, 1, 1, 1, 1, 0]);
+    assert_eq!(counts[2], vec![1, 1, 1, 1, 1, 0]);
+    assert_eq!(counts[3], vec![0; 6]);
+}
+
+/// Like `draw_rect`, but rasterized with the MSAA samples into a multisampled framebuffer
+fn draw_rect_msaa(framebuffer: &mut Framebuffer, x0: f32, y0: f32, x1: f32, y1: f32) {
+    framebuffer.set_msaa(true);
+    for tri in [[(x0, y0), (x1, y0), (x1, y1)], [(x0, y0), (x1, y1), (x0, y1)]] {
+        let [a, b, c] = tri.map(|(x, y)| screen_vertex(x, y, 0.5));
+        triangle_msaa(&a, &b, &c, WHOLE_SCREEN, &mut |fragment| {
+            let (x, y) = (fragment.position.x as i32, fragment.position.y as i32);
+            framebuffer.point_samples(x, y, Vector3::one(), fragment.depth, fragment.depth_slope, fragment.coverage);
+        });
+    }
+    framebuffer.resolve();
+}
+
+#[test]
+fn msaa_reports_partial_coverage_on_edges() {
+    // Borde en x = 0.5: en el píxel 0 quedan dentro las muestras con x < 0.5 (la 0 y la 2)
+    let [a, b, c] = [(-4.0, 0.0), (0.5, 0.0), (0.5, 4.0)].map(|(x, y)| screen_vertex(x, y, 0.5));
+    let mut coverage = std::collections::HashMap::new();
+    triangle_msaa(&a, &b, &c, WHOLE_SCREEN, &mut |fragment| {
+        coverage.insert((fragment.position.x.floor() as i32, fragment.position.y.floor() as i32), fragment.coverage);
+    });
+
+    assert_eq!(coverage.get(&(0, 0)), Some(&0b0101));
+    assert_eq!(coverage.get(&(-1, 0)), Some(&FULL_COVERAGE));
+    assert_eq!(coverage.get(&(1, 0)), None);
+}
+
+#[test]
+fn msaa_blends_edge_pixels_with_the_background() {
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw_rect_msaa(&mut framebuffer, 0.0, -8.0, 0.5, 8.0);
+
+    // Dos de cuatro muestras: mitad blanco, mitad fondo negro
+    assert_eq!(framebuffer.get_pixel_color(0, 1), Some(Color::new(128, 128, 128, 255)));
+    assert_eq!(framebuffer.get_pixel_color(1, 1), Some(BACKGROUND));
+}
+
+#[test]
+fn msaa_shared_edge_leaves_no_seam() {
+    // Dos rectángulos que comparten un borde en medio de la columna 1: cada muestra la
+    // cubre exactamente uno de los dos, así que el píxel queda completo
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw_rect_msaa(&mut framebuffer, 0.0, 0.0, 1.6, 4.0);
+    draw_rect_msaa(&mut framebuffer, 1.6, 0.0, 4.0, 4.0);
+
+    assert!((0..4).all(|x| framebuffer.get_pixel_color(x, 2) == Some(Color::new(255, 255, 255, 255))));
+}
+
+#[test]
+fn written_color_is_quantized_to_u8() {
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw(&mut framebuffer, [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)], 0.5, Vector3::new(1.0, 0.5, 2.0));
+
+    assert_eq!(framebuffer.get_pixel_color(0, 0), Some(Color::new(255, 127, 255, 255)));
+    assert_eq!(framebuffer.get_pixel_color(3, 3), Some(BACKGROUND));
+}
+
+#[test]
+fn depth_test_keeps_nearest_fragment_regardless_of_draw_order() {
+    let tri = [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+    let red = Vector3::new(1.0, 0.0, 0.0);
+    let green = Vector3::new(0.0, 1.0, 0.0);
+    let expected_near = Some(Color::new(0, 255, 0, 255));
+
+    // Near drawn last
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw(&mut framebuffer, tri, 0.8, red);
+    draw(&mut framebuffer, tri, 0.2, green);
+    assert_eq!(framebuffer.get_pixel_color(1, 1), expected_near);
+
+    // Near drawn first
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw(&mut framebuffer, tri, 0.2, green);
+    draw(&mut framebuffer, tri, 0.8, red);
+    assert_eq!(framebuffer.get_pixel_color(1, 1), expected_near);
+}
+
+#[test]
+fn equal_depth_keeps_first_fragment() {
+    let tri = [(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+    let mut framebuffer = new_framebuffer(4, 4);
+    draw(&mut framebuffer, tri, 0.5, Vector3::new(1.0, 0.0, 0.0));
+    draw(&mut framebuffer, tri, 0.5, Vector3::new(0.0, 0.0, 1.0));
+
+    assert_eq!(framebuffer.get_pixel_color(0, 0), Some(Color::new(255, 0, 0, 255)));
+}
+
+/// Two triangles forming the axis-aligned rectangle [x0, x1] x [y0, y1]
+fn draw_rect(framebuffer: &mut Framebuffer, x0: f32, y0: f32, x1: f32, y1: f32) {
+    let white = Vector3::new(1.0, 1.0, 1.0);
+    draw(framebuffer, [(x0, y0), (x1, y0), (x1, y1)], 0.5, white);
+    draw(framebuffer, [(x0, y0), (x1, y1), (x0, y1)], 0.5, white);
+}
+
+#[test]
+fn clipped_against_left_edge() {
+    let mut framebuffer = new_framebuffer(8, 8);
+    draw_rect(&mut framebuffer, -4.0, 0.0, 4.0, 8.0);
+    assert_eq!(covered_pixels(&mut framebuffer), 4 * 8);
+    assert_eq!(coverage_mask(&mut framebuffer)[0], "####....");
+}
+
+#[test]
+fn clipped_against_right_edge() {
+    let mut framebuffer = new_framebuffer(8, 8);
+    draw_rect(&mut framebuffer, 4.0, 0.0, 12.0, 8.0);
+    assert_eq!(covered_pixels(&mut framebuffer), 4 * 8);
+    assert_eq!(coverage_mask(&mut framebuffer)[7], "....####");
+}
+
+#[test]
+fn clipped_against_top_edge() {
+    let mut framebuffer = new_framebuffer(8, 8);
+    draw_rect(&mut framebuffer, 0.0, -4.0, 8.0, 4.0);
+    assert_eq!(covered_pixels(&mut framebuffer), 8 * 4);
+    assert_eq!(coverage_mask(&mut framebuffer)[3], "########");
+    assert_eq!(coverage_mask(&mut framebuffer)[4], "........");
+}
+
+#[test]
+fn clipped_against_bottom_edge() {
+    let mut framebuffer = new_framebuffer(8, 8);
+    draw_rect(&mut framebuffer, 0.0, 4.0, 8.0, 12.0);
+    assert_eq!(covered_pixels(&mut framebuffer), 8 * 4);
+    assert_eq!(coverage_mask(&mut framebuffer)[3], "........");
+    assert_eq!(coverage_mask(&mut framebuffer)[4], "########");
+}
+
+#[test]
+fn fully_offscreen_triangle_writes_nothing() {
+    let mut framebuffer = new_framebuffer(8, 8);
+    draw_rect(&mut framebuffer, -20.0, -20.0, -10.0, -10.0);
+    draw_rect(&mut framebuffer, 10.0, 10.0, 20.0, 20.0);
+    assert_eq!(covered_pixels(&mut framebuffer), 0);
+}
+
+#[test]
+fn interpolation_at_centroid_is_vertex_average() {
+    // Centroid of these vertices is (2.5, 2.5), the center of pixel (2, 2)
+    let mut a = screen_vertex(0.5, 0.5, 0.1);
+    let mut b = screen_vertex(6.5, 0.5, 0.4);
+    let mut c = screen_vertex(0.5, 6.5, 0.7);
+    a.position = Vector3::new(3.0, 0.0, 0.0);
+    b.position = Vector3::new(0.0, 3.0, 0.0);
+    c.position = Vector3::new(0.0, 0.0, 3.0);
+
+    let fragments = rasterize(&a, &b, &c);
+    let centroid = fragments
+        .iter()
+        .find(|f| f.position.x == 2.5 && f.position.y == 2.5)
+        .expect("centroid pixel must be covered");
+
+    assert!((centroid.depth - 0.4).abs() < 1e-5);
+    assert!((centroid.world_position.x - 1.0).abs() < 1e-5);
+    assert!((centroid.world_position.y - 1.0).abs() < 1e-5);
+    assert!((centroid.world_position.z - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn attributes_are_interpolated_with_perspective_correction() {
+    // Mismo triángulo que el del centroide, pero b y c están tres veces más lejos de la cámara
+    let mut a = screen_vertex(0.5, 0.5, 0.1);
+    let mut b = screen_vertex(6.5, 0.5, 0.4);
+    let mut c = screen_vertex(0.5, 6.5, 0.7);
+    a.position = Vector3::new(3.0, 0.0, 0.0);
+    b.position = Vector3::new(0.0, 3.0, 0.0);
+    c.position = Vector3::new(0.0, 0.0, 3.0);
+    a.color = Vector3::new(1.0, 0.0, 0.0);
+    b.color = Vector3::new(0.0, 1.0, 0.0);
+    c.color = Vector3::new(0.0, 0.0, 1.0);
+    b.clip_w = 3.0;
+    c.clip_w = 3.0;
+
+    let fragments = rasterize(&a, &b, &c);
+    let centroid = fragments
+        .iter()
+        .find(|f| f.position.x == 2.5 && f.position.y == 2.5)
+        .expect("centroid pixel must be covered");
+
+    // En pantalla los pesos son 1/3; divididos por w quedan 0.6, 0.2 y 0.2
+    assert!((centroid.world_position - Vector3::new(1.8, 0.6, 0.6)).length() < 1e-5);
+    assert!((centroid.color - Vector3::new(0.6, 0.2, 0.2)).length() < 1e-5);
+    // La profundidad sigue siendo lineal en pantalla
+    assert!((centroid.depth - 0.4).abs() < 1e-5);
+}
+
+#[test]
+fn interpolation_at_vertex_pixel_matches_vertex() {
+    let mut a = screen_vertex(0.5, 0.5, 0.1);
+    let b = screen_vertex(6.5, 0.5, 0.4);
+    let c = screen_vertex(0.5, 6.5, 0.7);
+    a.position = Vector3::new(-1.0, 2.0, 5.0);
+
+    let fragments = rasterize(&a, &b, &c);
+    let corner = fragments
+        .iter()
+        .find(|f| f.position.x == 0.5 && f.position.y == 0.5)
+        .expect("vertex pixel must be covered");
+
+    assert!((corner.depth - 0.1).abs() < 1e-6);
+    assert!((corner.world_position.x + 1.0).abs() < 1e-6);
+    assert!((corner.world_position.y - 2.0).abs() < 1e-6);
+    assert!((corner.world_position.z - 5.0).abs() < 1e-6);
+}
+
+#[test]
+fn assembly_culls_offscreen_and_degenerate_and_counts_clipped() {
+    let vertices: Vec<Vertex> = [
+        (1.0, 1.0), (5.0, 1.0), (1.0, 5.0),     // dentro
+        (-2.0, 1.0), (3.0, 1.0), (1.0, 4.0),    // cruza el borde izquierdo
+        (10.0, 1.0), (14.0, 1.0), (10.0, 5.0),  // fuera de pantalla
+        (1.0, 1.0), (2.0, 2.0), (3.0, 3.0),     // degenerado (colineal)
+    ]
+    .iter()
+    .map(|&(x, y)| screen_vertex(x, y, 0.5))
+    .collect();
+
+    let mut stats = RenderStats::default();
+    let mut triangles = Vec::new();
+    assemble_triangles(&vertices, 8, 8, DepthMode::Standard, &mut stats, &mut triangles);
+
+    assert_eq!(triangles.len(), 2);
+    assert_eq!(stats.triangles, 4);
+    assert_eq!(stats.triangles_culled, 2);
+    assert_eq!(stats.triangles_clipped, 1);
+}
+
+#[test]
+fn assembly_culls_triangles_behind_the_camera() {
+    // Tras dividir por un w negativo la profundidad queda fuera de [-1, 1]
+    let vertices = [screen_vertex(1.0, 1.0, 0.5), screen_vertex(5.0, 1.0, 1.3), screen_vertex(1.0, 5.0, 0.5)];
+
+    let mut stats = RenderStats::default();
+    let mut triangles = Vec::new();
+    assemble_triangles(&vertices, 8, 8, DepthMode::Standard, &mut stats, &mut triangles);
+
+    assert!(triangles.is_empty());
+    assert_eq!(stats.triangles_culled, 1);
+}
+
+/// Assembles the right triangle (0, 0), (6, 0), (0, 6) on an 8 x 8 target with the
+/// given vertex depths and counts the fragments of whatever survives assembly
+fn fragments_after_assembly(depths: [f32; 3], depth_mode: DepthMode) -> (TriangleStatus, usize) {
+    let vertices = [(0.0, 0.0), (6.0, 0.0), (0.0, 6.0)].iter().zip(depths).map(|(&(x, y), depth)| screen_vertex(x, y, depth)).collect::<Vec<_>>();
+    let mut triangles = Vec::new();
+    assemble_triangles(&vertices, 8, 8, depth_mode, &mut RenderStats::default(), &mut triangles);
+    let fragments = triangles.iter().map(|[a, b, c]| rasterize(a, b, c).len()).sum();
+    (classify_triangle(&vertices, 8, 8, depth_mode), fragments)
+}
+
+// Triángulo entero dentro del rango de profundidad: los centros con x + y <= 4 (la
+// hipotenusa pasa por los centros con x + y = 5 y la regla top-left los deja fuera)
+const WHOLE_TRIANGLE: usize = 15;
+
+#[test]
+fn triangle_crossing_the_near_plane_is_culled_whole_not_clipped() {
+    assert_eq!(fragments_after_assembly([0.5, 0.5, 0.5], DepthMode::Standard), (TriangleStatus::Drawn, WHOLE_TRIANGLE));
+    // Un solo vértice antes del plano cercano (NDC < -1) se lleva todo el triángulo,
+    // también la parte que estaría delante del plano
+    assert_eq!(fragments_after_assembly([0.5, -1.05, 0.5], DepthMode::Standard), (TriangleStatus::OutsideDepth, 0));
+    assert_eq!(fragments_after_assembly([0.5, -1.0, 0.5], DepthMode::Standard), (TriangleStatus::Drawn, WHOLE_TRIANGLE));
+}
+
+#[test]
+fn triangle_crossing_the_far_plane_is_culled_whole_not_clipped() {
+    assert_eq!(fragments_after_assembly([0.5, 0.5, 1.05], DepthMode::Standard), (TriangleStatus::OutsideDepth, 0));
+    assert_eq!(fragments_after_assembly([0.5, 0.5, 1.0], DepthMode::Standard), (TriangleStatus::Drawn, WHOLE_TRIANGLE));
+}
+
+#[test]
+fn reverse_z_culls_whole_triangles_past_either_plane() {
+    // Con reverse-Z el plano cercano está en 1 y el lejano en 0
+    assert_eq!(fragments_after_assembly([0.5, 1.05, 0.5], DepthMode::ReverseZ), (TriangleStatus::OutsideDepth, 0));
+    assert_eq!(fragments_after_assembly([0.5, -0.05, 0.5], DepthMode::ReverseZ), (TriangleStatus::OutsideDepth, 0));
+    assert_eq!(fragments_after_assembly([0.0, 1.0, 0.5], DepthMode::ReverseZ), (TriangleStatus::Drawn, WHOLE_TRIANGLE));
+}
+
+#[test]
+fn vertex_behind_the_camera_culls_the_triangle_even_with_depth_in_range() {
+    let mut vertices = [screen_vertex(0.0, 0.0, 0.5), screen_vertex(8.0, 0.0, 0.5), screen_vertex(0.0, 8.0, 0.5)];
+    vertices[1].clip_w = -0.5;
+    assert_eq!(classify_triangle(&vertices, 8, 8, DepthMode::Standard), TriangleStatus::OutsideDepth);
+}
+
+#[test]
+fn stats_count_depth_rejections_but_not_offscreen_fragments() {
+    let mut framebuffer = new_framebuffer(4, 4);
+    let mut stats = RenderStats::default();
+    let color = Vector3::new(1.0, 1.0, 1.0);
+
+    stats.record_fragment(framebuffer.point(1, 1, color, 0.5));
+    stats.record_fragment(framebuffer.point(1, 1, color, 0.7));
+    stats.record_fragment(framebuffer.point(1, 1, color, 0.2));
+    stats.record_fragment(framebuffer.point(9, 1, color, 0.1));
+
+    assert_eq!(stats.fragments_written, 2);
+    assert_eq!(stats.depth_rejected, 1);
+}
+
+#[test]
+fn early_depth_test_agrees_with_point_and_writes_nothing() {
+    for msaa in [false, true] {
+        let mut framebuffer = new_framebuffer(4, 4);
+        framebuffer.set_msaa(msaa);
+        let color = Vector3::new(1.0, 1.0, 1.0);
+        framebuffer.point(1, 1, color, 0.5);
+
+        let (slope, coverage) = (Vector2::zero(), FULL_COVERAGE);
+        assert_eq!(framebuffer.depth_test(1, 1, 0.7, slope, coverage), DepthTest::Rejected);
+        assert_eq!(framebuffer.depth_test(1, 1, 0.2, slope, coverage), DepthTest::Passed);
+        assert_eq!(framebuffer.depth_test(9, 1, 0.2, slope, coverage), DepthTest::OutOfBounds);
+        // Probar no escribe: el fragmento cercano sigue pasando y el lejano sigue fallando
+        assert_eq!(framebuffer.depth_test(1, 1, 0.2, slope, coverage), DepthTest::Passed);
+        assert_eq!(framebuffer.point(1, 1, color, 0.7), DepthTest::Rejected);
+        assert_eq!(framebuffer.point(1, 1, color, 0.2), DepthTest::Passed);
+    }
+}
+
+#[test]
+fn downsample_averages_each_block() {
+    let mut supersampled = new_framebuffer(4, 2);
+    // Bloque izquierdo: 1 de 4 muestras blanca; bloque derecho: todo blanco
+    supersampled.point(0, 0, Vector3::new(1.0, 1.0, 1.0), 0.5);
+    draw_rect(&mut supersampled, 2.0, 0.0, 4.0, 2.0); // draw() también llama a resolve()
+
+    let mut framebuffer = new_framebuffer(2, 1);
+    supersampled.downsample_into(&mut framebuffer);
+
+    assert_eq!(framebuffer.get_pixel_color(0, 0), Some(Color::new(63, 63, 63, 255)));
+    assert_eq!(framebuffer.get_pixel_color(1, 0), Some(Color::new(255, 255, 255, 255)));
+}
+
+#[test]
+fn line_covers_every_pixel_between_endpoints_once() {
+    let a = screen_vertex(0.5, 0.5, 0.0);
+    let b = screen_vertex(6.5, 3.5, 1.0);
+    let fragments = line(&a, &b);
+
+    // Pendiente < 1: un fragmento por columna, sin huecos
+    let columns: Vec<i32> = fragments.iter().map(|f| f.position.x as i32).collect();
+    assert_eq!(columns, (0..=6).collect::<Vec<_>>());
+    assert_eq!(fragments.first().unwrap().position.y as i32, 0);
+    assert_eq!(fragments.last().unwrap().position.y as i32, 3);
+    assert!((fragments.last().unwrap().depth - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn wireframe_draws_only_the_edges() {
+    let tri = [(0.0, 0.0), (7.0, 0.0), (0.0, 7.0)].map(|(x, y)| screen_vertex(x, y, 0.5));
+
+    let mut framebuffer = new_framebuffer(8, 8);
+    triangle::rasterize(&tri, RenderMode::Wireframe, false, WHOLE_SCREEN, &mut |fragment| {
+        framebuffer.point(fragment.position.x as i32, fragment.position.y as i32, Vector3::one(), fragment.depth);
+    });
+    framebuffer.resolve();
+
+    assert_eq!(coverage_mask(&mut framebuffer), vec![
+        "########",
+        "#.....#.",
+        "#....#..",
+        "#...#...",
+        "#..#....",
+        "#.#.....",
+        "##......",
+        "#.......",
+    ]);
+}
@@ -0,0 +1,30 @@
+// tests/recorder.rs
+use crate::recorder::{RecordFormat, Recorder};
+
+const DT: f32 = 1.0 / 60.0;
+
+#[test]
+fn skipped_frames_count_towards_the_kept_frame_duration() {
+    let mut recorder = Recorder::new(RecordFormat::Gif, "recordings");
+    recorder.target_fps = 0.0;
+    recorder.frame_skip = 2;
+
+    let kept: Vec<(usize, f32)> = (0..9).filter_map(|i| recorder.keep_frame(DT).map(|duration| (i, duration))).collect();
+    assert_eq!(kept.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 3, 6]);
+    // Uno de cada tres frames: cada uno dura tres frames renderizados, no 1/30 s
+    for (_, duration) in kept {
+        assert!((duration - 3.0 * DT).abs() < 1e-5, "{}", duration);
+    }
+}
+
+#[test]
+fn target_fps_keeps_that_many_frames_per_second_of_real_time() {
+    let mut recorder = Recorder::new(RecordFormat::Gif, "recordings");
+    recorder.target_fps = 15.0;
+
+    let durations: Vec<f32> = (0..120).filter_map(|_| recorder.keep_frame(DT)).collect();
+    assert_eq!(durations.len(), 30);
+    // Lo que duran los frames guardados suma el tiempo grabado
+    let total: f32 = durations.iter().sum();
+    assert!((total - 2.0).abs() < 2.0 / 15.0, "{}", total);
+}
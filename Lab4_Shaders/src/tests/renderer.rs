@@ -0,0 +1,64 @@
+// tests/renderer.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::planets::PlanetRegistry;
+use crate::postprocess::Fxaa;
+use crate::tonemap::{DisplayTransform, ToneMapping};
+use crate::{create_framebuffers, render_scene, FrameSettings, Renderer, Scene};
+use raylib::prelude::*;
+
+fn setup() -> (Camera, Light, SceneMeshes, AsteroidBelt, Comet) {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    (camera, light, meshes, AsteroidBelt::new(7), Comet::new())
+}
+
+#[test]
+fn renderer_without_post_process_matches_render_scene() {
+    let (camera, light, meshes, belt, comet) = setup();
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let frame = FrameSettings { time: 0.5, ..FrameSettings::default() };
+
+    let mut renderer = Renderer::new(64, 48, 1, DisplayTransform::default());
+    let stats = renderer.render(&camera, &light, &scene, &frame);
+
+    let (mut expected, _) = create_framebuffers(64, 48, 1, DisplayTransform::default());
+    let expected_stats = render_scene(&mut expected, &camera, &light, &scene, &frame, &mut FrameArena::default());
+    assert_eq!(renderer.framebuffer.pixels(), expected.pixels());
+    assert_eq!(stats.fragments, expected_stats.fragments);
+}
+
+#[test]
+fn renderer_applies_its_post_process_chain() {
+    let (camera, light, meshes, belt, comet) = setup();
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let frame = FrameSettings::default();
+
+    let mut plain = Renderer::new(64, 48, 1, DisplayTransform::default());
+    plain.render(&camera, &light, &scene, &frame);
+    let mut smoothed = Renderer::new(64, 48, 1, DisplayTransform::default());
+    smoothed.post_process.push(Fxaa, true);
+    smoothed.render(&camera, &light, &scene, &frame);
+
+    assert_ne!(plain.framebuffer.pixels(), smoothed.framebuffer.pixels());
+}
+
+#[test]
+fn renderer_keeps_its_display_settings_across_resizes() {
+    let display = DisplayTransform { tone_mapping: ToneMapping::Reinhard, exposure: 1.5, ..DisplayTransform::default() };
+    let mut renderer = Renderer::new(64, 48, 2, DisplayTransform::default());
+    renderer.set_display(display);
+
+    assert!(!renderer.resize(64, 48));
+    assert!(!renderer.resize(0, 0)); // ventana minimizada
+    assert!(renderer.resize(100, 30));
+    assert_eq!((renderer.framebuffer.width, renderer.framebuffer.height), (100, 30));
+    assert_eq!(renderer.framebuffer.display, display);
+}
@@ -0,0 +1,67 @@
+// tests/resize.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, generate_nucleus, generate_ring, LodMesh, SceneMeshes};
+use crate::planets::PlanetRegistry;
+use crate::tonemap::{DisplayTransform, ToneMapping};
+use crate::{create_framebuffers, render_scene, resize_framebuffers, FrameSettings, Scene};
+use raylib::prelude::*;
+
+#[test]
+fn resize_keeps_display_settings_and_supersampling() {
+    let display = DisplayTransform { tone_mapping: ToneMapping::Reinhard, exposure: 1.5, ..DisplayTransform::default() };
+    let (framebuffer, _) = create_framebuffers(64, 48, 2, display);
+
+    assert!(resize_framebuffers(&framebuffer, 64, 48, 2).is_none());
+    assert!(resize_framebuffers(&framebuffer, 0, 0, 2).is_none()); // ventana minimizada
+
+    let (resized, supersampled) = resize_framebuffers(&framebuffer, 100, 30, 2).unwrap();
+    assert_eq!((resized.width, resized.height), (100, 30));
+    assert_eq!(resized.display, display);
+    let supersampled = supersampled.unwrap();
+    assert_eq!((supersampled.width, supersampled.height), (200, 60));
+}
+
+/// Width and height in pixels of the surface under the screen center, following
+/// the center row and column until the depth buffer is empty
+fn planet_extent(framebuffer: &Framebuffer) -> (usize, usize) {
+    let (width, height) = (framebuffer.width as usize, framebuffer.height as usize);
+    let covered = |x: usize, y: usize| framebuffer.depth()[y * width + x].is_finite();
+    let row = (0..width).filter(|&x| covered(x, height / 2)).count();
+    let column = (0..height).filter(|&y| covered(width / 2, y)).count();
+    (row, column)
+}
+
+#[test]
+fn planet_stays_round_after_changing_the_aspect_ratio() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 2.5), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes {
+        sphere: LodMesh::single(generate_icosphere(3)),
+        ring: generate_ring(0.8, 1.5, 48),
+        nucleus: generate_nucleus(1, 0.35),
+        asteroid: generate_nucleus(1, 0.45),
+    };
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone(); // rocoso, sin anillos
+    let belt = AsteroidBelt::new(7);
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &Comet::new(), belt: &belt, fade_from: None };
+    let frame = FrameSettings { time: 0.0, dt: 0.1, ..FrameSettings::default() };
+
+    let (mut framebuffer, _) = create_framebuffers(96, 64, 1, DisplayTransform::default());
+    let mut arena = FrameArena::default();
+    render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut arena);
+    let (wide_row, wide_column) = planet_extent(&framebuffer);
+
+    // Misma altura, ventana angosta: el planeta mide lo mismo y sigue redondo
+    let (mut framebuffer, _) = resize_framebuffers(&framebuffer, 48, 64, 1).unwrap();
+    render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut arena);
+    let (narrow_row, narrow_column) = planet_extent(&framebuffer);
+
+    assert!(wide_row.abs_diff(wide_column) <= 1, "{} x {}", wide_row, wide_column);
+    assert!(narrow_row.abs_diff(narrow_column) <= 1, "{} x {}", narrow_row, narrow_column);
+    assert!(wide_column.abs_diff(narrow_column) <= 1);
+}
@@ -0,0 +1,151 @@
+// tests/shading.rs
+use crate::arena::FrameArena;
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::fragment::Fragment;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::moons::MoonShader;
+use crate::planets::PlanetRegistry;
+use crate::shaders::{cavity_occlusion, light_triangles, triplanar, Material, ShadingMode};
+use crate::shadow::ShadowMap;
+use crate::tonemap::DisplayTransform;
+use crate::tests::test_globals;
+use crate::uniforms::GlobalUniforms;
+use crate::vertex::Vertex;
+use crate::{create_framebuffers, render_scene, FrameSettings, Scene};
+use raylib::prelude::*;
+
+fn globals(shading: ShadingMode, light: Light) -> GlobalUniforms {
+    GlobalUniforms { camera_position: Vector3::new(0.0, 0.0, 5.0), light, shading, ..test_globals() }
+}
+
+/// A triangle on the lit side of a unit sphere, with its vertex normals pointing outwards
+fn lit_triangle() -> [Vertex; 3] {
+    [Vector3::new(0.0, 0.0, 0.5), Vector3::new(0.3, 0.0, 0.4), Vector3::new(0.0, 0.3, 0.4)].map(|position| {
+        let mut vertex = Vertex::new(position, position.normalized(), Vector2::zero());
+        vertex.transformed_normal = position.normalized();
+        vertex
+    })
+}
+
+#[test]
+fn flat_and_gouraud_store_the_lighting_in_the_vertices() {
+    let light = Light::new(Vector3::new(5.0, 0.0, 1.0));
+    let shadow_map = ShadowMap::new(64, &light, 3.5);
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let model = Matrix::identity();
+
+    let mut phong = [lit_triangle()];
+    light_triangles(&mut phong, &globals(ShadingMode::Phong, light), &planet, &model, &shadow_map);
+    assert!(phong[0].iter().all(|vertex| vertex.light == Vector3::zero()), "phong lights each fragment instead");
+    assert!(phong[0].iter().all(|vertex| vertex.color == Vector3::zero()));
+
+    let mut gouraud = [lit_triangle()];
+    light_triangles(&mut gouraud, &globals(ShadingMode::Gouraud, light), &planet, &model, &shadow_map);
+    let [a, b, c] = &gouraud[0];
+    assert!(a.light.x != b.light.x && b.light.x != c.light.x, "each vertex has its own light");
+    // La luz viene de +X: el vértice más inclinado hacia ella recibe más
+    assert!(b.light.x > a.light.x);
+
+    let mut flat = [lit_triangle()];
+    light_triangles(&mut flat, &globals(ShadingMode::Flat, light), &planet, &model, &shadow_map);
+    let [a, b, c] = &flat[0];
+    assert!(a.light == b.light && b.light == c.light, "one light value per triangle");
+    assert!(a.transformed_normal == b.transformed_normal && b.transformed_normal == c.transformed_normal);
+    assert!(a.light.x > 0.2 && a.light.x <= 1.0);
+    // El color de vértice queda como estaba
+    assert!(a.color == Vector3::zero());
+}
+
+/// Fragment of `triangle` at barycentric `weights`, with its attributes interpolated
+fn fragment_at(triangle: &[Vertex; 3], weights: [f32; 3]) -> Fragment {
+    let mix = |attribute: fn(&Vertex) -> Vector3| triangle.iter().zip(weights).fold(Vector3::zero(), |sum, (vertex, w)| sum + attribute(vertex) * w);
+    let mut fragment = Fragment::new(0.0, 0.0, Vector3::zero(), 0.5, mix(|vertex| vertex.position));
+    fragment.normal = mix(|vertex| vertex.transformed_normal).normalized();
+    fragment.light = mix(|vertex| vertex.light);
+    fragment
+}
+
+#[test]
+fn moons_follow_the_shading_mode() {
+    let light = Light::new(Vector3::new(5.0, 0.0, 1.0));
+    let shadow_map = ShadowMap::new(64, &light, 3.5);
+    let moon = Material::Moon { model: Matrix::identity(), shader: MoonShader::Rocky };
+    let shade = |shading, weights| {
+        let globals = globals(shading, light);
+        let mut triangles = [lit_triangle()];
+        moon.light_vertices(&mut triangles, &globals, &shadow_map);
+        moon.shade(&fragment_at(&triangles[0], weights), &globals, &shadow_map)
+    };
+    let vertex = [0.0, 1.0, 0.0];
+    let center = [1.0 / 3.0; 3];
+
+    // En un vértice Gouraud da la luz exacta y flat la de la cara entera
+    assert!((shade(ShadingMode::Gouraud, vertex) - shade(ShadingMode::Phong, vertex)).length() < 1e-4);
+    assert!(shade(ShadingMode::Flat, vertex).x < shade(ShadingMode::Phong, vertex).x);
+    // En el centro flat coincide con la normal promedio y Gouraud interpola
+    assert!((shade(ShadingMode::Flat, center) - shade(ShadingMode::Phong, center)).length() < 1e-4);
+    assert!((shade(ShadingMode::Gouraud, center) - shade(ShadingMode::Phong, center)).length() > 1e-4);
+}
+
+#[test]
+fn shading_modes_light_the_same_pixels_differently() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 2.5), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(1))); // pocas caras: las facetas se notan
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+
+    let render = |shading| {
+        let (mut framebuffer, _) = create_framebuffers(64, 64, 1, DisplayTransform::default());
+        let frame = FrameSettings { shading, ..FrameSettings::default() };
+        render_scene(&mut framebuffer, &camera, &light, &scene, &frame, &mut FrameArena::default());
+        (framebuffer.pixels(), framebuffer.depth().to_vec())
+    };
+    let (phong, phong_depth) = render(ShadingMode::Phong);
+    let (gouraud, gouraud_depth) = render(ShadingMode::Gouraud);
+    let (flat, flat_depth) = render(ShadingMode::Flat);
+
+    // La cobertura no depende de dónde se ilumina; el color sí
+    assert_eq!(phong_depth, gouraud_depth);
+    assert_eq!(phong_depth, flat_depth);
+    assert_ne!(phong, gouraud);
+    assert_ne!(gouraud, flat);
+}
+
+#[test]
+fn cavity_occlusion_darkens_the_bottom_of_a_bowl() {
+    // Un cuenco alrededor de +Z: altura 0 afuera y -1 en el centro
+    let bowl = |pos: &Vector3| -(1.0 - (pos.normalized() - Vector3::new(0.0, 0.0, 1.0)).length() / 0.2).max(0.0);
+    let occlusion = |pos: Vector3| cavity_occlusion(&(pos * 0.5), 0.05, 0.05, bowl);
+
+    let bottom = occlusion(Vector3::new(0.0, 0.0, 1.0));
+    let outside = occlusion(Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(outside, 1.0);
+    assert!(bottom < 0.7, "{}", bottom);
+    // Sobre la pared del cuenco el lado de afuera está más alto, el de adentro no
+    let wall = occlusion(Vector3::new(0.0, 0.1, 1.0).normalized());
+    assert!(bottom < wall && wall < outside, "{} {} {}", bottom, wall, outside);
+}
+
+#[test]
+fn triplanar_picks_the_plane_the_normal_faces() {
+    // Textura que dice qué coordenadas recibió
+    let sample = |uv: Vector2| Vector3::new(uv.x, uv.y, 0.0);
+    let pos = Vector3::new(0.1, 0.2, 0.3);
+
+    let top = triplanar(&pos, &Vector3::new(0.0, 1.0, 0.0), 4.0, sample);
+    assert_eq!((top.x, top.y), (0.1, 0.3));
+    let side = triplanar(&pos, &Vector3::new(-1.0, 0.0, 0.0), 4.0, sample);
+    assert_eq!((side.x, side.y), (0.3, 0.2));
+
+    // En diagonal se mezclan, y con más nitidez pesa más el eje dominante
+    let tilted = Vector3::new(0.0, 0.8, 0.6);
+    let soft = triplanar(&pos, &tilted, 1.0, sample);
+    let sharp = triplanar(&pos, &tilted, 8.0, sample);
+    assert!(soft.y > 0.2 && soft.y < 0.3, "{}", soft.y);
+    assert!((sharp.y - 0.3).abs() < (soft.y - 0.3).abs());
+}
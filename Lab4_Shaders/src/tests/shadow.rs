@@ -0,0 +1,64 @@
+// tests/shadow.rs
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, SPHERE_RADIUS};
+use crate::shaders::{planet_shadow_on_ring, ring_shadow};
+use crate::shadow::{ShadowMap, SHADOW_MAP_SIZE};
+use raylib::prelude::*;
+
+fn light() -> Light {
+    Light::new(Vector3::new(5.0, 5.0, 5.0))
+}
+
+#[test]
+fn lit_side_of_a_sphere_does_not_shadow_itself() {
+    let light = light();
+    let sphere = generate_icosphere(3);
+    let mut shadow_map = ShadowMap::new(SHADOW_MAP_SIZE, &light, 3.5);
+    shadow_map.render(&sphere, |position| position);
+
+    for vertex in &sphere {
+        let to_light = (light.position - vertex.position).normalized();
+        let n_dot_l = vertex.normal.dot(to_light);
+        if n_dot_l > 0.2 {
+            assert_eq!(shadow_map.visibility(vertex.position, n_dot_l), 1.0, "acne at {:?}", vertex.position);
+        }
+    }
+}
+
+#[test]
+fn occluder_between_light_and_surface_casts_a_shadow() {
+    let light = light();
+    let direction = light.position.normalized();
+    let moon_center = direction * 2.0;
+    let moon = generate_icosphere(2);
+    let mut shadow_map = ShadowMap::new(SHADOW_MAP_SIZE, &light, 3.5);
+    shadow_map.render(&moon, |position| moon_center + position * 0.3);
+
+    // Punto del planeta justo debajo de la luna, y otro del lado opuesto del ecuador
+    let shadowed = direction * SPHERE_RADIUS;
+    let lit = Vector3::new(-SPHERE_RADIUS, 0.0, 0.0);
+    assert_eq!(shadow_map.visibility(shadowed, 1.0), 0.0);
+    assert_eq!(shadow_map.visibility(lit, 0.5), 1.0);
+}
+
+#[test]
+fn rings_shade_the_planet_only_where_the_light_crosses_the_annulus() {
+    let model = Matrix::identity();
+    let light = Vector3::new(20.0, 8.0, 0.0);
+
+    // Bajo el plano: el rayo hacia la luz cruza los anillos a radio ~1.24
+    assert!(ring_shadow(Vector3::new(0.3, -0.4, 0.0), light, &model) < 0.5);
+    // Sobre el plano la luz llega directo; por el hueco interior también
+    assert_eq!(ring_shadow(Vector3::new(0.3, 0.4, 0.0), light, &model), 1.0);
+    assert_eq!(ring_shadow(Vector3::new(0.0, -0.5, 0.0), Vector3::new(0.0, -10.0, 0.0), &model), 1.0);
+}
+
+#[test]
+fn planet_shades_the_rings_behind_it() {
+    let model = Matrix::identity();
+    let light = Vector3::new(10.0, 0.0, 0.0);
+
+    assert!(planet_shadow_on_ring(Vector3::new(-1.0, 0.0, 0.2), light, &model) < 0.2);
+    assert_eq!(planet_shadow_on_ring(Vector3::new(-1.0, 0.0, 1.2), light, &model), 1.0);
+    assert_eq!(planet_shadow_on_ring(Vector3::new(1.0, 0.0, 0.2), light, &model), 1.0);
+}
@@ -0,0 +1,132 @@
+// tests/split.rs
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::light::Light;
+use crate::mesh::{generate_icosphere, LodMesh, SceneMeshes};
+use crate::planets::{PlanetRegistry, PlanetType};
+use crate::postprocess::{DepthOfField, Fxaa};
+use crate::shaders::ShadingMode;
+use crate::tonemap::DisplayTransform;
+use crate::{FrameSettings, Renderer, Scene, Viewport};
+use raylib::prelude::*;
+
+fn column(pixels: &[Color], width: i32, x: i32) -> Vec<Color> {
+    pixels.iter().skip(x as usize).step_by(width as usize).copied().collect()
+}
+
+#[test]
+fn each_half_matches_a_render_of_its_own_size() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    let planet = PlanetRegistry::with_builtins().get(0).unwrap().clone();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let phong = FrameSettings { time: 0.5, ..FrameSettings::default() };
+    let flat = FrameSettings { shading: ShadingMode::Flat, ..phong };
+
+    let mut half = Renderer::new(32, 48, 1, DisplayTransform::default());
+    half.render(&camera, &light, &scene, &phong);
+    let expected = half.framebuffer.pixels();
+
+    let mut split = Renderer::new(64, 48, 1, DisplayTransform::default());
+    let stats = split.render_split(&camera, &light, (&scene, &phong), (&scene, &flat));
+    let pixels = split.framebuffer.pixels();
+    assert!(stats.iter().all(|stats| stats.fragments_written > 0));
+
+    // La mitad izquierda es la imagen de 32 x 48; la derecha, con sombreado plano, no.
+    // La columna 32 es la línea divisoria.
+    for x in 0..32 {
+        assert_eq!(column(&pixels, 64, x), column(&expected, 32, x), "column {}", x);
+    }
+    assert!((33..64).any(|x| column(&pixels, 64, x) != column(&expected, 32, x - 32)));
+}
+
+#[test]
+fn a_scissored_clear_leaves_the_rest_of_the_frame() {
+    let mut renderer = Renderer::new(16, 8, 1, DisplayTransform::default());
+    let framebuffer = &mut renderer.framebuffer;
+    framebuffer.set_current_color(Color::WHITE);
+    for x in 0..16 {
+        framebuffer.set_pixel(x, 3);
+    }
+    framebuffer.set_scissor(Some(Viewport::RIGHT_HALF.pixels(16, 8)));
+    framebuffer.clear();
+    framebuffer.set_pixel(2, 5); // fuera del scissor: se ignora
+    framebuffer.set_scissor(None);
+
+    let pixels = framebuffer.pixels();
+    assert!((0..8).all(|x| pixels[3 * 16 + x] == Color::WHITE));
+    assert!((8..16).all(|x| pixels[3 * 16 + x] != Color::WHITE));
+    assert_ne!(pixels[5 * 16 + 2], Color::WHITE);
+}
+
+fn camera() -> Camera {
+    Camera::new(Vector3::new(0.0, 0.0, 3.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0))
+}
+
+fn rocky() -> PlanetType {
+    PlanetRegistry::with_builtins().get(0).unwrap().clone()
+}
+
+/// Renderer with a post-process chain that reads depth and neighbouring pixels
+fn post_processed(width: i32, height: i32) -> Renderer {
+    let mut renderer = Renderer::new(width, height, 1, DisplayTransform::default());
+    renderer.post_process.push(DepthOfField { focus_distance: 1.0, ..DepthOfField::default() }, true);
+    renderer.post_process.push(Fxaa, true);
+    renderer
+}
+
+#[test]
+fn each_half_is_post_processed_on_its_own() {
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(2)));
+    let planet = rocky();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let phong = FrameSettings { time: 0.5, ..FrameSettings::default() };
+    let flat = FrameSettings { shading: ShadingMode::Flat, ..phong };
+
+    let half = |frame: &FrameSettings| {
+        let mut renderer = post_processed(32, 48);
+        renderer.render(&camera(), &light, &scene, frame);
+        renderer.framebuffer.pixels()
+    };
+    let (left, right) = (half(&phong), half(&flat));
+    let mut split = post_processed(64, 48);
+    split.render_split(&camera(), &light, (&scene, &phong), (&scene, &flat));
+    let pixels = split.framebuffer.pixels();
+
+    // El desenfoque de cada mitad usa su propia proyección y no toma píxeles de la otra
+    for x in 0..32 {
+        assert_eq!(column(&pixels, 64, x), column(&left, 32, x), "left column {}", x);
+    }
+    for x in 33..64 {
+        assert_eq!(column(&pixels, 64, x), column(&right, 32, x - 32), "right column {}", x);
+    }
+    assert!((0..32).any(|x| column(&left, 32, x) != column(&right, 32, x)));
+}
+
+#[test]
+fn each_half_gets_its_own_depth_panel() {
+    let light = Light::new(Vector3::new(5.0, 5.0, 5.0));
+    let meshes = SceneMeshes::new(LodMesh::single(generate_icosphere(1)));
+    let planet = rocky();
+    let (comet, belt) = (Comet::new(), AsteroidBelt::new(7));
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let plain = FrameSettings::default();
+    let overlay = FrameSettings { depth_overlay: true, ..plain };
+
+    let mut renderer = Renderer::new(480, 120, 1, DisplayTransform::default());
+    renderer.render_split(&camera(), &light, (&scene, &plain), (&scene, &plain));
+    let before = renderer.framebuffer.pixels();
+    renderer.render_split(&camera(), &light, (&scene, &overlay), (&scene, &overlay));
+    let after = renderer.framebuffer.pixels();
+
+    // Un panel de 200 x 90 en la esquina inferior derecha de cada mitad de 240 x 120
+    let changed: Vec<(usize, usize)> = (0..before.len()).filter(|&i| before[i] != after[i]).map(|i| (i % 480, i / 480)).collect();
+    assert!(changed.iter().all(|&(x, y)| y >= 20 && ((30..230).contains(&x) || (270..470).contains(&x))));
+    assert!(changed.iter().any(|&(x, _)| x < 240));
+    assert!(changed.iter().any(|&(x, _)| x >= 240));
+}
@@ -0,0 +1,50 @@
+// tests/terminal.rs
+use crate::display::Backend;
+use crate::terminal::{downsample, write_cells};
+use raylib::prelude::*;
+
+#[test]
+fn downsample_averages_each_block() {
+    // 4x2 píxeles: mitad izquierda negra y blanca, mitad derecha roja
+    let black = Color::new(0, 0, 0, 255);
+    let white = Color::new(255, 255, 255, 255);
+    let red = Color::new(200, 0, 0, 255);
+    let pixels = [black, white, red, red, white, black, red, red];
+
+    let cells = downsample(&pixels, 4, 2, 2, 1);
+
+    assert_eq!(cells.len(), 2);
+    assert_eq!((cells[0].r, cells[0].g, cells[0].b), (127, 127, 127));
+    assert_eq!((cells[1].r, cells[1].g, cells[1].b), (200, 0, 0));
+}
+
+#[test]
+fn downsample_to_more_cells_than_pixels_repeats_pixels() {
+    let pixels = [Color::new(10, 20, 30, 255)];
+    let cells = downsample(&pixels, 1, 1, 3, 2);
+    assert_eq!(cells.len(), 6);
+    assert!(cells.iter().all(|cell| (cell.r, cell.g, cell.b) == (10, 20, 30)));
+}
+
+#[test]
+fn write_cells_prints_one_half_block_per_cell() {
+    // 3 columnas y 2 filas de celdas = 3x4 píxeles
+    let cells = vec![Color::new(0, 64, 128, 255); 12];
+    let mut out = Vec::new();
+
+    write_cells(&mut out, &cells, 3, 2).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.matches('▀').count(), 6);
+    // Mismo color en toda la fila: se fija una sola vez por fila
+    assert_eq!(text.matches("38;2;0;64;128").count(), 2);
+}
+
+#[test]
+fn backend_names_parse() {
+    assert_eq!("window".parse::<Backend>(), Ok(Backend::Window));
+    assert_eq!("raylib".parse::<Backend>(), Ok(Backend::Window));
+    assert_eq!("terminal".parse::<Backend>(), Ok(Backend::Terminal));
+    assert!("braille".parse::<Backend>().is_err());
+    assert_eq!(Backend::default(), Backend::Window);
+}
@@ -0,0 +1,90 @@
+// tests/tiles.rs
+use crate::tiles::{Tile, TileBins, TILE_SIZE};
+use crate::triangle::{rasterize, RenderMode};
+use crate::vertex::Vertex;
+use raylib::prelude::*;
+
+fn screen_triangle(points: [(f32, f32); 3]) -> [Vertex; 3] {
+    points.map(|(x, y)| {
+        let mut vertex = Vertex::new(Vector3::new(x, y, 0.5), Vector3::new(0.0, 0.0, 1.0), Vector2::zero());
+        vertex.set_transformed(Vector3::new(x, y, 0.5), Vector3::new(0.0, 0.0, 1.0));
+        vertex
+    })
+}
+
+/// Pixels covered by rasterizing every tile of the bins, sorted
+fn tiled_pixels(triangles: &[[Vertex; 3]], width: i32, height: i32, mode: RenderMode) -> Vec<(i32, i32)> {
+    let mut pixels = Vec::new();
+    let mut bins = TileBins::default();
+    bins.bin(triangles, width, height);
+    for (tile, triangle_ids) in bins.iter() {
+        for &triangle_id in triangle_ids {
+            rasterize(&triangles[triangle_id], mode, false, tile, &mut |fragment| {
+                pixels.push((fragment.position.x as i32, fragment.position.y as i32));
+            });
+        }
+    }
+    pixels.sort();
+    pixels
+}
+
+#[test]
+fn triangle_is_binned_into_every_tile_its_bounds_touch() {
+    let size = TILE_SIZE as f32;
+    let tri = screen_triangle([(size * 0.5, size * 0.5), (size * 2.5, size * 0.5), (size * 0.5, size * 1.5)]);
+    let mut bins = TileBins::default();
+    bins.bin(&[tri], TILE_SIZE * 4, TILE_SIZE * 4);
+
+    let tiles: Vec<(i32, i32)> = bins.iter().map(|(tile, _)| (tile.x0 / TILE_SIZE, tile.y0 / TILE_SIZE)).collect();
+    assert_eq!(tiles, vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+}
+
+#[test]
+fn offscreen_triangles_are_not_binned() {
+    let tri = screen_triangle([(-40.0, -40.0), (-10.0, -40.0), (-40.0, -10.0)]);
+    let mut bins = TileBins::default();
+    bins.bin(&[tri], 64, 64);
+    assert_eq!(bins.iter().count(), 0);
+}
+
+#[test]
+fn tiles_at_the_edge_are_cut_to_the_screen() {
+    let tri = screen_triangle([(0.0, 0.0), (50.0, 0.0), (0.0, 50.0)]);
+    let mut bins = TileBins::default();
+    bins.bin(&[tri], 40, 40);
+    let last = bins.iter().last().unwrap().0;
+    assert_eq!((last.x1, last.y1), (40, 40));
+}
+
+#[test]
+fn tiled_rasterization_matches_whole_triangle() {
+    let (width, height) = (100, 80);
+    let triangles = [
+        screen_triangle([(3.0, 5.0), (91.0, 17.0), (40.0, 75.0)]),
+        screen_triangle([(-20.0, 30.0), (60.0, 40.0), (10.0, 120.0)]), // cruza dos bordes
+    ];
+
+    for mode in [RenderMode::Solid, RenderMode::Wireframe] {
+        let screen = Tile { x0: 0, y0: 0, x1: width, y1: height };
+        let mut expected = Vec::new();
+        for tri in &triangles {
+            rasterize(tri, mode, false, screen, &mut |fragment| {
+                expected.push((fragment.position.x as i32, fragment.position.y as i32));
+            });
+        }
+        expected.sort();
+
+        // Cada píxel sale una sola vez aunque el triángulo cruce varios tiles
+        assert_eq!(tiled_pixels(&triangles, width, height, mode), expected, "{:?}", mode);
+    }
+}
+
+#[test]
+fn rebinning_forgets_the_previous_frame() {
+    let mut bins = TileBins::default();
+    bins.bin(&[screen_triangle([(0.0, 0.0), (60.0, 0.0), (0.0, 60.0)])], 64, 64);
+    bins.bin(&[screen_triangle([(40.0, 40.0), (60.0, 40.0), (40.0, 60.0)])], 64, 64);
+
+    let tiles: Vec<(i32, i32)> = bins.iter().map(|(tile, _)| (tile.x0, tile.y0)).collect();
+    assert_eq!(tiles, vec![(32, 32)]);
+}
@@ -0,0 +1,58 @@
+// tests/time.rs
+use crate::time::{TimeController, STEP_DT};
+
+#[test]
+fn speed_scales_and_reverses_the_frame_delta() {
+    let mut clock = TimeController::default();
+    clock.faster();
+    assert!((clock.advance(0.5) - 1.0).abs() < 1e-6);
+
+    clock.toggle_reverse();
+    assert!((clock.advance(0.5) + 1.0).abs() < 1e-6);
+    assert!(clock.time.abs() < 1e-6);
+}
+
+#[test]
+fn speed_stays_within_the_multiplier_range() {
+    let mut clock = TimeController::default();
+    for _ in 0..20 {
+        clock.faster();
+    }
+    assert_eq!(clock.speed(), 100.0);
+    for _ in 0..20 {
+        clock.slower();
+    }
+    assert_eq!(clock.speed(), 0.1);
+}
+
+#[test]
+fn pause_freezes_time_and_steps_advance_single_frames() {
+    let mut clock = TimeController::default();
+    clock.advance(1.0);
+    clock.toggle_pause();
+    assert_eq!(clock.advance(1.0), 0.0);
+    assert_eq!(clock.time, 1.0);
+
+    // Los pasos se consumen en el siguiente frame, sin importar el tiempo real
+    clock.step(1);
+    clock.step(1);
+    assert!((clock.advance(0.3) - 2.0 * STEP_DT).abs() < 1e-6);
+    assert_eq!(clock.advance(0.3), 0.0);
+
+    clock.step(-1);
+    clock.advance(0.3);
+    assert!((clock.time - (1.0 + STEP_DT)).abs() < 1e-6);
+    assert!(clock.paused);
+}
+
+#[test]
+fn slow_motion_caps_the_speed_but_keeps_the_direction() {
+    let mut clock = TimeController::default();
+    clock.faster();
+    clock.toggle_reverse();
+    clock.slow_motion = true;
+    assert_eq!(clock.speed(), -0.25);
+
+    clock.slow_motion = false;
+    assert_eq!(clock.speed(), -2.0);
+}
@@ -0,0 +1,79 @@
+// tests/tonemap.rs
+use crate::framebuffer::Framebuffer;
+use crate::tonemap::{ColorSpace, DisplayTransform, ToneMapping};
+use raylib::prelude::*;
+
+#[test]
+fn operators_are_monotonic_and_bounded() {
+    for tone_mapping in [ToneMapping::Clamp, ToneMapping::Reinhard, ToneMapping::Aces] {
+        let mut previous = tone_mapping.apply(0.0);
+        assert!(previous.abs() < 0.01, "{:?} should map black to black", tone_mapping);
+        for step in 1..200 {
+            let value = tone_mapping.apply(step as f32 * 0.1);
+            assert!(value >= previous && value <= 1.0, "{:?} at {}", tone_mapping, step as f32 * 0.1);
+            previous = value;
+        }
+    }
+}
+
+#[test]
+fn reinhard_never_saturates() {
+    assert_eq!(ToneMapping::Reinhard.apply(1.0), 0.5);
+    assert!(ToneMapping::Reinhard.apply(50.0) < 1.0);
+}
+
+#[test]
+fn gamma_brightens_mid_tones() {
+    let display = DisplayTransform { gamma: 2.2, ..DisplayTransform::linear() };
+    let mid = display.to_display(Vector3::new(0.2, 0.2, 0.2), 0, 0);
+    // 0.2^(1/2.2) ≈ 0.48
+    assert_eq!(mid.r, 122);
+}
+
+#[test]
+fn flat_colors_round_trip_through_the_linear_pipeline() {
+    let display = DisplayTransform { gamma: 2.2, ..DisplayTransform::linear() };
+    for authored in [0.05, 0.2, 0.5, 0.8, 1.0] {
+        let linear = ColorSpace::Linear.decode(Vector3::new(authored, authored, authored));
+        // En lineal los medios tonos valen mucho menos: la luz cae más suave al codificar
+        assert!(linear.x <= authored);
+        let shown = display.to_display(linear, 0, 0).r as f32;
+        assert!((shown - authored * 255.0).abs() <= 1.0, "{} shown as {}", authored, shown);
+    }
+}
+
+#[test]
+fn srgb_space_uses_colors_as_authored() {
+    let color = Vector3::new(0.2, 0.5, 0.8);
+    assert_eq!(ColorSpace::Srgb.decode(color), color);
+
+    // Sin decodificar tampoco se codifica: la gamma se ignora
+    let display = DisplayTransform { gamma: 2.2, color_space: ColorSpace::Srgb, ..DisplayTransform::linear() };
+    assert_eq!(display.to_display(Vector3::new(0.2, 0.2, 0.2), 0, 0).r, 51);
+}
+
+#[test]
+fn resolve_keeps_background_and_tone_maps_fragments() {
+    let background = Color::new(68, 29, 102, 255);
+    let mut framebuffer = Framebuffer::new(2, 1);
+    framebuffer.set_background_color(background);
+    framebuffer.clear();
+    framebuffer.point(0, 0, Vector3::new(4.0, 4.0, 4.0), 0.5);
+    framebuffer.resolve();
+
+    let bright = framebuffer.get_pixel_color(0, 0).unwrap();
+    assert!(bright.r > 240 && bright.r == bright.g);
+    assert_eq!(framebuffer.get_pixel_color(1, 0), Some(background));
+}
+
+#[test]
+fn dithering_averages_to_the_exact_value_over_a_tile() {
+    let display = DisplayTransform { dither: true, ..DisplayTransform::linear() };
+    // 100.25 / 255: sin dithering siempre daría 100
+    let value = 100.25 / 255.0;
+    let levels: Vec<u32> = (0..16).map(|i| display.to_display(Vector3::new(value, value, value), i % 4, i / 4).r as u32).collect();
+
+    assert!(levels.iter().all(|&level| level == 100 || level == 101));
+    let average = levels.iter().sum::<u32>() as f32 / 16.0;
+    assert!((average - 100.25).abs() < 0.07, "average {}", average);
+}
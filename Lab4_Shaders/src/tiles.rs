@@ -0,0 +1,86 @@
+// tiles.rs
+// Rasterización por tiles: los triángulos se reparten en bloques de pantalla
+// según su bounding box y cada bloque se rasteriza y sombrea directamente contra
+// el framebuffer, sin juntar todos los fragmentos del frame en un Vec. Los tiles
+// no comparten píxeles, así que se pueden procesar en paralelo.
+use crate::vertex::Vertex;
+
+pub const TILE_SIZE: i32 = 32;
+
+/// Pixel rectangle `[x0, x1) x [y0, y1)`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tile {
+    pub x0: i32,
+    pub y0: i32,
+    pub x1: i32,
+    pub y1: i32,
+}
+
+impl Tile {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x0 && x < self.x1 && y >= self.y0 && y < self.y1
+    }
+}
+
+/// Triangle indices grouped by the screen tiles their bounding boxes overlap
+#[derive(Default)]
+pub struct TileBins {
+    tiles_x: i32,
+    width: i32,
+    height: i32,
+    bins: Vec<Vec<usize>>,
+}
+
+impl TileBins {
+    /// Re-bins `triangles` for a `width` x `height` screen; the per-tile lists
+    /// are cleared but keep their capacity
+    pub fn bin(&mut self, triangles: &[[Vertex; 3]], width: i32, height: i32) {
+        let tiles_x = (width + TILE_SIZE - 1) / TILE_SIZE;
+        let tiles_y = (height + TILE_SIZE - 1) / TILE_SIZE;
+        self.tiles_x = tiles_x;
+        self.width = width;
+        self.height = height;
+        self.bins.resize_with((tiles_x * tiles_y).max(0) as usize, Vec::new);
+        self.bins.iter_mut().for_each(Vec::clear);
+
+        for (index, tri) in triangles.iter().enumerate() {
+            let (a, b, c) = (tri[0].transformed_position, tri[1].transformed_position, tri[2].transformed_position);
+            // Mismo rango de píxeles que recorre triangle()
+            let min_x = a.x.min(b.x).min(c.x).floor() as i32;
+            let max_x = a.x.max(b.x).max(c.x).ceil() as i32;
+            let min_y = a.y.min(b.y).min(c.y).floor() as i32;
+            let max_y = a.y.max(b.y).max(c.y).ceil() as i32;
+            if max_x < 0 || max_y < 0 || min_x >= width || min_y >= height {
+                continue;
+            }
+
+            let (first_x, last_x) = ((min_x.max(0)) / TILE_SIZE, max_x.min(width - 1) / TILE_SIZE);
+            let (first_y, last_y) = ((min_y.max(0)) / TILE_SIZE, max_y.min(height - 1) / TILE_SIZE);
+            for tile_y in first_y..=last_y {
+                for tile_x in first_x..=last_x {
+                    self.bins[(tile_y * tiles_x + tile_x) as usize].push(index);
+                }
+            }
+        }
+    }
+
+    pub fn tile(&self, index: usize) -> Tile {
+        let tile_x = index as i32 % self.tiles_x;
+        let tile_y = index as i32 / self.tiles_x;
+        Tile {
+            x0: tile_x * TILE_SIZE,
+            y0: tile_y * TILE_SIZE,
+            x1: ((tile_x + 1) * TILE_SIZE).min(self.width),
+            y1: ((tile_y + 1) * TILE_SIZE).min(self.height),
+        }
+    }
+
+    /// Every tile that has at least one triangle, with its triangle indices
+    pub fn iter(&self) -> impl Iterator<Item = (Tile, &[usize])> {
+        self.bins
+            .iter()
+            .enumerate()
+            .filter(|(_, bin)| !bin.is_empty())
+            .map(|(index, bin)| (self.tile(index), bin.as_slice()))
+    }
+}
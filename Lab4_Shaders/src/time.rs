@@ -0,0 +1,95 @@
+// time.rs
+// Reloj de la simulación: separa el tiempo de las animaciones (órbitas,
+// tormentas, pulsos) del tiempo real para poder pausarlo, acelerarlo,
+// invertirlo o avanzarlo de a un frame y revisar cualquier instante.
+
+// Multiplicadores disponibles; la velocidad normal es SPEEDS[NORMAL_SPEED]
+const SPEEDS: [f32; 10] = [0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0];
+const NORMAL_SPEED: usize = 3;
+
+// Tope de velocidad en cámara lenta (p. ej. durante un eclipse)
+const SLOW_MOTION_SPEED: f32 = 0.25;
+
+// Paso de un frame a 60 fps, en tiempo de simulación
+pub const STEP_DT: f32 = 1.0 / 60.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeController {
+    pub time: f32, // tiempo de simulación en segundos
+    pub paused: bool,
+    pub reversed: bool,
+    pub slow_motion: bool, // limita la velocidad a SLOW_MOTION_SPEED mientras esté activo
+    speed_index: usize,
+    pending_steps: i32, // frames pedidos mientras está en pausa (negativos hacia atrás)
+}
+
+impl Default for TimeController {
+    fn default() -> Self {
+        TimeController { time: 0.0, paused: false, reversed: false, slow_motion: false, speed_index: NORMAL_SPEED, pending_steps: 0 }
+    }
+}
+
+impl TimeController {
+    /// Signed speed multiplier; negative while reversed
+    pub fn speed(&self) -> f32 {
+        let mut speed = SPEEDS[self.speed_index];
+        if self.slow_motion {
+            speed = speed.min(SLOW_MOTION_SPEED);
+        }
+        if self.reversed { -speed } else { speed }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.pending_steps = 0;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    /// Back to 1x forward
+    pub fn reset_speed(&mut self) {
+        self.speed_index = NORMAL_SPEED;
+        self.reversed = false;
+    }
+
+    /// Queues `frames` single steps of `STEP_DT` (backwards if negative); pauses
+    /// first so the step can be inspected
+    pub fn step(&mut self, frames: i32) {
+        self.paused = true;
+        self.pending_steps += frames;
+    }
+
+    /// Advances the clock by `real_dt` seconds of wall time and returns the
+    /// signed simulation delta of this frame (0 while paused without steps)
+    pub fn advance(&mut self, real_dt: f32) -> f32 {
+        let dt = if self.paused {
+            let steps = std::mem::take(&mut self.pending_steps);
+            steps as f32 * STEP_DT
+        } else {
+            real_dt * self.speed()
+        };
+        self.time += dt;
+        dt
+    }
+
+    /// Short status for the HUD, e.g. "x2 (pausa)"
+    pub fn label(&self) -> String {
+        let mut label = format!("x{}", self.speed());
+        if self.paused {
+            label.push_str(" (pausa)");
+        } else if self.slow_motion {
+            label.push_str(" (lento)");
+        }
+        label
+    }
+}
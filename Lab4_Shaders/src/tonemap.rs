@@ -0,0 +1,157 @@
+// tonemap.rs
+// Conversión del color HDR lineal del framebuffer a valores de pantalla
+use crate::math::*;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToneMapping {
+    Clamp,    // recorta a 0..1 (como antes de tener HDR)
+    Reinhard, // x / (1 + x): nunca satura, pero aplana los brillos
+    Aces,     // curva fílmica de ACES (aproximación de Narkowicz)
+}
+
+impl ToneMapping {
+    /// Next operator, for cycling with a key
+    pub fn next(self) -> Self {
+        match self {
+            ToneMapping::Clamp => ToneMapping::Reinhard,
+            ToneMapping::Reinhard => ToneMapping::Aces,
+            ToneMapping::Aces => ToneMapping::Clamp,
+        }
+    }
+
+    /// Maps one linear HDR channel (0..inf) to 0..1
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            ToneMapping::Clamp => x.min(1.0),
+            ToneMapping::Reinhard => x / (1.0 + x),
+            ToneMapping::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+impl FromStr for ToneMapping {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "clamp" => Ok(ToneMapping::Clamp),
+            "reinhard" => Ok(ToneMapping::Reinhard),
+            "aces" => Ok(ToneMapping::Aces),
+            _ => Err(format!("invalid tone mapping: {} (expected clamp, reinhard or aces)", name)),
+        }
+    }
+}
+
+// Curva de sRGB aproximada con una potencia: la misma al decodificar los colores
+// de la paleta y al codificar la salida, así un color plano se ve tal como se escribió
+pub const SRGB_GAMMA: f32 = 2.2;
+
+/// Space the shaders light and blend in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorSpace {
+    #[default]
+    Linear, // la paleta se decodifica de sRGB a lineal y la salida se codifica con la gamma
+    Srgb,   // la paleta se usa tal cual y la salida no se corrige (como el lab original)
+}
+
+impl ColorSpace {
+    pub fn toggle(self) -> Self {
+        match self {
+            ColorSpace::Linear => ColorSpace::Srgb,
+            ColorSpace::Srgb => ColorSpace::Linear,
+        }
+    }
+
+    /// Converts an authored (sRGB) color to the space the shaders work in
+    pub fn decode(self, color: Vector3) -> Vector3 {
+        match self {
+            ColorSpace::Linear => Vector3::new(
+                color.x.max(0.0).powf(SRGB_GAMMA),
+                color.y.max(0.0).powf(SRGB_GAMMA),
+                color.z.max(0.0).powf(SRGB_GAMMA),
+            ),
+            ColorSpace::Srgb => color,
+        }
+    }
+}
+
+impl FromStr for ColorSpace {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "linear" => Ok(ColorSpace::Linear),
+            "srgb" => Ok(ColorSpace::Srgb),
+            _ => Err(format!("invalid color space: {} (expected linear or srgb)", name)),
+        }
+    }
+}
+
+/// How the HDR buffer is turned into 8-bit display colors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayTransform {
+    pub tone_mapping: ToneMapping,
+    pub exposure: f32, // multiplicador antes de la curva
+    pub gamma: f32,    // 2.2 para pantallas normales, 1.0 la desactiva
+    pub dither: bool,  // ruido ordenado al cuantizar, contra el banding en degradados suaves
+    pub color_space: ColorSpace, // en Srgb el buffer ya está en colores de pantalla y la gamma no se aplica
+}
+
+// Matriz de Bayer 4x4: umbrales repartidos para que cada bloque promedie el valor exacto
+const BAYER_4X4: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        DisplayTransform {
+            tone_mapping: ToneMapping::Aces,
+            exposure: 1.0,
+            gamma: SRGB_GAMMA,
+            dither: true,
+            color_space: ColorSpace::Linear,
+        }
+    }
+}
+
+impl DisplayTransform {
+    /// Values written as they are (clamped to 0..1), for debug views
+    pub fn linear() -> Self {
+        DisplayTransform {
+            tone_mapping: ToneMapping::Clamp,
+            exposure: 1.0,
+            gamma: 1.0,
+            dither: false,
+            color_space: ColorSpace::Linear,
+        }
+    }
+
+    /// Exposure, tone curve and gamma (in linear space only), then quantization to
+    /// u8. With `dither` the truncation threshold varies with the pixel position
+    /// (`x`, `y`), so a smooth gradient turns into a fine pattern instead of visible bands.
+    pub fn to_display(self, color: Vector3, x: i32, y: i32) -> Color {
+        let threshold = if self.dither {
+            (BAYER_4X4[(y & 3) as usize][(x & 3) as usize] + 0.5) / 16.0
+        } else {
+            0.0
+        };
+        let gamma = match self.color_space {
+            ColorSpace::Linear => self.gamma,
+            ColorSpace::Srgb => 1.0,
+        };
+        let channel = |value: f32| {
+            let mapped = self.tone_mapping.apply(value * self.exposure);
+            let corrected = if gamma == 1.0 { mapped } else { mapped.powf(1.0 / gamma) };
+            (corrected.clamp(0.0, 1.0) * 255.0 + threshold).min(255.0) as u8
+        };
+        Color::new(channel(color.x), channel(color.y), channel(color.z), 255)
+    }
+}
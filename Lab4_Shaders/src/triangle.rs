@@ -1,104 +1,291 @@
-use crate::fragment::Fragment;
+use crate::depth::DepthMode;
+use crate::fragment::{Fragment, FULL_COVERAGE, MSAA_OFFSETS};
 use crate::vertex::Vertex;
-use crate::light::Light;
-use raylib::prelude::Vector3;
+use crate::math::{Vector2, Vector3};
+use crate::stats::RenderStats;
+use crate::tiles::Tile;
+use serde::Serialize;
 
-fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Vertex)  -> (f32, f32, f32) {
-    let a_x = a.transformed_position.x;   
-    let a_y = a.transformed_position.y;
-    let b_x = b.transformed_position.x;
-    let b_y = b.transformed_position.y;
-    let c_x = c.transformed_position.x;
-    let c_y = c.transformed_position.y;
+/// How assembled triangles are turned into fragments
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RenderMode {
+    #[default]
+    Solid,     // triángulos rellenos
+    Wireframe, // solo los tres bordes de cada triángulo
+}
 
-    let denom = (b_y - c_y) * (a_x - c_x) + (c_x - b_x) * (a_y - c_y);
+// Los vértices se redondean a 1/256 de píxel y las funciones de borde se evalúan en
+// enteros: dos triángulos que comparten un borde obtienen valores exactamente opuestos
+// en cada centro de píxel, así la regla top-left decide sin empates por redondeo
+const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL: i64 = 1 << SUBPIXEL_BITS;
 
-    if denom.abs() < 1e-10  {
-        return (-1.0, -1.0, -1.0);
-    }
-    
-    let w1 = ((b_y - c_y) * (p_x - c_x) + (c_x - b_x) * (p_y - c_y)) / denom;
-    let w2 = ((c_y - a_y) * (p_x - c_x) + (a_x - c_x) * (p_y - c_y)) / denom;
-    let w3 = 1.0 - w1 - w2;
+type FixedPoint = (i64, i64);
 
-    (w1, w2, w3)
+fn to_fixed(position: Vector3) -> FixedPoint {
+    ((position.x * SUBPIXEL as f32).round() as i64, (position.y * SUBPIXEL as f32).round() as i64)
 }
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, light: &Light) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
+/// Twice the signed area of (a, b, p): positive when p is on the inner side of
+/// the edge a -> b of a triangle with positive area
+fn edge_function(a: FixedPoint, b: FixedPoint, p: FixedPoint) -> i64 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
 
-    let base_color = Vector3::new(0.5, 0.5, 0.5);
+/// Top-left fill rule (y grows downwards): a pixel center exactly on an edge
+/// belongs to the triangle only if that edge is a top edge (horizontal, with
+/// the triangle below) or a left edge, so a shared edge is drawn exactly once
+fn is_top_left(a: FixedPoint, b: FixedPoint) -> bool {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    dy < 0 || (dy == 0 && dx > 0)
+}
+
+/// Weights of the three vertices corrected for perspective: attributes vary
+/// linearly across the surface in camera space, not on screen, so each screen
+/// weight is divided by its vertex's clip w and the result renormalized
+fn perspective_weights(w1: f32, w2: f32, w3: f32, v1: &Vertex, v2: &Vertex, v3: &Vertex) -> (f32, f32, f32) {
+    let p1 = w1 / v1.clip_w;
+    let p2 = w2 / v2.clip_w;
+    let p3 = w3 / v3.clip_w;
+    let sum = p1 + p2 + p3;
+    if sum.abs() < 1e-10 || !sum.is_finite() {
+        return (w1, w2, w3);
+    }
+    (p1 / sum, p2 / sum, p3 / sum)
+}
 
+/// Rasterizes the part of a triangle inside `tile`, handing each fragment to `emit`
+/// with the vertex attributes (position, normal, UV, color, light) interpolated at its center.
+/// Coverage uses half-space tests with the top-left rule, so triangles that share an
+/// edge cover every pixel along it exactly once.
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, tile: Tile, emit: &mut impl FnMut(Fragment)) {
+    cover(v1, v2, v3, tile, [(0, 0)], &mut |mut fragment| {
+        fragment.coverage = FULL_COVERAGE;
+        emit(fragment);
+    });
+}
+
+/// Like `triangle`, but tests the four MSAA sample positions of each pixel: a
+/// fragment is emitted when any of them is inside, with `coverage` telling which.
+/// It is still shaded once, at the pixel center.
+pub fn triangle_msaa(v1: &Vertex, v2: &Vertex, v3: &Vertex, tile: Tile, emit: &mut impl FnMut(Fragment)) {
+    let samples = MSAA_OFFSETS.map(|(x, y)| ((x * SUBPIXEL as f32) as i64, (y * SUBPIXEL as f32) as i64));
+    cover(v1, v2, v3, tile, samples, emit);
+}
+
+/// Shared body of `triangle` and `triangle_msaa`: `samples` are sub-pixel offsets from
+/// each pixel center and bit i of a fragment's `coverage` is set when sample i is inside
+fn cover<const N: usize>(v1: &Vertex, v2: &Vertex, v3: &Vertex, tile: Tile, samples: [(i64, i64); N], emit: &mut impl FnMut(Fragment)) {
     let min_x = v1.transformed_position.x.min(v2.transformed_position.x).min(v3.transformed_position.x).floor() as i32;
     let max_x = v1.transformed_position.x.max(v2.transformed_position.x).max(v3.transformed_position.x).ceil() as i32;
     let min_y = v1.transformed_position.y.min(v2.transformed_position.y).min(v3.transformed_position.y).floor() as i32;
     let max_y = v1.transformed_position.y.max(v2.transformed_position.y).max(v3.transformed_position.y).ceil() as i32;
 
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let p_x = x as f32 + 0.5; //sample at pixel center
-            let p_y = y as f32 + 0.5;
+    // Recortar el bounding box al tile
+    let (min_x, max_x) = (min_x.max(tile.x0), max_x.min(tile.x1.saturating_sub(1)));
+    let (min_y, max_y) = (min_y.max(tile.y0), max_y.min(tile.y1.saturating_sub(1)));
+    if min_x > max_x || min_y > max_y {
+        return;
+    }
 
-            // Calculate barycentric coordinates
-            let (w1, w2, w3) = barycentric_coordinates(p_x, p_y, v1, v2, v3);
+    let points = [v1, v2, v3].map(|v| to_fixed(v.transformed_position));
+    let area = edge_function(points[0], points[1], points[2]);
+    if area == 0 {
+        return;
+    }
+    // Con área negativa se recorren los vértices al revés para que el interior quede
+    // del lado positivo de los tres bordes; `order` recuerda a qué vértice va cada peso
+    let order = if area > 0 { [0, 1, 2] } else { [0, 2, 1] };
+    let [a, b, c] = order.map(|i| points[i]);
+    let area = area.abs() as f32;
 
-            if w1 >= 0.0 && w2 >= 0.0 && w3 >= 0.0 {
+    // Borde opuesto a cada vértice: su función es el peso (sin normalizar) de ese vértice
+    let edges = [(b, c), (c, a), (a, b)];
+    // Fuera de los bordes top-left, un centro justo sobre el borde (valor 0) no cuenta
+    let bias = edges.map(|(from, to)| if is_top_left(from, to) { 0 } else { -1 });
+    // Cuánto cambia cada función al avanzar un píxel en x o en y
+    let step_x = edges.map(|(from, to)| -(to.1 - from.1) * SUBPIXEL);
+    let step_y = edges.map(|(from, to)| (to.0 - from.0) * SUBPIXEL);
+    // Diferencia de cada función entre el centro del píxel y cada muestra
+    let sample_deltas = samples.map(|(dx, dy)| edges.map(|(from, to)| -(to.1 - from.1) * dx + (to.0 - from.0) * dy));
 
-                // Interpolate normals using barycentric coordinates
-                let interpolated_normal = Vector3::new(
-                    w1 * v1.transformed_normal.x + w2 * v2.transformed_normal.x + w3 * v3.transformed_normal.x,
-                    w1 * v1.transformed_normal.y + w2 * v2.transformed_normal.y + w3 * v3.transformed_normal.y,
-                    w1 * v1.transformed_normal.z + w2 * v2.transformed_normal.z + w3 * v3.transformed_normal.z,
-                );
+    // La profundidad es lineal en pantalla: con su pendiente se obtiene la de cada muestra
+    let depths = order.map(|i| [v1, v2, v3][i].transformed_position.z);
+    let slope = |steps: [i64; 3]| (0..3).map(|i| depths[i] * steps[i] as f32).sum::<f32>() / area;
+    let depth_slope = Vector2::new(slope(step_x), slope(step_y));
 
-                // Normalize the interpolated normal
-                let normal_length = (interpolated_normal.x * interpolated_normal.x + interpolated_normal.y * interpolated_normal.y + interpolated_normal.z * interpolated_normal.z).sqrt();
+    let origin = (min_x as i64 * SUBPIXEL + SUBPIXEL / 2, min_y as i64 * SUBPIXEL + SUBPIXEL / 2); //sample at pixel center
+    let mut row = edges.map(|(from, to)| edge_function(from, to, origin));
 
-                let mut normalized_normal = interpolated_normal;
-                if normal_length > 0.0 {
-                    normalized_normal.x /= normal_length;
-                    normalized_normal.y /= normal_length;
-                    normalized_normal.z /= normal_length;
+    for y in min_y..=max_y {
+        let mut values = row;
+        for x in min_x..=max_x {
+            let mut coverage = 0u8;
+            for (sample, deltas) in sample_deltas.iter().enumerate() {
+                if (0..3).all(|i| values[i] + deltas[i] + bias[i] >= 0) {
+                    coverage |= 1 << sample;
                 }
-                
-                // Calculate position in world space for this fragment
-                let world_pos = Vector3::new(
-                    w1 * v1.position.x + w2 * v2.position.x + w3 * v3.position.x,
-                    w1 * v1.position.y + w2 * v2.position.y + w3 * v3.position.y,
-                    w1 * v1.position.z + w2 * v2.position.z + w3 * v3.position.z,
-                );
-
-                // Light direction (from surface to light) for this fragment
-                let mut light_dir = Vector3::new(
-                    light.position.x - world_pos.x,
-                    light.position.y - world_pos.y,
-                    light.position.z - world_pos.z,
-                );
-
-                // Normalize light direction
-                let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
-                if light_length > 0.0 {
-                    light_dir.x /= light_length;
-                    light_dir.y /= light_length;
-                    light_dir.z /= light_length;
+            }
+            if coverage != 0 {
+                let p_x = x as f32 + 0.5;
+                let p_y = y as f32 + 0.5;
+
+                let mut weights = [0.0; 3];
+                for (&vertex, &value) in order.iter().zip(&values) {
+                    weights[vertex] = value as f32 / area;
                 }
+                let [w1, w2, w3] = weights;
+
+                // La profundidad y la velocidad son de pantalla: se interpolan con los pesos en
+                // pantalla; los atributos de la superficie con los pesos corregidos
+                let (p1, p2, p3) = perspective_weights(w1, w2, w3, v1, v2, v3);
+                let interpolate = |a: Vector3, b: Vector3, c: Vector3| a * p1 + b * p2 + c * p3;
 
-                // Calculate per-fragment lighting intensity using interpolated normal and light direction
-                let intensity = (normalized_normal.x * light_dir.x + normalized_normal.y * light_dir.y + normalized_normal.z * light_dir.z).max(0.0);
+                // Normal interpolada, normalizada de nuevo (la interpolación la acorta)
+                let normal = interpolate(v1.transformed_normal, v2.transformed_normal, v3.transformed_normal);
+                let normal = if normal.length() > 0.0 { normal.normalized() } else { normal };
 
-                let shaded_color = Vector3::new(
-                    base_color.x * intensity,
-                    base_color.y * intensity,
-                    base_color.z * intensity,
-                );
+                // Posición en el espacio del modelo, que es donde viven los patrones de los shaders
+                let world_pos = interpolate(v1.position, v2.position, v3.position);
 
                 // Interpolate depth using barycentric coordinates
                 let depth = w1 * v1.transformed_position.z + w2 * v2.transformed_position.z + w3 * v3.transformed_position.z;
 
-                fragments.push(Fragment::new(p_x, p_y, shaded_color, depth, world_pos));
+                let color = interpolate(v1.color, v2.color, v3.color);
+                let mut fragment = Fragment::new(p_x, p_y, color, depth, world_pos);
+                fragment.normal = normal;
+                fragment.light = interpolate(v1.light, v2.light, v3.light);
+                fragment.tex_coords = v1.tex_coords * p1 + v2.tex_coords * p2 + v3.tex_coords * p3;
+                fragment.barycentric = Vector3::new(w1, w2, w3);
+                // Velocidad en pantalla: cuánto se movió cada vértice desde el frame anterior
+                let motion = |v: &Vertex| v.transformed_position - v.previous_position;
+                let velocity = motion(v1) * w1 + motion(v2) * w2 + motion(v3) * w3;
+                fragment.velocity = Vector2::new(velocity.x, velocity.y);
+                fragment.coverage = coverage;
+                fragment.depth_slope = depth_slope;
+                emit(fragment);
+            }
+            values.iter_mut().zip(step_x).for_each(|(value, step)| *value += step);
+        }
+        row.iter_mut().zip(step_y).for_each(|(value, step)| *value += step);
+    }
+}
+
+/// Primitive assembly: groups transformed vertices into `triangles` (which is
+/// cleared first), dropping the ones that are degenerate or entirely off-screen
+/// and counting those that cross the screen edge (their off-screen fragments
+/// are discarded later)
+pub fn assemble_triangles(vertices: &[Vertex], width: i32, height: i32, depth_mode: DepthMode, stats: &mut RenderStats, triangles: &mut Vec<[Vertex; 3]>) {
+    triangles.clear();
+
+    for tri in vertices.chunks_exact(3) {
+        stats.triangles += 1;
+        match classify_triangle(tri, width, height, depth_mode) {
+            TriangleStatus::Degenerate | TriangleStatus::OffScreen | TriangleStatus::OutsideDepth => {
+                stats.triangles_culled += 1;
+                continue;
             }
+            TriangleStatus::Clipped => stats.triangles_clipped += 1,
+            TriangleStatus::Drawn => {}
         }
+
+        triangles.push([tri[0].clone(), tri[1].clone(), tri[2].clone()]);
+    }
+}
+
+/// What primitive assembly does with one triangle
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TriangleStatus {
+    Drawn,
+    Clipped,      // se dibuja, pero parte queda fuera de pantalla
+    Degenerate,   // área nula o NaN
+    OffScreen,
+    OutsideDepth, // algún vértice fuera de los planos cercano o lejano, o detrás de la cámara: se descarta entero
+}
+
+impl TriangleStatus {
+    pub fn culled(self) -> bool {
+        !matches!(self, TriangleStatus::Drawn | TriangleStatus::Clipped)
+    }
+}
+
+/// Sorts the screen-space triangle `tri` (three transformed vertices) the way
+/// `assemble_triangles` does on a `width` x `height` target
+pub fn classify_triangle(tri: &[Vertex], width: i32, height: i32, depth_mode: DepthMode) -> TriangleStatus {
+    let (a, b, c) = (&tri[0].transformed_position, &tri[1].transformed_position, &tri[2].transformed_position);
+
+    let area = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+    let min_x = a.x.min(b.x).min(c.x);
+    let max_x = a.x.max(b.x).max(c.x);
+    let min_y = a.y.min(b.y).min(c.y);
+    let max_y = a.y.max(b.y).max(c.y);
+
+    // Área nula o NaN por vértices detrás de la cámara
+    if area.is_nan() || area.abs() < 1e-10 {
+        return TriangleStatus::Degenerate;
+    }
+    if max_x < 0.0 || max_y < 0.0 || min_x >= width as f32 || min_y >= height as f32 {
+        return TriangleStatus::OffScreen;
+    }
+    // Profundidad fuera del rango del modo (o w negativo): el vértice está antes del plano
+    // cercano, más allá del lejano o detrás de la cámara, así que el triángulo se proyectaría
+    // mal. No se recorta contra esos planos: el triángulo entero se descarta. Con reverse-Z
+    // lo que queda detrás de la cámara cae en NDC negativos, de ahí también el chequeo de w
+    let range = depth_mode.ndc_range();
+    if tri.iter().any(|vertex| !range.contains(&vertex.transformed_position.z) || vertex.clip_w <= 0.0) {
+        return TriangleStatus::OutsideDepth;
     }
+    if min_x < 0.0 || min_y < 0.0 || max_x >= width as f32 || max_y >= height as f32 {
+        return TriangleStatus::Clipped;
+    }
+    TriangleStatus::Drawn
+}
+
+
+/// Line rasterizer (DDA): one fragment per pixel along the segment, with depth
+/// interpolated on screen and world position, normal, UV, color and light interpolated
+/// with perspective correction between the two endpoints
+pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
+    let start = a.transformed_position;
+    let end = b.transformed_position;
+    let steps = (end.x - start.x).abs().max((end.y - start.y).abs()).ceil().max(1.0) as usize;
 
+    let mut fragments = Vec::with_capacity(steps + 1);
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let x = (start.x + (end.x - start.x) * t).floor() + 0.5;
+        let y = (start.y + (end.y - start.y) * t).floor() + 0.5;
+        let depth = start.z + (end.z - start.z) * t;
+        let (_, along, _) = perspective_weights(1.0 - t, t, 0.0, a, b, b);
+        let world_pos = a.position.lerp(b.position, along);
+
+        let mut fragment = Fragment::new(x, y, a.color.lerp(b.color, along), depth, world_pos);
+        fragment.light = a.light.lerp(b.light, along);
+        let normal = a.transformed_normal.lerp(b.transformed_normal, along);
+        if normal.length() > 0.0 {
+            fragment.normal = normal.normalized();
+        }
+        fragment.tex_coords = a.tex_coords + (b.tex_coords - a.tex_coords) * along;
+        fragment.barycentric = Vector3::new(1.0 - t, t, 0.0);
+        fragments.push(fragment);
+    }
     fragments
-}
\ No newline at end of file
+}
+
+/// Rasterizes the part of one assembled triangle inside `tile` according to `mode`;
+/// with `msaa` filled triangles report the coverage of each MSAA sample
+pub fn rasterize(tri: &[Vertex; 3], mode: RenderMode, msaa: bool, tile: Tile, emit: &mut impl FnMut(Fragment)) {
+    match mode {
+        RenderMode::Solid if msaa => triangle_msaa(&tri[0], &tri[1], &tri[2], tile, emit),
+        RenderMode::Solid => triangle(&tri[0], &tri[1], &tri[2], tile, emit),
+        RenderMode::Wireframe => {
+            for (a, b) in [(&tri[0], &tri[1]), (&tri[1], &tri[2]), (&tri[2], &tri[0])] {
+                line(a, b)
+                    .into_iter()
+                    .filter(|fragment| tile.contains(fragment.position.x as i32, fragment.position.y as i32))
+                    .for_each(&mut *emit);
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// ui.rs
+// Overlay de modo inmediato: cada frame se vuelven a declarar los controles,
+// que leen el mouse, modifican el valor que reciben y se dibujan en el acto.
+use raylib::prelude::*;
+
+const PANEL_WIDTH: i32 = 300;
+const ROW_HEIGHT: i32 = 24;
+const LABEL_WIDTH: i32 = 120;
+const FONT_SIZE: i32 = 14;
+const PADDING: i32 = 8;
+
+const PANEL_COLOR: Color = Color::new(20, 10, 35, 200);
+const BAR_COLOR: Color = Color::new(60, 45, 90, 255);
+const FILL_COLOR: Color = Color::new(200, 120, 220, 255);
+const TEXT_COLOR: Color = Color::new(240, 230, 250, 255);
+
+pub struct Ui {
+    pub visible: bool,
+    x: i32,
+    y: i32,
+    cursor_y: i32,
+    next_id: usize,
+    active: Option<usize>, // control que se está arrastrando
+    panel_height: i32,     // alto del panel en el frame anterior
+}
+
+impl Ui {
+    pub fn new(x: i32, y: i32) -> Self {
+        Ui {
+            visible: true,
+            x,
+            y,
+            cursor_y: y,
+            next_id: 0,
+            active: None,
+            panel_height: 0,
+        }
+    }
+
+    /// Starts a new frame of controls and draws the panel background
+    pub fn begin(&mut self, d: &mut RaylibDrawHandle, title: &str) {
+        self.next_id = 0;
+        self.cursor_y = self.y + PADDING;
+        if !d.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.active = None;
+        }
+
+        d.draw_rectangle(self.x, self.y, PANEL_WIDTH, self.panel_height, PANEL_COLOR);
+        d.draw_text(title, self.x + PADDING, self.cursor_y, FONT_SIZE + 2, TEXT_COLOR);
+        self.cursor_y += ROW_HEIGHT;
+    }
+
+    pub fn end(&mut self) {
+        self.panel_height = self.cursor_y - self.y + PADDING;
+    }
+
+    /// Whether `point` is over the visible panel, so a click there belongs to the panel
+    pub fn contains(&self, point: Vector2) -> bool {
+        self.visible && contains(self.x, self.y, PANEL_WIDTH, self.panel_height, point)
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn label(&self, d: &mut RaylibDrawHandle, text: &str) {
+        d.draw_text(text, self.x + PADDING, self.cursor_y + 4, FONT_SIZE, TEXT_COLOR);
+    }
+
+    /// Horizontal slider; returns true when the value changed this frame
+    pub fn slider(&mut self, d: &mut RaylibDrawHandle, label: &str, value: &mut f32, min: f32, max: f32) -> bool {
+        let id = self.next_id();
+        let bar_x = self.x + LABEL_WIDTH;
+        let bar_width = PANEL_WIDTH - LABEL_WIDTH - PADDING;
+        let bar_height = ROW_HEIGHT - 6;
+        let mouse = d.get_mouse_position();
+
+        if d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)
+            && contains(bar_x, self.cursor_y, bar_width, bar_height, mouse)
+        {
+            self.active = Some(id);
+        }
+
+        let mut changed = false;
+        if self.active == Some(id) {
+            let t = ((mouse.x - bar_x as f32) / bar_width as f32).clamp(0.0, 1.0);
+            let new_value = min + t * (max - min);
+            if new_value != *value {
+                *value = new_value;
+                changed = true;
+            }
+        }
+
+        let t = ((*value - min) / (max - min)).clamp(0.0, 1.0);
+        self.label(d, label);
+        d.draw_rectangle(bar_x, self.cursor_y, bar_width, bar_height, BAR_COLOR);
+        d.draw_rectangle(bar_x, self.cursor_y, (bar_width as f32 * t) as i32, bar_height, FILL_COLOR);
+        d.draw_text(&format!("{:.2}", value), bar_x + 4, self.cursor_y + 3, FONT_SIZE, TEXT_COLOR);
+
+        self.cursor_y += ROW_HEIGHT;
+        changed
+    }
+
+    /// Checkbox; returns true when it was toggled this frame
+    pub fn checkbox(&mut self, d: &mut RaylibDrawHandle, label: &str, value: &mut bool) -> bool {
+        self.next_id();
+        let box_x = self.x + LABEL_WIDTH;
+        let box_size = ROW_HEIGHT - 6;
+        let mouse = d.get_mouse_position();
+
+        let toggled = d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)
+            && contains(box_x, self.cursor_y, box_size, box_size, mouse);
+        if toggled {
+            *value = !*value;
+        }
+
+        self.label(d, label);
+        d.draw_rectangle(box_x, self.cursor_y, box_size, box_size, BAR_COLOR);
+        if *value {
+            d.draw_rectangle(box_x + 4, self.cursor_y + 4, box_size - 8, box_size - 8, FILL_COLOR);
+        }
+
+        self.cursor_y += ROW_HEIGHT;
+        toggled
+    }
+
+    /// "< name >" selector over a list of options; returns true when the selection changed
+    pub fn selector(&mut self, d: &mut RaylibDrawHandle, label: &str, options: &[&str], index: &mut usize) -> bool {
+        self.next_id();
+        if options.is_empty() {
+            return false;
+        }
+        let button_size = ROW_HEIGHT - 6;
+        let prev_x = self.x + LABEL_WIDTH;
+        let next_x = self.x + PANEL_WIDTH - PADDING - button_size;
+        let mouse = d.get_mouse_position();
+        let clicked = d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+        let mut changed = false;
+        if clicked && contains(prev_x, self.cursor_y, button_size, button_size, mouse) {
+            *index = (*index + options.len() - 1) % options.len();
+            changed = true;
+        }
+        if clicked && contains(next_x, self.cursor_y, button_size, button_size, mouse) {
+            *index = (*index + 1) % options.len();
+            changed = true;
+        }
+
+        self.label(d, label);
+        d.draw_rectangle(prev_x, self.cursor_y, button_size, button_size, BAR_COLOR);
+        d.draw_text("<", prev_x + 6, self.cursor_y + 2, FONT_SIZE, TEXT_COLOR);
+        d.draw_text(options[*index], prev_x + button_size + 8, self.cursor_y + 4, FONT_SIZE, TEXT_COLOR);
+        d.draw_rectangle(next_x, self.cursor_y, button_size, button_size, BAR_COLOR);
+        d.draw_text(">", next_x + 6, self.cursor_y + 2, FONT_SIZE, TEXT_COLOR);
+
+        self.cursor_y += ROW_HEIGHT;
+        changed
+    }
+}
+
+fn contains(x: i32, y: i32, width: i32, height: i32, point: Vector2) -> bool {
+    point.x >= x as f32 && point.x < (x + width) as f32 && point.y >= y as f32 && point.y < (y + height) as f32
+}
@@ -0,0 +1,52 @@
+// uniforms.rs
+// Estado que reciben los shaders: lo global se arma una vez por frame y cada
+// cuerpo solo aporta su propio DrawUniforms
+use crate::math::*;
+use crate::debug::DebugView;
+use crate::light::Light;
+use crate::matrix::multiply_matrix_vector4;
+use crate::shaders::{Material, ShadingMode, VertexEffect};
+use crate::tonemap::ColorSpace;
+use crate::triangle::RenderMode;
+
+/// State shared by every draw in a frame: camera, time, light and view settings
+pub struct GlobalUniforms {
+    pub view_matrix: Matrix,
+    pub projection_matrix: Matrix,
+    pub viewport_matrix: Matrix,
+    pub time: f32, // elapsed time in seconds
+    pub previous_time: f32, // el del frame anterior: ahí se evalúan las deformaciones para los vectores de movimiento
+    pub camera_position: Vector3,
+    pub light: Light,
+    pub debug_view: DebugView,
+    pub render_mode: RenderMode,
+    pub shading: ShadingMode, // dónde se evalúa la iluminación: por triángulo, vértice o fragmento
+    pub previous_view_projection: Option<Matrix>, // vista * proyección del frame anterior, solo si se piden vectores de movimiento
+    pub color_space: ColorSpace, // espacio en que se iluminan y mezclan los colores
+}
+
+impl GlobalUniforms {
+    /// Screen position (x, y, depth) of a world point, or None behind the camera
+    pub fn project(&self, world: Vector3) -> Option<Vector3> {
+        let view = multiply_matrix_vector4(&self.view_matrix, &Vector4::new(world.x, world.y, world.z, 1.0));
+        let clip = multiply_matrix_vector4(&self.projection_matrix, &view);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = Vector4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0);
+        let screen = multiply_matrix_vector4(&self.viewport_matrix, &ndc);
+        Some(Vector3::new(screen.x, screen.y, screen.z))
+    }
+}
+
+/// State of a single draw call: where the body is and how it is shaded
+pub struct DrawUniforms<'a> {
+    pub model_matrix: Matrix,
+    pub instances: &'a [Matrix], // una matriz por copia, aplicada antes de model_matrix; vacío = una sola copia
+    pub previous_model_matrix: Matrix,    // dónde estaba el cuerpo en el frame anterior
+    pub previous_instances: &'a [Matrix], // ídem por copia; vacío = las mismas que `instances`
+    pub effect: VertexEffect, // deformación animada que aplica el vertex shader
+    pub bounds: (Vector3, f32), // esfera envolvente en el mundo (centro, radio), para el frustum culling
+    pub mesh_id: i32, // 0: planet, 1: rings, 2: moons, 3: sun, 4: comet, 5: asteroids (identifica el cuerpo, p. ej. en las vistas de depuración)
+    pub material: Material<'a>,
+}
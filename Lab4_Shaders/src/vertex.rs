@@ -1,7 +1,7 @@
 //vertex.rs
 #![allow(dead_code)]
 
-use raylib::math::{Vector2, Vector3};
+use crate::math::{Vector2, Vector3};
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -9,8 +9,11 @@ pub struct Vertex {
   pub normal: Vector3,
   pub tex_coords: Vector2,
   pub color: Vector3,
+  pub light: Vector3, // con Flat y Gouraud: (difusa, especular, sombra) calculadas antes de rasterizar
   pub transformed_position: Vector3,
   pub transformed_normal: Vector3,
+  pub previous_position: Vector3, // posición en pantalla en el frame anterior (vectores de movimiento)
+  pub clip_w: f32, // w antes de la división de perspectiva, para interpolar con corrección de perspectiva
 }
 
 impl Vertex {
@@ -20,8 +23,11 @@ impl Vertex {
       normal,
       tex_coords,
       color: Vector3::new(0.0, 0.0, 0.0), // Black
+      light: Vector3::new(0.0, 0.0, 0.0),
       transformed_position: position,
       transformed_normal: normal,
+      previous_position: position,
+      clip_w: 1.0,
     }
   }
 
@@ -31,14 +37,19 @@ impl Vertex {
       normal: Vector3::new(0.0, 0.0, 0.0),
       tex_coords: Vector2::new(0.0, 0.0),
       color,
+      light: Vector3::new(0.0, 0.0, 0.0),
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 0.0, 0.0),
+      previous_position: Vector3::new(0.0, 0.0, 0.0),
+      clip_w: 1.0,
     }
   }
 
   pub fn set_transformed(&mut self, position: Vector3, normal: Vector3) {
     self.transformed_position = position;
     self.transformed_normal = normal;
+    self.previous_position = position; // sin movimiento
+    self.clip_w = 1.0;                 // ya en pantalla: sin perspectiva que corregir
   }
 }
 
@@ -49,8 +60,11 @@ impl Default for Vertex {
       normal: Vector3::new(0.0, 1.0, 0.0),
       tex_coords: Vector2::new(0.0, 0.0),
       color: Vector3::new(0.0, 0.0, 0.0), // Black
+      light: Vector3::new(0.0, 0.0, 0.0),
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 1.0, 0.0),
+      previous_position: Vector3::new(0.0, 0.0, 0.0),
+      clip_w: 1.0,
     }
   }
 }
\ No newline at end of file
@@ -0,0 +1,201 @@
+// web.rs
+// Frontend web (wasm32-unknown-unknown): el mismo Renderer que la ventana, pero el
+// framebuffer se copia con putImageData a <canvas id="planetas"> en cada
+// requestAnimationFrame. Teclas: 1-9 planeta, flechas orbitar, +/- zoom, espacio pausa.
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{console, CanvasRenderingContext2d, HtmlCanvasElement, ImageData, KeyboardEvent};
+use crate::asteroids::AsteroidBelt;
+use crate::camera::Camera;
+use crate::comet::Comet;
+use crate::config::Config;
+use crate::display::Presenter;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::math::*;
+use crate::mesh::{LodMesh, SceneMeshes};
+use crate::planets::{PlanetRegistry, PlanetType};
+use crate::plugins;
+use crate::time::TimeController;
+use crate::tonemap::DisplayTransform;
+use crate::renderer::{FrameSettings, Renderer, Scene};
+
+const CANVAS_ID: &str = "planetas";
+const ORBIT_STEP: f32 = 0.1; // radianes por tecla
+const ZOOM_STEP: f32 = 0.25;
+
+// Callback de requestAnimationFrame (recibe el instante en milisegundos)
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+/// An HTML canvas as a presentation backend
+pub struct CanvasPresenter {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    rgba: Vec<u8>, // se reutiliza entre frames
+}
+
+impl CanvasPresenter {
+    pub fn new(canvas: HtmlCanvasElement) -> Result<Self, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or("the canvas has no 2d context")?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        Ok(CanvasPresenter { canvas, context, rgba: Vec::new() })
+    }
+}
+
+impl Presenter for CanvasPresenter {
+    fn size(&self) -> (i32, i32) {
+        (self.canvas.width() as i32, self.canvas.height() as i32)
+    }
+
+    fn present(&mut self, framebuffer: &mut Framebuffer, status: &[String]) -> io::Result<()> {
+        let js_error = |e: JsValue| io::Error::other(format!("{:?}", e));
+        self.rgba.clear();
+        for color in framebuffer.pixels() {
+            self.rgba.extend_from_slice(&[color.r, color.g, color.b, 255]);
+        }
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&self.rgba), framebuffer.width as u32, framebuffer.height as u32)
+            .map_err(js_error)?;
+        self.context.put_image_data(&image, 0.0, 0.0).map_err(js_error)?;
+
+        self.context.set_fill_style_str("white");
+        self.context.set_font("14px monospace");
+        for (i, line) in status.iter().enumerate() {
+            self.context.fill_text(line, 10.0, 20.0 + i as f64 * 18.0).map_err(js_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything the page keeps between animation frames
+struct App {
+    presenter: CanvasPresenter,
+    camera: Camera,
+    light: Light,
+    meshes: SceneMeshes,
+    planets: Vec<PlanetType>,
+    planet: usize,
+    renderer: Renderer,
+    comet: Comet,
+    belt: AsteroidBelt,
+    clock: TimeController,
+    last_timestamp: Option<f64>,
+}
+
+impl App {
+    fn new(canvas: HtmlCanvasElement) -> Result<Self, JsValue> {
+        let presenter = CanvasPresenter::new(canvas)?;
+        // Sin sistema de archivos: presets compilados y esferas generadas en vez del OBJ
+        let mut registry = PlanetRegistry::with_builtins();
+        plugins::register_plugins(&mut registry);
+        let config = Config::builtin();
+        let planets = config.planet_types(&registry)?;
+        let meshes = SceneMeshes::new(LodMesh::icospheres());
+        let (width, height) = presenter.size();
+        let renderer = Renderer::new(width, height, 1, DisplayTransform::default());
+
+        Ok(App {
+            presenter,
+            camera: Camera::new(Vector3::new(0.0, 0.0, 8.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0)),
+            light: config.light(),
+            meshes,
+            planets,
+            planet: 0,
+            renderer,
+            comet: Comet::new(),
+            belt: AsteroidBelt::new(7),
+            clock: TimeController::default(),
+            last_timestamp: None,
+        })
+    }
+
+    /// Handles a `keydown`, with `key` as in `KeyboardEvent.key`
+    fn key(&mut self, key: &str) {
+        match key {
+            "ArrowLeft" => self.camera.yaw -= ORBIT_STEP,
+            "ArrowRight" => self.camera.yaw += ORBIT_STEP,
+            "ArrowUp" => self.camera.pitch += ORBIT_STEP,
+            "ArrowDown" => self.camera.pitch -= ORBIT_STEP,
+            "+" => self.camera.distance = (self.camera.distance - ZOOM_STEP).max(0.8),
+            "-" => self.camera.distance += ZOOM_STEP,
+            " " => self.clock.toggle_pause(),
+            _ => {
+                if let Ok(number @ 1..=9) = key.parse::<usize>() && number <= self.planets.len() {
+                    self.planet = number - 1;
+                }
+            }
+        }
+        self.camera.set_orbit(self.camera.target, self.camera.distance);
+    }
+
+    /// Renders and presents one frame; `timestamp` is requestAnimationFrame's, in milliseconds
+    fn frame(&mut self, timestamp: f64) -> io::Result<()> {
+        let real_dt = self.last_timestamp.map_or(0.0, |last| ((timestamp - last) / 1000.0) as f32);
+        self.last_timestamp = Some(timestamp);
+
+        // El canvas puede cambiar de tamaño desde la página (atributos width y height)
+        let (width, height) = self.presenter.size();
+        self.renderer.resize(width, height);
+
+        let dt = self.clock.advance(real_dt);
+        let time = self.clock.time;
+        let light = self.light.at(time);
+        self.comet.update(time, dt.abs(), &light);
+        self.belt.update(time);
+
+        let frame = FrameSettings { time, dt, ..FrameSettings::default() };
+        let scene = Scene { meshes: &self.meshes, planet: &self.planets[self.planet], comet: &self.comet, belt: &self.belt, fade_from: None };
+        self.renderer.render(&self.camera, &light, &scene, &frame);
+
+        let fps = if real_dt > 0.0 { 1.0 / real_dt } else { 0.0 };
+        let status = format!("{} | {:.0} fps | {}", self.planets[self.planet].name, fps, self.clock.label());
+        self.presenter.present(&mut self.renderer.framebuffer, &[status])
+    }
+}
+
+/// Entry point of the web build: finds the canvas, listens to the keyboard and
+/// renders on every animation frame; errors go to the browser console
+pub fn start() {
+    if let Err(e) = run() {
+        console::error_1(&e);
+    }
+}
+
+fn run() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("no global window")?;
+    let canvas = window
+        .document()
+        .ok_or("no document")?
+        .get_element_by_id(CANVAS_ID)
+        .ok_or("the page has no <canvas id=\"planetas\">")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    let app = Rc::new(RefCell::new(App::new(canvas)?));
+
+    let keys = {
+        let app = Rc::clone(&app);
+        Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| app.borrow_mut().key(&event.key()))
+    };
+    window.add_event_listener_with_callback("keydown", keys.as_ref().unchecked_ref())?;
+    keys.forget(); // vive lo que dure la página
+
+    // Cada callback renderiza un frame y se vuelve a pedir a sí mismo
+    let callback: Rc<RefCell<Option<FrameCallback>>> = Rc::new(RefCell::new(None));
+    let next = Rc::clone(&callback);
+    *callback.borrow_mut() = Some(Closure::new(move |timestamp: f64| {
+        if let Err(e) = app.borrow_mut().frame(timestamp) {
+            console::error_1(&e.to_string().into());
+            return;
+        }
+        if let (Some(window), Some(next)) = (web_sys::window(), next.borrow().as_ref()) {
+            let _ = window.request_animation_frame(next.as_ref().unchecked_ref());
+        }
+    }));
+    if let Some(first) = callback.borrow().as_ref() {
+        window.request_animation_frame(first.as_ref().unchecked_ref())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// tests/allocations.rs
+// Benchmark de asignaciones: cuenta cuántas veces se pide memoria al renderizar
+// un frame con un FrameArena nuevo contra uno reutilizado. Va en su propio
+// binario de tests porque instala un asignador global que cuenta:
+//
+//     cargo test --test allocations
+use ship::arena::FrameArena;
+use ship::asteroids::AsteroidBelt;
+use ship::camera::Camera;
+use ship::comet::Comet;
+use ship::framebuffer::Framebuffer;
+use ship::light::Light;
+use ship::math::Vector3;
+use ship::mesh::{generate_icosphere, generate_nucleus, generate_ring, LodMesh, SceneMeshes};
+use ship::planets::PlanetRegistry;
+use ship::{render_scene, FrameSettings, Scene};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// System allocator that counts allocations per thread, so tests running in
+/// parallel don't see each other's
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn reused_arena_allocates_less_per_frame() {
+    let camera = Camera::new(Vector3::new(0.0, 0.0, 5.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let light = Light::new(Vector3::new(5.0, 3.0, 5.0));
+    let meshes = SceneMeshes {
+        sphere: LodMesh::single(generate_icosphere(2)),
+        ring: generate_ring(0.8, 1.5, 48),
+        nucleus: generate_nucleus(1, 0.35),
+        asteroid: generate_nucleus(1, 0.45),
+    };
+    let planet = PlanetRegistry::with_builtins().get(3).unwrap().clone(); // glitter, con anillos
+    let comet = Comet::new();
+    let belt = AsteroidBelt::new(7);
+    let scene = Scene { meshes: &meshes, planet: &planet, comet: &comet, belt: &belt, fade_from: None };
+    let mut framebuffer = Framebuffer::new(96, 64);
+
+    let mut frame = |time: f32, arena: &mut FrameArena| {
+        let settings = FrameSettings { time, dt: 0.1, ..FrameSettings::default() };
+        allocations_during(|| {
+            render_scene(&mut framebuffer, &camera, &light, &scene, &settings, arena);
+        })
+    };
+
+    let fresh = frame(0.1, &mut FrameArena::default());
+    let mut arena = FrameArena::default();
+    frame(0.1, &mut arena);
+    let reused = frame(0.2, &mut arena);
+
+    assert!(reused * 2 < fresh, "fresh {} vs reused {}", fresh, reused);
+}